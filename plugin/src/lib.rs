@@ -18,8 +18,8 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
 use std::time::Duration;
 use typespec_api::{
-    codegen::{Generator, Language, Side},
-    parse, TypeSpecFile,
+    codegen::{CodegenError, Generator, Language, Side},
+    Program,
 };
 
 /// Plugin-specific CLI service ID
@@ -376,38 +376,10 @@ fn do_generate(
     side: Side,
     package: &str,
 ) -> Result<String, String> {
-    // Parse all input files with import resolution
-    let mut combined = TypeSpecFile::default();
-    let mut resolved = HashSet::new();
-
-    for input in input_files {
-        let canonical = input
-            .canonicalize()
-            .map_err(|e| format!("Failed to resolve path {}: {}", input.display(), e))?;
-
-        // Skip if already processed
-        if resolved.contains(&canonical) {
-            continue;
-        }
-        resolved.insert(canonical.clone());
-
-        let source = std::fs::read_to_string(&canonical)
-            .map_err(|e| format!("Failed to read {}: {}", input.display(), e))?;
-
-        let file =
-            parse(&source).map_err(|e| format!("Failed to parse {}: {}", input.display(), e))?;
-
-        // Resolve imports relative to the input file's directory
-        let base_dir = canonical.parent().unwrap_or(Path::new("."));
-        let resolved_file = resolve_imports(file, base_dir, &mut resolved)?;
-
-        // Merge declarations
-        combined.usings.extend(resolved_file.usings);
-        combined.declarations.extend(resolved_file.declarations);
-
-        if resolved_file.namespace.is_some() {
-            combined.namespace = resolved_file.namespace;
-        }
+    // Load all input files with import resolution
+    let program = Program::load(input_files).map_err(|e| e.to_string())?;
+    if program.has_errors() {
+        return Err(program.render_diagnostics());
     }
 
     // Generate code
@@ -418,12 +390,22 @@ fn do_generate(
         Language::OpenApi => "openapi",
     });
 
-    let generator = Generator::new(&combined, &output_subdir, package);
-    let generated = generator
-        .generate(language, side)
-        .map_err(|e| format!("Code generation failed: {}", e))?;
-
-    let mut output = format!("Generated {} files:", generated.len());
+    let generator = Generator::from_program(&program, &output_subdir, package);
+    let generated = generator.generate(language, side).map_err(|e| match e {
+        CodegenError::Diagnostics(diagnostics) => diagnostics
+            .iter()
+            .map(|d| program.render(d))
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        e => format!("Code generation failed: {}", e),
+    })?;
+
+    let mut output = String::new();
+    for warning in program.diagnostics() {
+        output.push_str(&program.render(warning));
+        output.push_str("\n\n");
+    }
+    output.push_str(&format!("Generated {} files:", generated.len()));
     for path in &generated {
         output.push_str(&format!("\n  {}", path));
     }
@@ -503,63 +485,3 @@ fn parse_side(s: &str) -> Result<Side, String> {
         _ => Err(format!("Unknown side: {}. Use: client, server, or both", s)),
     }
 }
-
-/// Recursively resolve imports from a TypeSpec file
-fn resolve_imports(
-    file: TypeSpecFile,
-    base_path: &Path,
-    resolved: &mut HashSet<PathBuf>,
-) -> Result<TypeSpecFile, String> {
-    let mut combined = TypeSpecFile {
-        imports: Vec::new(),
-        usings: file.usings,
-        namespace: file.namespace,
-        declarations: file.declarations,
-    };
-
-    // Process each import
-    for import in file.imports {
-        // Skip TypeSpec standard library imports
-        if import.path.starts_with("@typespec/") {
-            continue;
-        }
-
-        // Resolve the import path relative to the current file
-        let import_path = base_path.join(&import.path);
-
-        // Normalize path and add .tsp extension if missing
-        let import_path = if import_path.extension().is_none() {
-            import_path.with_extension("tsp")
-        } else {
-            import_path
-        };
-
-        // Canonicalize to handle .. and .
-        let import_path = import_path.canonicalize().unwrap_or(import_path);
-
-        // Skip if already resolved (prevents circular imports)
-        if resolved.contains(&import_path) {
-            continue;
-        }
-        resolved.insert(import_path.clone());
-
-        // Read and parse the imported file
-        if import_path.exists() {
-            let source = std::fs::read_to_string(&import_path)
-                .map_err(|e| format!("Failed to read import {}: {}", import_path.display(), e))?;
-
-            let imported = parse(&source)
-                .map_err(|e| format!("Failed to parse import {}: {}", import_path.display(), e))?;
-
-            // Recursively resolve imports from the imported file
-            let import_dir = import_path.parent().unwrap_or(Path::new("."));
-            let resolved_import = resolve_imports(imported, import_dir, resolved)?;
-
-            // Merge declarations from the imported file
-            combined.usings.extend(resolved_import.usings);
-            combined.declarations.extend(resolved_import.declarations);
-        }
-    }
-
-    Ok(combined)
-}
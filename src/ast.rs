@@ -3,6 +3,38 @@
 //! Abstract syntax tree for TypeSpec definitions.
 
 use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+
+/// Byte range of a node in its source file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Span covering both `self` and `other`.
+    pub fn to(self, other: Span) -> Span {
+        Span {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+}
+
+impl From<Range<usize>> for Span {
+    fn from(range: Range<usize>) -> Self {
+        Self {
+            start: range.start,
+            end: range.end,
+        }
+    }
+}
 
 /// Root of a TypeSpec file.
 #[derive(Debug, Clone, Default)]
@@ -14,9 +46,17 @@ pub struct TypeSpecFile {
 }
 
 impl TypeSpecFile {
+    /// Iterate all declarations depth-first, including those nested in
+    /// namespace blocks (the namespaces themselves are yielded too).
+    pub fn all_declarations(&self) -> Declarations<'_> {
+        Declarations {
+            stack: vec![self.declarations.iter()],
+        }
+    }
+
     /// Get all models.
     pub fn models(&self) -> impl Iterator<Item = &Model> {
-        self.declarations.iter().filter_map(|d| match d {
+        self.all_declarations().filter_map(|d| match d {
             Declaration::Model(m) => Some(m),
             _ => None,
         })
@@ -24,37 +64,115 @@ impl TypeSpecFile {
 
     /// Get all enums.
     pub fn enums(&self) -> impl Iterator<Item = &Enum> {
-        self.declarations.iter().filter_map(|d| match d {
+        self.all_declarations().filter_map(|d| match d {
             Declaration::Enum(e) => Some(e),
             _ => None,
         })
     }
 
+    /// Get all named unions.
+    pub fn unions(&self) -> impl Iterator<Item = &Union> {
+        self.all_declarations().filter_map(|d| match d {
+            Declaration::Union(u) => Some(u),
+            _ => None,
+        })
+    }
+
     /// Get all interfaces (services).
     pub fn interfaces(&self) -> impl Iterator<Item = &Interface> {
-        self.declarations.iter().filter_map(|d| match d {
+        self.all_declarations().filter_map(|d| match d {
             Declaration::Interface(i) => Some(i),
             _ => None,
         })
     }
 
+    /// Get all free operations (declared with `op` outside an interface).
+    pub fn operations(&self) -> impl Iterator<Item = &Operation> {
+        self.all_declarations().filter_map(|d| match d {
+            Declaration::Operation(o) => Some(o),
+            _ => None,
+        })
+    }
+
     /// Get all scalars.
     pub fn scalars(&self) -> impl Iterator<Item = &Scalar> {
-        self.declarations.iter().filter_map(|d| match d {
+        self.all_declarations().filter_map(|d| match d {
             Declaration::Scalar(s) => Some(s),
             _ => None,
         })
     }
+
+    /// Get all aliases.
+    pub fn aliases(&self) -> impl Iterator<Item = &Alias> {
+        self.all_declarations().filter_map(|d| match d {
+            Declaration::Alias(a) => Some(a),
+            _ => None,
+        })
+    }
+
+    /// Move the declarations and `using`s of a `namespace X;` file into a
+    /// `namespace X { }` block, so files with different namespaces can be
+    /// merged without losing track of where each declaration lives.
+    pub fn into_scoped(self) -> TypeSpecFile {
+        let Some(name) = self.namespace else {
+            return self;
+        };
+        let span = self
+            .declarations
+            .iter()
+            .map(Declaration::span)
+            .reduce(Span::to)
+            .unwrap_or_default();
+        TypeSpecFile {
+            imports: self.imports,
+            usings: Vec::new(),
+            namespace: None,
+            declarations: vec![Declaration::Namespace(Namespace {
+                name,
+                decorators: Vec::new(),
+                usings: self.usings,
+                declarations: self.declarations,
+                span,
+            })],
+        }
+    }
+}
+
+/// Depth-first iterator over declarations, see [`TypeSpecFile::all_declarations`].
+pub struct Declarations<'a> {
+    stack: Vec<std::slice::Iter<'a, Declaration>>,
+}
+
+impl<'a> Iterator for Declarations<'a> {
+    type Item = &'a Declaration;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.stack.last_mut()?.next() {
+                Some(decl) => {
+                    if let Declaration::Namespace(ns) = decl {
+                        self.stack.push(ns.declarations.iter());
+                    }
+                    return Some(decl);
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Import {
     pub path: String,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone)]
 pub struct Using {
     pub namespace: String,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone)]
@@ -63,16 +181,35 @@ pub enum Declaration {
     Enum(Enum),
     Union(Union),
     Interface(Interface),
+    Operation(Operation),
     Scalar(Scalar),
     Alias(Alias),
     Namespace(Namespace),
 }
 
+impl Declaration {
+    /// Source span of the declaration.
+    pub fn span(&self) -> Span {
+        match self {
+            Declaration::Model(m) => m.span,
+            Declaration::Enum(e) => e.span,
+            Declaration::Union(u) => u.span,
+            Declaration::Interface(i) => i.span,
+            Declaration::Operation(o) => o.span,
+            Declaration::Scalar(s) => s.span,
+            Declaration::Alias(a) => a.span,
+            Declaration::Namespace(n) => n.span,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Namespace {
     pub name: String,
     pub decorators: Vec<Decorator>,
+    pub usings: Vec<Using>,
     pub declarations: Vec<Declaration>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone)]
@@ -81,17 +218,21 @@ pub struct Model {
     pub decorators: Vec<Decorator>,
     pub type_params: Vec<String>,
     pub extends: Option<TypeRef>,
+    /// Model whose properties are copied: `model TaskPage is Page<Task>`
+    pub is: Option<TypeRef>,
     pub properties: Vec<Property>,
     pub spread_refs: Vec<TypeRef>,
+    pub span: Span,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Property {
     pub name: String,
     pub decorators: Vec<Decorator>,
     pub type_ref: TypeRef,
     pub optional: bool,
     pub default: Option<Value>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone)]
@@ -99,6 +240,7 @@ pub struct Enum {
     pub name: String,
     pub decorators: Vec<Decorator>,
     pub members: Vec<EnumMember>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone)]
@@ -106,6 +248,7 @@ pub struct EnumMember {
     pub name: String,
     pub decorators: Vec<Decorator>,
     pub value: Option<Value>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone)]
@@ -113,12 +256,14 @@ pub struct Union {
     pub name: String,
     pub decorators: Vec<Decorator>,
     pub variants: Vec<UnionVariant>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone)]
 pub struct UnionVariant {
     pub name: Option<String>,
     pub type_ref: TypeRef,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone)]
@@ -126,14 +271,27 @@ pub struct Interface {
     pub name: String,
     pub decorators: Vec<Decorator>,
     pub operations: Vec<Operation>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone)]
 pub struct Operation {
     pub name: String,
     pub decorators: Vec<Decorator>,
+    /// Template parameters: `op ReadResource<T>(...)`
+    pub type_params: Vec<String>,
+    /// Operation this one reuses: `op getUser is ReadResource<User>`
+    pub is: Option<TypeRef>,
     pub params: Vec<OperationParam>,
     pub return_type: Option<TypeRef>,
+    pub span: Span,
+}
+
+impl Operation {
+    /// Whether this operation is a template that is only used through `is`.
+    pub fn is_template(&self) -> bool {
+        !self.type_params.is_empty()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -142,7 +300,10 @@ pub struct OperationParam {
     pub decorators: Vec<Decorator>,
     pub type_ref: TypeRef,
     pub optional: bool,
+    /// Default value: `@query page?: int32 = 1`
+    pub default: Option<Value>,
     pub spread: bool,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone)]
@@ -150,18 +311,21 @@ pub struct Scalar {
     pub name: String,
     pub decorators: Vec<Decorator>,
     pub extends: Option<String>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone)]
 pub struct Alias {
     pub name: String,
     pub type_ref: TypeRef,
+    pub span: Span,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Decorator {
     pub name: String,
     pub args: Vec<DecoratorArg>,
+    pub span: Span,
 }
 
 impl Decorator {
@@ -173,13 +337,13 @@ impl Decorator {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum DecoratorArg {
     Value(Value),
     Named { name: String, value: Value },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     String(String),
     Int(i64),
@@ -192,7 +356,7 @@ pub enum Value {
     Object(HashMap<String, Value>),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum TypeRef {
     /// Built-in type: string, int32, boolean, etc.
     Builtin(String),
@@ -246,3 +410,34 @@ impl TypeRef {
         }
     }
 }
+
+impl fmt::Display for TypeRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn join(f: &mut fmt::Formatter<'_>, types: &[TypeRef], sep: &str) -> fmt::Result {
+            for (i, t) in types.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(sep)?;
+                }
+                write!(f, "{}", t)?;
+            }
+            Ok(())
+        }
+
+        match self {
+            TypeRef::Builtin(name) | TypeRef::Named(name) => f.write_str(name),
+            TypeRef::Qualified(parts) => f.write_str(&parts.join(".")),
+            TypeRef::Array(inner) => write!(f, "{}[]", inner),
+            TypeRef::Generic { base, args } => {
+                write!(f, "{}<", base)?;
+                join(f, args, ", ")?;
+                f.write_str(">")
+            }
+            TypeRef::Union(variants) => join(f, variants, " | "),
+            TypeRef::Intersection(parts) => join(f, parts, " & "),
+            TypeRef::Optional(inner) => write!(f, "{}?", inner),
+            TypeRef::StringLiteral(s) => write!(f, "\"{}\"", s),
+            TypeRef::IntLiteral(n) => write!(f, "{}", n),
+            TypeRef::AnonymousModel(_) => f.write_str("{ ... }"),
+        }
+    }
+}
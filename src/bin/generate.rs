@@ -1,18 +1,18 @@
 //! TypeSpec Code Generator CLI
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Result};
 use chrono::Local;
 use clap::Parser;
 use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
 use std::collections::HashSet;
 use std::io::{self, Write};
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
 use std::time::Duration;
 use typespec_api::{
-    codegen::{Generator, Language, Side},
-    parse, TypeSpecFile,
+    codegen::{CodegenError, Generator, Language, PythonModels, Side},
+    Program,
 };
 
 /// Global flag for watch mode termination
@@ -38,6 +38,10 @@ struct Cli {
     #[arg(short, long, value_enum, default_value = "both")]
     side: Side,
 
+    /// Style of generated Python models
+    #[arg(long, value_enum, default_value = "dataclass")]
+    python_models: PythonModels,
+
     /// Package name for generated code
     #[arg(short, long, default_value = "api")]
     package: String,
@@ -47,106 +51,15 @@ struct Cli {
     watch: bool,
 }
 
-/// Recursively resolve imports from a TypeSpec file
-fn resolve_imports(
-    file: TypeSpecFile,
-    base_path: &Path,
-    resolved: &mut HashSet<PathBuf>,
-) -> Result<TypeSpecFile> {
-    let mut combined = TypeSpecFile {
-        imports: Vec::new(), // Don't carry forward imports
-        usings: file.usings,
-        namespace: file.namespace,
-        declarations: file.declarations,
-    };
-
-    // Process each import
-    for import in file.imports {
-        // Skip TypeSpec standard library imports
-        if import.path.starts_with("@typespec/") {
-            continue;
-        }
-
-        // Resolve the import path relative to the current file
-        let import_path = if import.path.starts_with("./") || import.path.starts_with("../") {
-            base_path.join(&import.path)
-        } else {
-            base_path.join(&import.path)
-        };
-
-        // Normalize path and add .tsp extension if missing
-        let import_path = if import_path.extension().is_none() {
-            import_path.with_extension("tsp")
-        } else {
-            import_path
-        };
-
-        // Canonicalize to handle .. and .
-        let import_path = import_path.canonicalize().unwrap_or(import_path);
-
-        // Skip if already resolved (prevents circular imports)
-        if resolved.contains(&import_path) {
-            continue;
-        }
-        resolved.insert(import_path.clone());
-
-        // Read and parse the imported file
-        if import_path.exists() {
-            let source = std::fs::read_to_string(&import_path)
-                .with_context(|| format!("Failed to read import {}", import_path.display()))?;
-
-            let imported = parse(&source)
-                .with_context(|| format!("Failed to parse import {}", import_path.display()))?;
-
-            // Recursively resolve imports from the imported file
-            let import_dir = import_path.parent().unwrap_or(Path::new("."));
-            let resolved_import = resolve_imports(imported, import_dir, resolved)?;
-
-            // Merge declarations from the imported file
-            combined.usings.extend(resolved_import.usings);
-            combined.declarations.extend(resolved_import.declarations);
-
-            // Don't override namespace from imports
-        }
-    }
-
-    Ok(combined)
-}
-
 /// Perform a single generation run
 fn do_generate(cli: &Cli) -> Result<Vec<String>> {
-    // Parse all input files with import resolution
-    let mut combined = TypeSpecFile::default();
-    let mut resolved = HashSet::new();
-
-    for input in &cli.input {
-        let canonical = input
-            .canonicalize()
-            .with_context(|| format!("Failed to resolve path {}", input.display()))?;
-
-        // Skip if already processed
-        if resolved.contains(&canonical) {
-            continue;
-        }
-        resolved.insert(canonical.clone());
-
-        let source = std::fs::read_to_string(&canonical)
-            .with_context(|| format!("Failed to read {}", input.display()))?;
-
-        let file =
-            parse(&source).with_context(|| format!("Failed to parse {}", input.display()))?;
-
-        // Resolve imports relative to the input file's directory
-        let base_dir = canonical.parent().unwrap_or(Path::new("."));
-        let resolved_file = resolve_imports(file, base_dir, &mut resolved)?;
-
-        // Merge declarations
-        combined.usings.extend(resolved_file.usings);
-        combined.declarations.extend(resolved_file.declarations);
-
-        if resolved_file.namespace.is_some() {
-            combined.namespace = resolved_file.namespace;
-        }
+    // Load all input files with import resolution
+    let program = Program::load(&cli.input)?;
+    if program.has_errors() {
+        return Err(anyhow!("{}", program.render_diagnostics()));
+    }
+    for warning in program.diagnostics() {
+        eprintln!("{}\n", program.render(warning));
     }
 
     // Generate code
@@ -157,8 +70,17 @@ fn do_generate(cli: &Cli) -> Result<Vec<String>> {
         Language::OpenApi => "openapi",
     });
 
-    let generator = Generator::new(&combined, &output_dir, &cli.package);
-    let generated = generator.generate(cli.language, cli.side)?;
+    let generator = Generator::from_program(&program, &output_dir, &cli.package)
+        .with_python_models(cli.python_models);
+    let generated = generator
+        .generate(cli.language, cli.side)
+        .map_err(|e| match e {
+            CodegenError::Diagnostics(diagnostics) => {
+                let rendered: Vec<_> = diagnostics.iter().map(|d| program.render(d)).collect();
+                anyhow!("{}", rendered.join("\n\n"))
+            }
+            e => e.into(),
+        })?;
 
     Ok(generated)
 }
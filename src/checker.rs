@@ -0,0 +1,274 @@
+//! Semantic Checker
+//!
+//! Checks a parsed file for errors the parser cannot see: unknown or
+//! ambiguous types, duplicate declarations and members, wrong template
+//! argument counts, operations reusing themselves through `is`, incompatible
+//! property overrides, generic models that never stop instantiating, routes
+//! that do not match their operation's path parameters and operation
+//! parameters that cannot be sent.
+
+use crate::ast::{Declaration, Span, TypeRef, TypeSpecFile};
+use crate::diagnostic::Diagnostic;
+use crate::semantic::{build_model_map, collect_services, http, inheritance, templates};
+use crate::symbols::SymbolTable;
+use std::collections::HashSet;
+
+/// Check a file, returning every error found.
+pub fn check(file: &TypeSpecFile) -> Vec<Diagnostic> {
+    let table = SymbolTable::build(file);
+    let mut diagnostics = Vec::new();
+
+    check_duplicates(&table, &mut diagnostics);
+    diagnostics.extend(table.check_references());
+    check_template_arguments(&table, &mut diagnostics);
+    diagnostics.extend(templates::check_operation_cycles(&table));
+
+    let models = build_model_map(file);
+    for model in file.models() {
+        diagnostics.extend(inheritance::check_overrides(model, &models));
+    }
+    diagnostics.extend(templates::check_instantiations(file, &models));
+
+    for iface in &collect_services(file) {
+        for operation in http::http_operations(iface, &models) {
+            diagnostics.extend(http::check_params(&operation));
+        }
+    }
+
+    diagnostics
+}
+
+fn check_duplicates(table: &SymbolTable<'_>, diagnostics: &mut Vec<Diagnostic>) {
+    let mut declared = HashSet::new();
+    for symbol in table.symbols() {
+        if !declared.insert(symbol.qualified_name.as_str()) {
+            diagnostics.push(Diagnostic::error(
+                format!("duplicate declaration `{}`", symbol.qualified_name),
+                symbol.declaration.span(),
+            ));
+        }
+
+        match symbol.declaration {
+            Declaration::Model(model) => report_duplicates(
+                model.properties.iter().map(|p| (p.name.as_str(), p.span)),
+                "property",
+                &model.name,
+                diagnostics,
+            ),
+            Declaration::Enum(e) => report_duplicates(
+                e.members.iter().map(|m| (m.name.as_str(), m.span)),
+                "member",
+                &e.name,
+                diagnostics,
+            ),
+            Declaration::Interface(iface) => {
+                report_duplicates(
+                    iface.operations.iter().map(|o| (o.name.as_str(), o.span)),
+                    "operation",
+                    &iface.name,
+                    diagnostics,
+                );
+                for op in &iface.operations {
+                    report_duplicates(
+                        op.params.iter().map(|p| (p.name.as_str(), p.span)),
+                        "parameter",
+                        &op.name,
+                        diagnostics,
+                    );
+                }
+            }
+            Declaration::Operation(op) => report_duplicates(
+                op.params.iter().map(|p| (p.name.as_str(), p.span)),
+                "parameter",
+                &op.name,
+                diagnostics,
+            ),
+            _ => {}
+        }
+    }
+}
+
+fn report_duplicates<'a>(
+    members: impl Iterator<Item = (&'a str, Span)>,
+    what: &str,
+    owner: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let mut seen = HashSet::new();
+    for (name, span) in members {
+        if !seen.insert(name) {
+            diagnostics.push(Diagnostic::error(
+                format!("duplicate {} `{}` in `{}`", what, name, owner),
+                span,
+            ));
+        }
+    }
+}
+
+fn check_template_arguments(table: &SymbolTable<'_>, diagnostics: &mut Vec<Diagnostic>) {
+    table.visit_references(|reference| {
+        let Some(Ok(symbol)) = table.resolve(reference.type_ref, reference.scope) else {
+            return;
+        };
+        let expected = match symbol.declaration {
+            Declaration::Model(model) => model.type_params.len(),
+            Declaration::Operation(op) => op.type_params.len(),
+            _ => 0,
+        };
+        let given = match reference.type_ref {
+            TypeRef::Generic { args, .. } => args.len(),
+            _ => 0,
+        };
+        if expected == given {
+            return;
+        }
+        diagnostics.push(Diagnostic::error(
+            format!(
+                "`{}` expects {} type argument{}, got {}",
+                symbol.name(),
+                expected,
+                if expected == 1 { "" } else { "s" },
+                given
+            ),
+            reference.span,
+        ));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    fn messages(source: &str) -> Vec<String> {
+        check(&parse(source).unwrap())
+            .into_iter()
+            .map(|d| d.message)
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_file_has_no_errors() {
+        let source = r#"
+            enum TaskStatus { pending, done }
+            model Page<T> { items: T[]; }
+            model Task { status: TaskStatus; tags: Record<string>; }
+            model TaskPage is Page<Task>;
+        "#;
+        assert!(messages(source).is_empty());
+    }
+
+    #[test]
+    fn test_reports_duplicates() {
+        let source = r#"
+            model Task { id: string; id: int32; }
+            model Task {}
+            enum Status { a, a }
+        "#;
+        assert_eq!(
+            messages(source),
+            vec![
+                "duplicate property `id` in `Task`",
+                "duplicate declaration `Task`",
+                "duplicate member `a` in `Status`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reports_wrong_template_argument_count() {
+        let source = r#"
+            model Page<T> { items: T[]; }
+            model Task {}
+            model Lists {
+                a: Page<Task, Task>;
+                b: Page;
+                c: Task<string>;
+            }
+        "#;
+        assert_eq!(
+            messages(source),
+            vec![
+                "`Page` expects 1 type argument, got 2",
+                "`Page` expects 1 type argument, got 0",
+                "`Task` expects 0 type arguments, got 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reports_operations_reusing_themselves() {
+        let source = r#"
+            op Read<T>(@query id: string): T;
+            op getTask is Read<string>;
+            op a is b;
+            op b is a;
+        "#;
+        assert_eq!(
+            messages(source),
+            vec![
+                "operation `a` reuses itself through `is`",
+                "operation `b` reuses itself through `is`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reports_body_params_that_cannot_be_sent() {
+        let source = r#"
+            model Task { id: string; }
+
+            @route("/tasks")
+            interface Tasks {
+                @get @route("/{id}") get(id: string, verbose: boolean): Task;
+                @post create(title: string, done?: boolean): Task;
+                @put @route("/{id}") replace(id: string, @body body: Task, force: boolean): Task;
+            }
+        "#;
+        assert_eq!(
+            messages(source),
+            vec![
+                "parameter `verbose` needs @path, @query or @header: `get` is a GET operation without a body",
+                "parameter `force` needs @path, @query or @header: `replace` already has a @body",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reports_path_params_missing_from_the_route() {
+        let source = r#"
+            model Task { id: string; }
+
+            @route("/tasks")
+            interface Tasks {
+                @get read(@path id: string): Task;
+                @get @route("/{id}") get(@path id: string): Task;
+            }
+        "#;
+        assert_eq!(
+            messages(source),
+            vec!["@path parameter `id` of `read` is not in its route `/tasks`"]
+        );
+    }
+
+    #[test]
+    fn test_reports_route_params_without_a_parameter() {
+        let source = r#"
+            model Task { id: string; }
+
+            @route("/tasks")
+            interface Tasks {
+                @get @route("/{taskId}") get(@path id: string): Task;
+                @get @route("/{id}/runs/{runId}") run(id: string, @query runId: string): Task;
+            }
+        "#;
+        assert_eq!(
+            messages(source),
+            vec![
+                "@path parameter `id` of `get` is not in its route `/tasks/{taskId}`",
+                "route `/tasks/{taskId}` of `get` has no path parameter `taskId`",
+                "route `/tasks/{id}/runs/{runId}` of `run` has no path parameter `runId`",
+            ]
+        );
+    }
+}
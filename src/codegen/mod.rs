@@ -6,61 +6,100 @@ pub mod openapi;
 pub mod python;
 pub mod rust;
 pub mod typescript;
-
-use crate::ast::{Model, Property, TypeRef, TypeSpecFile};
+pub mod validation;
+
+use crate::ast::{Decorator, TypeSpecFile};
+use crate::diagnostic::Diagnostic;
+use crate::program::Program;
+use crate::semantic::templates::check_instance_names;
+use crate::semantic::{self, names};
+use crate::symbols::{Symbol, SymbolTable};
 use std::collections::HashMap;
 use std::path::Path;
 use thiserror::Error;
 
-/// Map of scalar name -> base type it extends
-pub type ScalarMap = HashMap<String, String>;
-
-/// Map of model name -> Model
-pub type ModelMap<'a> = HashMap<&'a str, &'a Model>;
-
-/// Build a map of custom scalars from parsed TypeSpec file
-pub fn build_scalar_map(file: &TypeSpecFile) -> ScalarMap {
-    file.scalars()
-        .filter_map(|s| {
-            s.extends
-                .as_ref()
-                .map(|base| (s.name.clone(), base.clone()))
-        })
-        .collect()
-}
+pub use crate::semantic::{
+    build_model_map, build_scalar_map, resolve_properties, ModelMap, ScalarMap,
+};
+
+/// Check that declarations get distinct generated names. Types and
+/// interfaces are generated without their namespace; ones sharing a name
+/// are prefixed with the namespaces telling them apart (see
+/// [`names::renamed`]), which may still clash with another declaration.
+/// Services, from interfaces and namespaces alike, and instances of generic
+/// models, named after their type arguments, must not clash either.
+pub fn check_generated_names(file: &TypeSpecFile) -> Vec<Diagnostic> {
+    let table = SymbolTable::build(file);
+    let renamed = names::renamed(&table);
+    let mut generated: HashMap<&str, &Symbol<'_>> = HashMap::new();
+    let mut diagnostics = Vec::new();
+    for symbol in table.symbols().filter(|s| names::is_generated(s.kind)) {
+        let name = renamed
+            .get(&symbol.qualified_name)
+            .map_or(symbol.name(), String::as_str);
+        match generated.get(name) {
+            // The same qualified name twice is a duplicate declaration
+            Some(first) if first.qualified_name != symbol.qualified_name => {
+                diagnostics.push(Diagnostic::error(
+                    format!(
+                        "`{}` and `{}` would both be generated as `{}`",
+                        first.qualified_name, symbol.qualified_name, name
+                    ),
+                    symbol.declaration.span(),
+                ));
+            }
+            Some(_) => {}
+            None => {
+                generated.insert(name, symbol);
+            }
+        }
+    }
 
-/// Build a map of model names to models for spread resolution
-pub fn build_model_map(file: &TypeSpecFile) -> ModelMap<'_> {
-    file.models().map(|m| (m.name.as_str(), m)).collect()
+    let file = names::disambiguate(file);
+    diagnostics.extend(check_service_names(&file));
+    diagnostics.extend(check_instance_names(&file, &build_model_map(&file)));
+    diagnostics
 }
 
-/// Resolve spread references and return all properties including spread ones
-pub fn resolve_properties<'a>(model: &'a Model, models: &'a ModelMap<'a>) -> Vec<&'a Property> {
-    let mut properties = Vec::new();
-
-    // First add properties from spread references
-    for spread_ref in &model.spread_refs {
-        if let Some(name) = get_type_name(spread_ref) {
-            if let Some(spread_model) = models.get(name.as_str()) {
-                // Recursively resolve spread model's properties
-                properties.extend(resolve_properties(spread_model, models));
+/// Services left sharing a name once prefixed, such as free operations and
+/// an interface `Default` at the root
+fn check_service_names(file: &TypeSpecFile) -> Vec<Diagnostic> {
+    let describe = |qualified: &str| match qualified {
+        "" => "the free operations".to_string(),
+        qualified => format!("`{}`", qualified),
+    };
+    let services = semantic::qualified_services(file);
+    let mut generated: HashMap<&str, &str> = HashMap::new();
+    let mut diagnostics = Vec::new();
+    for (qualified, service) in &services {
+        match generated.get(service.name.as_str()) {
+            // The same qualified name twice is a duplicate declaration
+            Some(first) if first != qualified => {
+                diagnostics.push(Diagnostic::error(
+                    format!(
+                        "{} and {} would both be generated as the `{}` service",
+                        describe(first),
+                        describe(qualified),
+                        service.name
+                    ),
+                    service.span,
+                ));
+            }
+            Some(_) => {}
+            None => {
+                generated.insert(&service.name, qualified);
             }
         }
     }
-
-    // Then add this model's own properties (they override spread ones)
-    properties.extend(model.properties.iter());
-
-    properties
+    diagnostics
 }
 
-/// Get the type name from a TypeRef
-fn get_type_name(type_ref: &TypeRef) -> Option<String> {
-    match type_ref {
-        TypeRef::Named(name) => Some(name.clone()),
-        TypeRef::Qualified(parts) => parts.last().cloned(),
-        _ => None,
-    }
+/// The description of a declaration, from its `@doc` or doc comment
+pub fn description(decorators: &[Decorator]) -> Option<&str> {
+    decorators
+        .iter()
+        .find(|d| d.name == "doc")
+        .and_then(|d| d.get_string_arg(0))
 }
 
 #[derive(Debug, Error)]
@@ -73,6 +112,10 @@ pub enum CodegenError {
 
     #[error("Generation error: {0}")]
     Generation(String),
+
+    /// Errors in the TypeSpec source, pointing at where they are
+    #[error("{}", .0.iter().map(|d| d.message.as_str()).collect::<Vec<_>>().join("\n"))]
+    Diagnostics(Vec<Diagnostic>),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
@@ -94,10 +137,23 @@ pub enum Side {
     Both,
 }
 
+/// How Python models are generated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum PythonModels {
+    /// `@dataclass`es with generated `to_dict`/`from_dict`
+    #[default]
+    Dataclass,
+    /// Pydantic v2 `BaseModel`s, validated and converted on construction
+    Pydantic,
+}
+
 pub struct Generator<'a> {
     file: &'a TypeSpecFile,
     output_dir: &'a Path,
     package_name: &'a str,
+    python_models: PythonModels,
+    /// The program the file was loaded by, which checked it already
+    program: Option<&'a Program>,
 }
 
 impl<'a> Generator<'a> {
@@ -106,24 +162,56 @@ impl<'a> Generator<'a> {
             file,
             output_dir,
             package_name,
+            python_models: PythonModels::default(),
+            program: None,
+        }
+    }
+
+    /// Generate the merged file of a loaded program, reusing the program's
+    /// diagnostics instead of checking the file again
+    pub fn from_program(program: &'a Program, output_dir: &'a Path, package_name: &'a str) -> Self {
+        Self {
+            program: Some(program),
+            ..Self::new(program.file(), output_dir, package_name)
         }
     }
 
+    /// Set how Python models are generated
+    pub fn with_python_models(mut self, python_models: PythonModels) -> Self {
+        self.python_models = python_models;
+        self
+    }
+
     pub fn generate(&self, language: Language, side: Side) -> Result<Vec<String>, CodegenError> {
+        let checked = match self.program {
+            Some(program) => program.diagnostics().to_vec(),
+            None => crate::checker::check(self.file),
+        };
+        let errors: Vec<_> = checked
+            .into_iter()
+            .chain(check_generated_names(self.file))
+            .filter(|d| d.is_error())
+            .collect();
+        if !errors.is_empty() {
+            return Err(CodegenError::Diagnostics(errors));
+        }
+
+        let file = &*names::disambiguate(self.file);
         let mut generated = Vec::new();
 
         match language {
             Language::Python => {
                 generated.extend(python::generate(
-                    self.file,
+                    file,
                     self.output_dir,
                     self.package_name,
                     side,
+                    self.python_models,
                 )?);
             }
             Language::TypeScript => {
                 generated.extend(typescript::generate(
-                    self.file,
+                    file,
                     self.output_dir,
                     self.package_name,
                     side,
@@ -131,7 +219,7 @@ impl<'a> Generator<'a> {
             }
             Language::Rust => {
                 generated.extend(rust::generate(
-                    self.file,
+                    file,
                     self.output_dir,
                     self.package_name,
                     side,
@@ -139,11 +227,7 @@ impl<'a> Generator<'a> {
             }
             Language::OpenApi => {
                 // OpenAPI ignores side parameter - it generates the full spec
-                generated.extend(openapi::generate(
-                    self.file,
-                    self.output_dir,
-                    self.package_name,
-                )?);
+                generated.extend(openapi::generate(file, self.output_dir, self.package_name)?);
             }
         }
 
@@ -3,8 +3,17 @@
 //! Generates OpenAPI 3.0 specification from TypeSpec AST.
 
 use crate::ast::*;
-use crate::codegen::{
-    build_model_map, build_scalar_map, resolve_properties, CodegenError, ModelMap, ScalarMap,
+use crate::codegen::validation::{
+    build_constraint_map, number_literal, ConstraintMap, Constraints,
+};
+use crate::codegen::{description, CodegenError};
+use crate::semantic::http::{
+    header_name, http_operations, is_metadata, HttpOperation, ParamLocation, Response,
+};
+use crate::semantic::templates::{instance_name, instantiate_models};
+use crate::semantic::{
+    build_model_map, build_scalar_map, collect_services, own_properties, resolve_properties,
+    ModelMap, ScalarMap,
 };
 use convert_case::{Case, Casing};
 use serde_json::{json, Map, Value};
@@ -19,10 +28,11 @@ pub fn generate(
     let mut generated = Vec::new();
     let scalars = build_scalar_map(file);
     let models = build_model_map(file);
+    let constraints = build_constraint_map(file);
 
     fs::create_dir_all(output_dir)?;
 
-    let spec = generate_openapi_spec(file, &scalars, &models, title)?;
+    let spec = generate_openapi_spec(file, &scalars, &constraints, &models, title)?;
 
     // Write JSON
     let json_path = output_dir.join("openapi.json");
@@ -40,9 +50,11 @@ pub fn generate(
     Ok(generated)
 }
 
-fn generate_openapi_spec(
+/// Build the OpenAPI document of a file
+pub fn generate_openapi_spec(
     file: &TypeSpecFile,
     scalars: &ScalarMap,
+    constraints: &ConstraintMap,
     models: &ModelMap<'_>,
     title: &str,
 ) -> Result<Value, CodegenError> {
@@ -66,12 +78,17 @@ fn generate_openapi_spec(
         "security": [{ "bearerAuth": [] }]
     });
 
-    // Generate schemas for models
+    // Generate schemas for models. OpenAPI has no generics, so templates are
+    // replaced by a concrete schema per instantiation.
     let schemas = spec["components"]["schemas"].as_object_mut().unwrap();
-    for model in file.models() {
-        let schema = model_to_schema(model, scalars, models);
+    for model in file.models().filter(|m| m.type_params.is_empty()) {
+        let schema = model_to_schema(model, scalars, constraints, models);
         schemas.insert(model.name.clone(), schema);
     }
+    for instance in instantiate_models(file, models) {
+        let schema = model_to_schema(&instance, scalars, constraints, models);
+        schemas.insert(instance.name.clone(), schema);
+    }
 
     // Generate schemas for enums
     for enum_def in file.enums() {
@@ -81,38 +98,50 @@ fn generate_openapi_spec(
 
     // Generate paths from interfaces
     let paths = spec["paths"].as_object_mut().unwrap();
-    for iface in file.interfaces() {
-        let base_path = get_route(&iface.decorators).unwrap_or_default();
-
-        for op in &iface.operations {
-            let op_path = get_route(&op.decorators).unwrap_or_default();
-            let full_path = format!("{}{}", base_path, op_path);
-            let method = get_http_method(&op.decorators).to_lowercase();
-
-            let operation = operation_to_openapi(op, &iface.name, scalars);
+    for iface in &collect_services(file) {
+        for http_op in http_operations(iface, models) {
+            let operation = operation_to_openapi(file, &http_op, &iface.name, scalars, constraints);
 
             // Get or create path item
             let path_item = paths
-                .entry(full_path)
+                .entry(http_op.path)
                 .or_insert_with(|| json!({}))
                 .as_object_mut()
                 .unwrap();
 
-            path_item.insert(method, operation);
+            path_item.insert(http_op.method.to_lowercase(), operation);
         }
     }
 
     Ok(spec)
 }
 
-fn model_to_schema(model: &Model, scalars: &ScalarMap, models: &ModelMap<'_>) -> Value {
-    let all_properties = resolve_properties(model, models);
+fn model_to_schema(
+    model: &Model,
+    scalars: &ScalarMap,
+    constraints: &ConstraintMap,
+    models: &ModelMap<'_>,
+) -> Value {
+    // Derived models reference their base with `allOf`
+    let all_properties = if model.extends.is_some() {
+        own_properties(model, models)
+    } else {
+        resolve_properties(model, models)
+    };
 
     let mut properties = Map::new();
     let mut required = Vec::new();
 
-    for prop in all_properties {
-        let schema = type_to_schema(&prop.type_ref, scalars);
+    // `@statusCode` is sent as the response status, not in the body
+    for prop in all_properties.iter().filter(|p| !is_metadata(p)) {
+        let mut schema = type_to_schema(&prop.type_ref, scalars);
+        apply_constraints(
+            &mut schema,
+            &Constraints::of(&prop.decorators, &prop.type_ref, constraints),
+        );
+        if let Some(desc) = description(&prop.decorators) {
+            schema["description"] = Value::from(desc);
+        }
         properties.insert(prop.name.clone(), schema);
 
         if !prop.optional {
@@ -129,33 +158,79 @@ fn model_to_schema(model: &Model, scalars: &ScalarMap, models: &ModelMap<'_>) ->
         schema["required"] = Value::Array(required);
     }
 
-    if let Some(desc) = get_description(&model.decorators) {
-        schema["description"] = Value::String(desc);
+    if let Some(base) = &model.extends {
+        schema = json!({
+            "allOf": [type_to_schema(base, scalars), schema]
+        });
+    }
+
+    if let Some(desc) = description(&model.decorators) {
+        schema["description"] = Value::from(desc);
     }
 
     schema
 }
 
-fn enum_to_schema(enum_def: &Enum) -> Value {
-    let values: Vec<Value> = enum_def
-        .members
-        .iter()
-        .map(|m| {
-            m.value
-                .as_ref()
-                .map(|v| match v {
-                    crate::ast::Value::String(s) => Value::String(s.clone()),
-                    crate::ast::Value::Int(n) => Value::Number((*n).into()),
-                    _ => Value::String(m.name.to_case(Case::Snake)),
-                })
-                .unwrap_or_else(|| Value::String(m.name.to_case(Case::Snake)))
+/// The value an enum member is serialized as
+fn member_value(member: &EnumMember) -> Value {
+    member
+        .value
+        .as_ref()
+        .map(|v| match v {
+            crate::ast::Value::String(s) => Value::String(s.clone()),
+            crate::ast::Value::Int(n) => Value::Number((*n).into()),
+            _ => Value::String(member.name.to_case(Case::Snake)),
         })
-        .collect();
+        .unwrap_or_else(|| Value::String(member.name.to_case(Case::Snake)))
+}
 
-    json!({
+/// JSON of a default value. Enum members, e.g. `Status.active`, are their
+/// serialized values.
+fn value_to_json(value: &crate::ast::Value, file: &TypeSpecFile) -> Value {
+    match value {
+        crate::ast::Value::String(s) => Value::String(s.clone()),
+        crate::ast::Value::Int(n) => Value::Number((*n).into()),
+        crate::ast::Value::Float(n) => serde_json::Number::from_f64(*n)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        crate::ast::Value::Bool(b) => Value::Bool(*b),
+        crate::ast::Value::Ident(name) => Value::String(name.clone()),
+        crate::ast::Value::QualifiedIdent(parts) => {
+            let [.., enum_name, member_name] = parts.as_slice() else {
+                return Value::String(parts.join("."));
+            };
+            file.enums()
+                .filter(|e| &e.name == enum_name)
+                .flat_map(|e| &e.members)
+                .find(|m| &m.name == member_name)
+                .map(member_value)
+                .unwrap_or_else(|| Value::String(member_name.to_case(Case::Snake)))
+        }
+        crate::ast::Value::Array(items) => {
+            Value::Array(items.iter().map(|v| value_to_json(v, file)).collect())
+        }
+        crate::ast::Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(k, v)| (k.clone(), value_to_json(v, file)))
+                .collect(),
+        ),
+    }
+}
+
+fn enum_to_schema(enum_def: &Enum) -> Value {
+    let values: Vec<Value> = enum_def.members.iter().map(member_value).collect();
+
+    let mut schema = json!({
         "type": "string",
         "enum": values
-    })
+    });
+
+    if let Some(desc) = description(&enum_def.decorators) {
+        schema["description"] = Value::from(desc);
+    }
+
+    schema
 }
 
 fn type_to_schema(type_ref: &TypeRef, scalars: &ScalarMap) -> Value {
@@ -190,8 +265,8 @@ fn type_to_schema(type_ref: &TypeRef, scalars: &ScalarMap) -> Value {
                     });
                 }
             }
-            // For other generics, just reference the base
-            type_to_schema(base, scalars)
+            // Other generics reference their instantiated schema
+            json!({ "$ref": format!("#/components/schemas/{}", instance_name(type_ref)) })
         }
         TypeRef::Optional(inner) => {
             let mut schema = type_to_schema(inner, scalars);
@@ -266,6 +341,40 @@ fn type_to_schema(type_ref: &TypeRef, scalars: &ScalarMap) -> Value {
     }
 }
 
+/// Add validation keywords (`minLength`, `pattern`, `format`...) to a schema
+fn apply_constraints(schema: &mut Value, constraints: &Constraints) {
+    let Some(obj) = schema.as_object_mut() else {
+        return;
+    };
+    let counts = [
+        ("minLength", constraints.min_length),
+        ("maxLength", constraints.max_length),
+        ("minItems", constraints.min_items),
+        ("maxItems", constraints.max_items),
+    ];
+    for (keyword, count) in counts {
+        if let Some(n) = count {
+            obj.insert(keyword.to_string(), Value::from(n));
+        }
+    }
+    let bounds = [
+        ("minimum", constraints.min_value),
+        ("maximum", constraints.max_value),
+    ];
+    for (keyword, bound) in bounds {
+        if let Some(n) = bound {
+            let number = serde_json::from_str(&number_literal(n)).unwrap_or(Value::Null);
+            obj.insert(keyword.to_string(), number);
+        }
+    }
+    if let Some(pattern) = &constraints.pattern {
+        obj.insert("pattern".to_string(), Value::String(pattern.clone()));
+    }
+    if let Some(format) = &constraints.format {
+        obj.insert("format".to_string(), Value::String(format.clone()));
+    }
+}
+
 fn builtin_to_schema(name: &str) -> Value {
     match name {
         "string" => json!({ "type": "string" }),
@@ -288,57 +397,159 @@ fn builtin_to_schema(name: &str) -> Value {
     }
 }
 
-fn operation_to_openapi(op: &Operation, interface_name: &str, scalars: &ScalarMap) -> Value {
+/// Header objects of the `@header` properties of a response, by header name
+fn response_headers(headers: &[Property], scalars: &ScalarMap) -> Value {
+    let mut map = Map::new();
+    for header in headers {
+        let mut object = json!({
+            "required": !header.optional,
+            "schema": type_to_schema(&header.type_ref, scalars)
+        });
+        if let Some(desc) = description(&header.decorators) {
+            object["description"] = Value::from(desc);
+        }
+        map.insert(header_name(&header.decorators, &header.name), object);
+    }
+    Value::Object(map)
+}
+
+/// The `@header`s of responses sent under the same status. A header only
+/// some of them send is optional.
+fn merged_headers(responses: &[&Response<'_>]) -> Vec<Property> {
+    let mut headers: Vec<Property> = Vec::new();
+    for header in responses.iter().flat_map(|r| &r.headers) {
+        if headers.iter().any(|h| h.name == header.name) {
+            continue;
+        }
+        let everywhere = responses
+            .iter()
+            .all(|r| r.headers.iter().any(|h| h.name == header.name));
+        headers.push(Property {
+            optional: header.optional || !everywhere,
+            ..header.clone()
+        });
+    }
+    headers
+}
+
+fn operation_to_openapi(
+    file: &TypeSpecFile,
+    http_op: &HttpOperation<'_>,
+    interface_name: &str,
+    scalars: &ScalarMap,
+    constraints: &ConstraintMap,
+) -> Value {
+    let op = http_op.op;
     let mut operation = json!({
         "operationId": format!("{}_{}", interface_name, op.name).to_case(Case::Camel),
         "tags": [interface_name],
         "responses": {}
     });
 
-    // Add description
-    if let Some(desc) = get_description(&op.decorators) {
-        operation["summary"] = Value::String(desc);
+    // The first line of the description is the summary
+    if let Some(desc) = description(&op.decorators) {
+        let summary = desc.lines().next().unwrap_or_default();
+        operation["summary"] = Value::String(summary.to_string());
+        if summary != desc {
+            operation["description"] = Value::from(desc);
+        }
     }
 
     // Process parameters
     let mut parameters = Vec::new();
     let mut request_body: Option<Value> = None;
 
-    for param in &op.params {
-        if param.spread && param.name.is_empty() {
-            continue;
-        }
+    // Params without a location are the properties of an implicit body
+    let mut body_properties = Map::new();
+    let mut body_required = Vec::new();
 
-        if has_decorator(&param.decorators, "path") {
-            parameters.push(json!({
+    for param in &http_op.params {
+        let schema = type_to_schema(&param.type_ref, scalars);
+        let mut parameter = match param.location {
+            ParamLocation::Path => json!({
                 "name": param.name,
                 "in": "path",
                 "required": true,
-                "schema": type_to_schema(&param.type_ref, scalars)
-            }));
-        } else if has_decorator(&param.decorators, "query") {
-            parameters.push(json!({
+                "schema": schema
+            }),
+            ParamLocation::Query => json!({
                 "name": param.name,
                 "in": "query",
                 "required": !param.optional,
-                "schema": type_to_schema(&param.type_ref, scalars)
-            }));
-        } else if has_decorator(&param.decorators, "body") {
-            request_body = Some(json!({
-                "required": true,
-                "content": {
-                    "application/json": {
-                        "schema": type_to_schema(&param.type_ref, scalars)
+                "schema": schema
+            }),
+            ParamLocation::Header => json!({
+                "name": header_name(&param.decorators, &param.name),
+                "in": "header",
+                "required": !param.optional,
+                "schema": schema
+            }),
+            ParamLocation::Body => {
+                request_body = Some(json!({
+                    "required": true,
+                    "content": {
+                        "application/json": {
+                            "schema": schema
+                        }
                     }
+                }));
+                continue;
+            }
+            ParamLocation::BodyProperty => {
+                let mut schema = schema;
+                apply_constraints(
+                    &mut schema,
+                    &Constraints::of(&param.decorators, &param.type_ref, constraints),
+                );
+                if let Some(default) = &param.default {
+                    schema["default"] = value_to_json(default, file);
+                }
+                if let Some(desc) = description(&param.decorators) {
+                    schema["description"] = Value::from(desc);
+                }
+                if !param.optional {
+                    body_required.push(Value::String(param.name.clone()));
                 }
-            }));
+                body_properties.insert(param.name.clone(), schema);
+                continue;
+            }
+        };
+
+        apply_constraints(
+            &mut parameter["schema"],
+            &Constraints::of(&param.decorators, &param.type_ref, constraints),
+        );
+        if let Some(default) = &param.default {
+            parameter["schema"]["default"] = value_to_json(default, file);
+        }
+        if let Some(desc) = description(&param.decorators) {
+            parameter["description"] = Value::from(desc);
         }
+        parameters.push(parameter);
     }
 
     if !parameters.is_empty() {
         operation["parameters"] = Value::Array(parameters);
     }
 
+    if request_body.is_none() && !body_properties.is_empty() {
+        let mut schema = json!({
+            "type": "object",
+            "properties": body_properties
+        });
+        if !body_required.is_empty() {
+            schema["required"] = Value::Array(body_required);
+        }
+        request_body = Some(json!({
+            "required": true,
+            "content": {
+                "application/json": {
+                    "schema": schema
+                }
+            }
+        }));
+    }
+
     if let Some(body) = request_body {
         operation["requestBody"] = body;
     }
@@ -346,140 +557,104 @@ fn operation_to_openapi(op: &Operation, interface_name: &str, scalars: &ScalarMa
     // Process response type
     let responses = operation["responses"].as_object_mut().unwrap();
 
-    if let Some(ret) = &op.return_type {
-        let (status_code, body_schema) = extract_response_info(ret, scalars);
-
-        if let Some(schema) = body_schema {
-            responses.insert(
-                status_code.clone(),
-                json!({
-                    "description": "Successful response",
-                    "content": {
-                        "application/json": {
-                            "schema": schema
-                        }
+    // Each success response under its own status
+    for success in &http_op.responses {
+        let status_code = success.status.unwrap_or(200).to_string();
+        let mut response = if let Some(body) = &success.body {
+            json!({
+                "description": "Successful response",
+                "content": {
+                    "application/json": {
+                        "schema": type_to_schema(body, scalars)
                     }
-                }),
-            );
+                }
+            })
         } else {
-            responses.insert(
-                status_code,
-                json!({
-                    "description": "Successful response (no content)"
-                }),
-            );
-        }
-    } else {
-        responses.insert(
-            "200".to_string(),
             json!({
-                "description": "Successful response"
-            }),
-        );
+                "description": "Successful response (no content)"
+            })
+        };
+        if !success.headers.is_empty() {
+            response["headers"] = response_headers(&success.headers, scalars);
+        }
+        responses.insert(status_code, response);
     }
 
-    // Add error response
-    responses.insert(
-        "default".to_string(),
-        json!({
-            "description": "Error response",
+    // `@error` models are sent with their status, or as the default response
+    // when their `@statusCode` is picked at runtime. Models sharing a status
+    // are one response with either body.
+    let errors = &http_op.errors;
+    let mut by_status: Vec<(String, Vec<&Response<'_>>)> = Vec::new();
+    for error in errors
+        .iter()
+        .filter(|e| e.error.is_some() && e.body.is_some())
+    {
+        let status_code = error
+            .status
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| "default".to_string());
+        match by_status.iter_mut().find(|(s, _)| *s == status_code) {
+            Some((_, same)) => same.push(error),
+            None => by_status.push((status_code, vec![error])),
+        }
+    }
+    for (status_code, same) in by_status {
+        if responses.contains_key(&status_code) {
+            continue;
+        }
+        let mut schemas: Vec<_> = same
+            .iter()
+            .filter_map(|e| e.body.as_ref())
+            .map(|body| type_to_schema(body, scalars))
+            .collect();
+        let schema = match schemas.len() {
+            1 => schemas.remove(0),
+            _ => json!({ "oneOf": schemas }),
+        };
+        let description = match same.as_slice() {
+            [error] => error
+                .error
+                .and_then(|model| description(&model.decorators))
+                .unwrap_or("Error response"),
+            _ => "Error response",
+        };
+        let mut response = json!({
+            "description": description,
             "content": {
                 "application/json": {
-                    "schema": {
-                        "type": "object",
-                        "properties": {
-                            "code": { "type": "string" },
-                            "message": { "type": "string" }
-                        }
-                    }
+                    "schema": schema
                 }
             }
-        }),
-    );
-
-    operation
-}
+        });
+        let headers = merged_headers(&same);
+        if !headers.is_empty() {
+            response["headers"] = response_headers(&headers, scalars);
+        }
+        responses.insert(status_code, response);
+    }
 
-fn extract_response_info(type_ref: &TypeRef, scalars: &ScalarMap) -> (String, Option<Value>) {
-    match type_ref {
-        TypeRef::Union(variants) => {
-            for variant in variants {
-                if let TypeRef::AnonymousModel(props) = variant {
-                    let mut status_code = "200".to_string();
-                    let mut body_schema = None;
-
-                    for prop in props {
-                        if has_decorator(&prop.decorators, "statusCode") {
-                            if let TypeRef::IntLiteral(code) = &prop.type_ref {
-                                status_code = code.to_string();
+    // Without `@error` models, errors have the generated servers' shape
+    if errors.is_empty() {
+        responses.insert(
+            "default".to_string(),
+            json!({
+                "description": "Error response",
+                "content": {
+                    "application/json": {
+                        "schema": {
+                            "type": "object",
+                            "properties": {
+                                "code": { "type": "string" },
+                                "message": { "type": "string" }
                             }
                         }
-                        if has_decorator(&prop.decorators, "body") {
-                            body_schema = Some(type_to_schema(&prop.type_ref, scalars));
-                        }
-                    }
-
-                    if status_code == "204" {
-                        return (status_code, None);
-                    }
-                    if body_schema.is_some() {
-                        return (status_code, body_schema);
-                    }
-                }
-            }
-            ("200".to_string(), None)
-        }
-        TypeRef::AnonymousModel(props) => {
-            let mut status_code = "200".to_string();
-            let mut body_schema = None;
-
-            for prop in props {
-                if has_decorator(&prop.decorators, "statusCode") {
-                    if let TypeRef::IntLiteral(code) = &prop.type_ref {
-                        status_code = code.to_string();
                     }
                 }
-                if has_decorator(&prop.decorators, "body") {
-                    body_schema = Some(type_to_schema(&prop.type_ref, scalars));
-                }
-            }
-
-            (status_code, body_schema)
-        }
-        _ => ("200".to_string(), Some(type_to_schema(type_ref, scalars))),
-    }
-}
-
-fn get_description(decorators: &[Decorator]) -> Option<String> {
-    decorators
-        .iter()
-        .find(|d| d.name == "doc")
-        .and_then(|d| d.get_string_arg(0).map(|s| s.to_string()))
-}
-
-fn get_route(decorators: &[Decorator]) -> Option<String> {
-    decorators
-        .iter()
-        .find(|d| d.name == "route")
-        .and_then(|d| d.get_string_arg(0).map(|s| s.to_string()))
-}
-
-fn get_http_method(decorators: &[Decorator]) -> &'static str {
-    for d in decorators {
-        match d.name.as_str() {
-            "get" => return "GET",
-            "post" => return "POST",
-            "put" => return "PUT",
-            "patch" => return "PATCH",
-            "delete" => return "DELETE",
-            _ => {}
-        }
+            }),
+        );
     }
-    "GET"
-}
 
-fn has_decorator(decorators: &[Decorator], name: &str) -> bool {
-    decorators.iter().any(|d| d.name == name)
+    operation
 }
 
 /// Simple JSON to YAML conversion (no external dependency)
@@ -498,7 +673,9 @@ fn json_to_yaml_impl(value: &Value, out: &mut String, indent: usize) {
         Value::Number(n) => out.push_str(&n.to_string()),
         Value::String(s) => {
             if s.contains('\n') || s.contains(':') || s.contains('#') || s.is_empty() {
-                out.push_str(&format!("\"{}\"", s.replace('\"', "\\\"")));
+                // A JSON string is a valid double-quoted YAML scalar, with
+                // newlines, quotes and backslashes escaped
+                out.push_str(&Value::String(s.clone()).to_string());
             } else {
                 out.push_str(s);
             }
@@ -1,10 +1,23 @@
 //! Python Code Generator
 
 use crate::ast::*;
-use crate::codegen::{
-    build_model_map, build_scalar_map, resolve_properties, CodegenError, ModelMap, ScalarMap, Side,
+use crate::codegen::openapi::generate_openapi_spec;
+use crate::codegen::validation::{
+    build_constraint_map, nested_model, number_literal, validated_models, Check, ConstraintMap,
+    Constraints, NestedModel,
+};
+use crate::codegen::{description, CodegenError, PythonModels, Side};
+use crate::semantic::http::{
+    header_name, http_operations, is_error_model, is_metadata, is_status_code, path_params,
+    status_literal, status_name, status_property, HttpOperation, ParamLocation, Response,
+};
+use crate::semantic::{
+    build_model_map, build_scalar_map, collect_services,
+    inheritance::{lookup_model, models_in_inheritance_order},
+    own_properties, resolve_properties, ModelMap, ScalarMap,
 };
 use convert_case::{Case, Casing};
+use std::collections::HashSet;
 use std::fmt::Write;
 use std::fs;
 use std::path::Path;
@@ -14,15 +27,17 @@ pub fn generate(
     output_dir: &Path,
     package_name: &str,
     side: Side,
+    style: PythonModels,
 ) -> Result<Vec<String>, CodegenError> {
     let mut generated = Vec::new();
     let scalars = build_scalar_map(file);
     let models = build_model_map(file);
+    let constraints = build_constraint_map(file);
 
     fs::create_dir_all(output_dir)?;
 
     // Generate models
-    let models_content = generate_models(file, &scalars, &models)?;
+    let models_content = generate_models(file, &scalars, &constraints, &models, style)?;
     let models_path = output_dir.join("models.py");
     fs::write(&models_path, models_content)?;
     generated.push(models_path.display().to_string());
@@ -38,7 +53,7 @@ pub fn generate(
         let client_dir = output_dir.join("client");
         fs::create_dir_all(&client_dir)?;
 
-        let client_content = generate_client(file, &scalars)?;
+        let client_content = generate_client(file, &scalars, &models, style)?;
         let client_path = client_dir.join("__init__.py");
         fs::write(&client_path, client_content)?;
         generated.push(client_path.display().to_string());
@@ -49,14 +64,27 @@ pub fn generate(
         let server_dir = output_dir.join("server");
         fs::create_dir_all(&server_dir)?;
 
-        let server_content = generate_server(file, &scalars)?;
+        let server_content = generate_server(file, &scalars, &models)?;
         let server_path = server_dir.join("__init__.py");
         fs::write(&server_path, server_content)?;
         generated.push(server_path.display().to_string());
+
+        let app_content = generate_server_app(file, &scalars, &models, style)?;
+        let app_path = server_dir.join("app.py");
+        fs::write(&app_path, app_content)?;
+        generated.push(app_path.display().to_string());
+
+        // The app serves the OpenAPI document of the API
+        let spec = generate_openapi_spec(file, &scalars, &constraints, &models, package_name)?;
+        let spec_path = server_dir.join("openapi.json");
+        let spec_content = serde_json::to_string_pretty(&spec)
+            .map_err(|e| CodegenError::Generation(e.to_string()))?;
+        fs::write(&spec_path, spec_content)?;
+        generated.push(spec_path.display().to_string());
     }
 
     // Generate __init__.py
-    let init_content = generate_init(package_name)?;
+    let init_content = generate_init(package_name, side)?;
     let init_path = output_dir.join("__init__.py");
     fs::write(&init_path, init_content)?;
     generated.push(init_path.display().to_string());
@@ -67,9 +95,13 @@ pub fn generate(
 fn generate_models(
     file: &TypeSpecFile,
     scalars: &ScalarMap,
+    constraints: &ConstraintMap,
     models: &ModelMap<'_>,
+    style: PythonModels,
 ) -> Result<String, CodegenError> {
     let mut out = String::new();
+    let validated = validated_models(file, models, constraints);
+    let json = JsonConverter::new(file, scalars, models, style);
 
     writeln!(
         out,
@@ -80,87 +112,881 @@ DO NOT EDIT.
     )?;
     writeln!(out)?;
     writeln!(out, "from __future__ import annotations")?;
+    writeln!(out, "import re")?;
     writeln!(out, "from dataclasses import dataclass, field")?;
     writeln!(out, "from datetime import datetime")?;
     writeln!(
         out,
-        "from typing import Any, Optional, List, Dict, Literal, TypeVar, Generic"
+        "from typing import Any, Callable, Optional, List, Dict, Literal, Mapping, TypeVar, Generic"
     )?;
     writeln!(out, "from uuid import UUID")?;
+    if style == PythonModels::Pydantic {
+        writeln!(out)?;
+        writeln!(out, "import pydantic")?;
+        writeln!(out, "from pydantic import BaseModel, ConfigDict, Field")?;
+    }
+    writeln!(out)?;
+    // Enums are converted to and from their values
+    writeln!(out, "from .enums import *")?;
     writeln!(out)?;
     writeln!(out, "T = TypeVar('T')")?;
+    writeln!(out, "H = TypeVar('H')")?;
     writeln!(out)?;
+    writeln!(
+        out,
+        r#"
+class ValidationError(ValueError):
+    """A value that breaks a constraint of the TypeSpec schema"""
 
-    for model in file.models() {
-        writeln!(out)?;
+    def __init__(self, field: str, message: str):
+        self.field = field
+        self.message = message
+        super().__init__(f"{{field}}: {{message}}")
+
+
+@dataclass
+class WithHeaders(Generic[T, H]):
+    """A response body with the `@header`s sent along with it"""
+
+    body: T
+    headers: H
+"#
+    )?;
+    write!(out, "{}", CONVERSION_RUNTIME)?;
+    if style == PythonModels::Pydantic {
+        write!(out, "{}", PYDANTIC_RUNTIME)?;
+    }
+
+    // Base classes must be defined before their subclasses
+    for model in models_in_inheritance_order(file, models) {
+        match style {
+            PythonModels::Dataclass => {
+                write_dataclass(&mut out, model, &json, constraints, models, &validated)?
+            }
+            PythonModels::Pydantic => {
+                write_pydantic_model(&mut out, model, scalars, constraints, models)?
+            }
+        }
+    }
+
+    // Typed `@header`s of responses
+    for iface in &collect_services(file) {
+        for http_op in http_operations(iface, models) {
+            for response in http_op.header_responses() {
+                write_response_headers(&mut out, &iface.name, &http_op, response, scalars)?;
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Write a model as a `@dataclass` with `to_dict`/`from_dict` methods
+fn write_dataclass(
+    out: &mut String,
+    model: &Model,
+    json: &JsonConverter<'_>,
+    constraints: &ConstraintMap,
+    models: &ModelMap<'_>,
+    validated: &HashSet<String>,
+) -> Result<(), CodegenError> {
+    let mut bases = Vec::new();
+    if let Some(base) = &model.extends {
+        bases.push(type_to_python(base, json.scalars));
+    }
+    // Add Generic base if model has type parameters
+    if !model.type_params.is_empty() {
+        bases.push(format!("Generic[{}]", model.type_params.join(", ")));
+    }
+
+    writeln!(out)?;
+    if model.extends.is_some() {
+        // Keyword-only fields may follow the base's optional fields
+        writeln!(out, "@dataclass(kw_only=True)")?;
+    } else {
         writeln!(out, "@dataclass")?;
-        // Add Generic base if model has type parameters
-        if model.type_params.is_empty() {
-            writeln!(out, "class {}:", model.name)?;
+    }
+    if bases.is_empty() {
+        writeln!(out, "class {}:", model.name)?;
+    } else {
+        writeln!(out, "class {}({}):", model.name, bases.join(", "))?;
+    }
+
+    write_docstring(out, "    ", &model.decorators)?;
+
+    // Fields inherited from the base class are not redeclared. A literal
+    // `@statusCode` is implied by the model and not stored.
+    let all_properties: Vec<_> = resolve_properties(model, models)
+        .into_iter()
+        .filter(|p| status_literal(p).is_none())
+        .collect();
+    let fields: Vec<_> = own_properties(model, models)
+        .into_iter()
+        .filter(|p| status_literal(p).is_none())
+        .collect();
+
+    if fields.is_empty() {
+        writeln!(out, "    pass")?;
+    } else {
+        // Required fields first
+        for prop in fields.iter().filter(|p| !p.optional) {
+            let py_type = type_to_python(&prop.type_ref, json.scalars);
+            let name = prop.name.to_case(Case::Snake);
+            writeln!(out, "    {}: {}", name, py_type)?;
+            write_docstring(out, "    ", &prop.decorators)?;
+        }
+
+        // Optional fields
+        for prop in fields.iter().filter(|p| p.optional) {
+            let py_type = type_to_python(&prop.type_ref, json.scalars);
+            let name = prop.name.to_case(Case::Snake);
+            writeln!(out, "    {}: Optional[{}] = None", name, py_type)?;
+            write_docstring(out, "    ", &prop.decorators)?;
+        }
+    }
+
+    // Templates take a function per type param converting its values, the
+    // identity by default
+    let json = &json.in_template(&model.type_params);
+    let converters: String = model
+        .type_params
+        .iter()
+        .map(|param| {
+            format!(
+                ", {}: Callable[[Any], Any] = lambda value: value",
+                param.to_case(Case::Snake)
+            )
+        })
+        .collect();
+
+    // Add to_dict method
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    def to_dict(self{}) -> Dict[str, Any]:",
+        converters
+    )?;
+    writeln!(out, "        result: Dict[str, Any] = {{}}")?;
+    for prop in all_properties.iter().filter(|p| !is_metadata(p)) {
+        let name = prop.name.to_case(Case::Snake);
+        let orig = &prop.name;
+        let field = format!("self.{}", name);
+        let type_ref = if prop.optional {
+            nullable(&prop.type_ref).unwrap_or(&prop.type_ref)
+        } else {
+            &prop.type_ref
+        };
+        let value = json.encode(type_ref, &field).unwrap_or(field);
+        if prop.optional {
+            writeln!(out, "        if self.{} is not None:", name)?;
+            writeln!(out, r#"            result["{}"] = {}"#, orig, value)?;
         } else {
-            let params = model.type_params.join(", ");
-            writeln!(out, "class {}(Generic[{}]):", model.name, params)?;
+            writeln!(out, r#"        result["{}"] = {}"#, orig, value)?;
+        }
+    }
+    writeln!(out, "        return result")?;
+
+    // Add from_dict method
+    writeln!(out)?;
+    writeln!(out, "    @classmethod")?;
+    writeln!(
+        out,
+        "    def from_dict(cls, data: Dict[str, Any]{}) -> \"{}\":",
+        converters, model.name
+    )?;
+    writeln!(out, "        return cls(")?;
+    for prop in &all_properties {
+        // Null values of optional and nullable fields are left as `None`.
+        // `@header`s and `@statusCode`s are not in the body, `from_response`
+        // sets the status.
+        let type_ref = nullable(&prop.type_ref);
+        let required = !prop.optional && type_ref.is_none() && !is_metadata(prop);
+        let decoded = json.decode(type_ref.unwrap_or(&prop.type_ref), "value");
+        writeln!(
+            out,
+            "            {}={},",
+            prop.name.to_case(Case::Snake),
+            JsonConverter::read("data", &prop.name, decoded, required, None)
+        )?;
+    }
+    writeln!(out, "        )")?;
+
+    if is_error_model(model) {
+        write_error_methods(out, model, &all_properties, models)?;
+    }
+
+    if validated.contains(&model.name) {
+        write_validate(out, &all_properties, constraints, models, validated)?;
+    }
+    Ok(())
+}
+
+/// Helpers of the conversions written by [`JsonConverter`], used by the
+/// dataclasses' `from_dict` and by servers reading implicit bodies
+const CONVERSION_RUNTIME: &str = r#"
+
+def _datetime(value: str) -> datetime:
+    """Parse an ISO 8601 date-time, `Z` suffix included"""
+    return datetime.fromisoformat(value.replace("Z", "+00:00"))
+
+
+def _read(
+    data: Dict[str, Any],
+    field: str,
+    convert: Optional[Callable[[Any], Any]] = None,
+    *,
+    required: bool = False,
+    default: Any = None,
+) -> Any:
+    """Read a field of JSON data, converting its value. Missing required
+    fields and values that do not convert raise `ValidationError`."""
+    value = data.get(field)
+    if value is None:
+        if required:
+            raise ValidationError(field, "is required")
+        return default
+    if convert is None:
+        return value
+    try:
+        return convert(value)
+    except ValidationError as e:
+        # Errors of a whole nested body are errors of the field
+        if e.field == "body":
+            path = field
+        elif e.field.startswith("["):
+            path = f"{field}{e.field}"
+        else:
+            path = f"{field}.{e.field}"
+        raise ValidationError(path, e.message) from None
+    except (AttributeError, TypeError, ValueError):
+        raise ValidationError(field, "is invalid") from None
+"#;
+
+/// Writes the Python expressions converting values of a TypeSpec type to
+/// their JSON form and back: nested models through `to_dict`/`from_dict`,
+/// enums through their values, datetimes as ISO 8601 and UUIDs as strings,
+/// recursing through arrays, records and optional values.
+#[derive(Clone)]
+struct JsonConverter<'a> {
+    scalars: &'a ScalarMap,
+    models: &'a ModelMap<'a>,
+    enums: HashSet<&'a str>,
+    style: PythonModels,
+    /// Type params of the template model being written, converted by the
+    /// functions its `to_dict` and `from_dict` are given
+    type_params: Vec<String>,
+}
+
+impl<'a> JsonConverter<'a> {
+    fn new(
+        file: &'a TypeSpecFile,
+        scalars: &'a ScalarMap,
+        models: &'a ModelMap<'a>,
+        style: PythonModels,
+    ) -> Self {
+        Self {
+            scalars,
+            models,
+            enums: file.enums().map(|e| e.name.as_str()).collect(),
+            style,
+            type_params: Vec::new(),
         }
+    }
 
-        if let Some(desc) = get_description(&model.decorators) {
-            writeln!(out, r#"    """{}""""#, desc)?;
+    /// The converter of the fields of a model with `type_params`
+    fn in_template(&self, type_params: &[String]) -> Self {
+        Self {
+            type_params: type_params.to_vec(),
+            ..self.clone()
         }
+    }
 
-        // Resolve spread references and get all properties
-        let all_properties = resolve_properties(model, models);
+    /// The JSON form of `value`, or `None` when `value` is JSON already
+    fn encode(&self, type_ref: &TypeRef, value: &str) -> Option<String> {
+        self.convert(type_ref, value, Conversion::Encode, 0)
+    }
 
-        if all_properties.is_empty() {
-            writeln!(out, "    pass")?;
-        } else {
-            // Required fields first
-            for prop in all_properties.iter().filter(|p| !p.optional) {
-                let py_type = type_to_python(&prop.type_ref, scalars);
-                let name = prop.name.to_case(Case::Snake);
-                writeln!(out, "    {}: {}", name, py_type)?;
+    /// The Python value of JSON `value`, or `None` when it needs no conversion
+    fn decode(&self, type_ref: &TypeRef, value: &str) -> Option<String> {
+        self.convert(type_ref, value, Conversion::Decode, 0)
+    }
+
+    /// Like [`Self::decode`], also checking that strings, numbers, booleans
+    /// and arrays have their declared JSON type, e.g. `_int(value)`
+    fn check(&self, type_ref: &TypeRef, value: &str) -> Option<String> {
+        self.convert(type_ref, value, Conversion::Check, 0)
+    }
+
+    /// Expression reading `field` from the JSON object `data` with `_read`,
+    /// converting it with `decoded`, an expression of `value`, e.g.
+    /// `_read(data, "status", Status, required=True)`
+    fn read(
+        data: &str,
+        field: &str,
+        decoded: Option<String>,
+        required: bool,
+        default: Option<String>,
+    ) -> String {
+        let mut args = vec![data.to_string(), format!("\"{}\"", field)];
+        if let Some(decoded) = decoded {
+            args.push(converter(&decoded));
+        }
+        if required {
+            args.push("required=True".to_string());
+        }
+        if let Some(default) = default {
+            args.push(format!("default={}", default));
+        }
+        format!("_read({})", args.join(", "))
+    }
+
+    fn convert(
+        &self,
+        type_ref: &TypeRef,
+        value: &str,
+        conversion: Conversion,
+        depth: usize,
+    ) -> Option<String> {
+        // Comprehensions nested in one another get names of their own
+        let var = |name: &str| match depth {
+            0 => name.to_string(),
+            _ => format!("{}{}", name, depth),
+        };
+        if let Some(inner) = nullable(type_ref) {
+            return self
+                .convert(inner, value, conversion, depth)
+                .map(|e| format!("(None if {} is None else {})", value, e));
+        }
+        match type_ref {
+            TypeRef::Array(inner) => {
+                let item = var("item");
+                let items = match conversion {
+                    Conversion::Check => format!("_list({})", value),
+                    _ => value.to_string(),
+                };
+                self.convert(inner, &item, conversion, depth + 1)
+                    .map(|e| format!("[{} for {} in {}]", e, item, items))
             }
+            TypeRef::Generic { base, args }
+                if args.len() == 1 && type_to_python(base, self.scalars) == "Record" =>
+            {
+                let (key, item) = (var("key"), var("item"));
+                self.convert(&args[0], &item, conversion, depth + 1)
+                    .map(|e| {
+                        format!(
+                            "{{{}: {} for {}, {} in {}.items()}}",
+                            key, e, key, item, value
+                        )
+                    })
+            }
+            TypeRef::Generic { base, args } => {
+                self.convert_instance(type_ref, base, args, value, conversion, depth)
+            }
+            TypeRef::Builtin(name) => convert_builtin(name, value, conversion),
+            // Values of a type param convert with the function passed for it
+            TypeRef::Named(name) if self.type_params.contains(name) => {
+                Some(format!("{}({})", name.to_case(Case::Snake), value))
+            }
+            TypeRef::Named(name) => self.convert_named(name, name, value, conversion),
+            TypeRef::Qualified(parts) => {
+                let name = parts.last()?;
+                self.convert_named(name, &parts.join("."), value, conversion)
+            }
+            _ => None,
+        }
+    }
 
-            // Optional fields
-            for prop in all_properties.iter().filter(|p| p.optional) {
-                let py_type = type_to_python(&prop.type_ref, scalars);
-                let name = prop.name.to_case(Case::Snake);
-                writeln!(out, "    {}: Optional[{}] = None", name, py_type)?;
+    /// Instances of template models convert like the template. Dataclasses
+    /// are given a function per type argument, e.g.
+    /// `Page.from_dict(value, t=Task.from_dict)`, and Pydantic models are
+    /// decoded as the instance, e.g. `Page[Task].from_dict(value)`.
+    fn convert_instance(
+        &self,
+        type_ref: &TypeRef,
+        base: &TypeRef,
+        args: &[TypeRef],
+        value: &str,
+        conversion: Conversion,
+        depth: usize,
+    ) -> Option<String> {
+        let template = lookup_model(base, self.models).filter(|m| !m.type_params.is_empty());
+        let Some(template) = template.filter(|_| self.style == PythonModels::Dataclass) else {
+            if conversion != Conversion::Encode && template.is_some() {
+                let instance = type_to_python(type_ref, self.scalars);
+                return Some(format!("{}.from_dict({})", instance, value));
+            }
+            return self.convert(base, value, conversion, depth);
+        };
+        let mut converters = Vec::new();
+        for (param, arg) in template.type_params.iter().zip(args) {
+            if let Some(converted) = self.convert(arg, "value", conversion, 0) {
+                converters.push(format!(
+                    "{}={}",
+                    param.to_case(Case::Snake),
+                    converter(&converted)
+                ));
             }
         }
+        Some(match conversion {
+            Conversion::Encode => format!("{}.to_dict({})", value, converters.join(", ")),
+            Conversion::Decode | Conversion::Check => format!(
+                "{}.from_dict({})",
+                type_to_python(base, self.scalars),
+                [value.to_string()]
+                    .into_iter()
+                    .chain(converters)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        })
+    }
 
-        // Add to_dict method
-        writeln!(out)?;
-        writeln!(out, "    def to_dict(self) -> Dict[str, Any]:")?;
-        writeln!(out, "        result: Dict[str, Any] = {{}}")?;
-        for prop in &all_properties {
-            let name = prop.name.to_case(Case::Snake);
-            let orig = &prop.name;
-            if prop.optional {
-                writeln!(out, "        if self.{} is not None:", name)?;
-                writeln!(out, r#"            result["{}"] = self.{}"#, orig, name)?;
-            } else {
-                writeln!(out, r#"        result["{}"] = self.{}"#, orig, name)?;
+    fn convert_named(
+        &self,
+        name: &str,
+        qualified: &str,
+        value: &str,
+        conversion: Conversion,
+    ) -> Option<String> {
+        let (encoded, decoded) = if name == "uuid" {
+            (format!("str({})", value), format!("UUID({})", value))
+        } else if let Some(base) = self.scalars.get(name) {
+            return convert_builtin(base, value, conversion);
+        } else if self.enums.contains(name) {
+            (
+                format!("{}({}).value", name, value),
+                format!("{}({})", name, value),
+            )
+        } else if self.models.contains_key(qualified) {
+            (
+                format!("{}.to_dict()", value),
+                format!("{}.from_dict({})", name, value),
+            )
+        } else {
+            return None;
+        };
+        Some(match conversion {
+            Conversion::Encode => encoded,
+            Conversion::Decode | Conversion::Check => decoded,
+        })
+    }
+}
+
+/// A function of `value` converting it like `decoded`, an expression of
+/// `value`: `Status` for `Status(value)`, a lambda otherwise
+fn converter(decoded: &str) -> String {
+    let callee = decoded.strip_suffix("(value)").filter(|f| {
+        f.chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+    });
+    match callee {
+        Some(callee) => callee.to_string(),
+        None => format!("lambda value: {}", decoded),
+    }
+}
+
+/// Direction of a [`JsonConverter`] conversion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Conversion {
+    Encode,
+    Decode,
+    /// Decode JSON that is not trusted to match the schema, e.g. request bodies
+    Check,
+}
+
+/// The type of the values of `T | null`, or of an optional `T`
+fn nullable(type_ref: &TypeRef) -> Option<&TypeRef> {
+    match type_ref {
+        TypeRef::Optional(inner) => Some(inner),
+        TypeRef::Union(variants) => match variants.as_slice() {
+            [TypeRef::Builtin(null), inner] | [inner, TypeRef::Builtin(null)] if null == "null" => {
+                Some(inner)
             }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Conversion of a builtin type: only date-times have a JSON form of their
+/// own. Checked strings, numbers and booleans go through `_str`, `_int`,
+/// `_float` and `_bool`.
+fn convert_builtin(name: &str, value: &str, conversion: Conversion) -> Option<String> {
+    match (builtin_to_python(name).as_str(), conversion) {
+        ("datetime", Conversion::Encode) => Some(format!("{}.isoformat()", value)),
+        ("datetime", _) => Some(format!("_datetime({})", value)),
+        (ty @ ("str" | "int" | "float" | "bool"), Conversion::Check) => {
+            Some(format!("_{}({})", ty, value))
         }
-        writeln!(out, "        return result")?;
+        _ => None,
+    }
+}
 
-        // Add from_dict method
-        writeln!(out)?;
-        writeln!(out, "    @classmethod")?;
+/// Write a model as a Pydantic `BaseModel`. Pydantic checks the schema's
+/// constraints and converts enums, datetimes and nested models when the
+/// model is built; fields are read and written under their TypeSpec names.
+fn write_pydantic_model(
+    out: &mut String,
+    model: &Model,
+    scalars: &ScalarMap,
+    constraints: &ConstraintMap,
+    models: &ModelMap<'_>,
+) -> Result<(), CodegenError> {
+    let mut bases = vec![match &model.extends {
+        Some(base) => type_to_python(base, scalars),
+        None => "ApiModel".to_string(),
+    }];
+    if !model.type_params.is_empty() {
+        bases.push(format!("Generic[{}]", model.type_params.join(", ")));
+    }
+
+    writeln!(out)?;
+    writeln!(out)?;
+    writeln!(out, "class {}({}):", model.name, bases.join(", "))?;
+    write_docstring(out, "    ", &model.decorators)?;
+
+    let fields: Vec<_> = own_properties(model, models)
+        .into_iter()
+        .filter(|p| status_literal(p).is_none())
+        .collect();
+    if fields.is_empty() {
+        writeln!(out, "    pass")?;
+    }
+    for prop in &fields {
+        let name = prop.name.to_case(Case::Snake);
+        let mut py_type = type_to_python(&prop.type_ref, scalars);
+        let mut args = Vec::new();
+
+        // `@header`s and `@statusCode`s are not part of the body
+        let metadata = is_metadata(prop);
+        let default = match &prop.default {
+            Some(value) => Some(value_to_python(value)),
+            None => (prop.optional || metadata).then(|| "None".to_string()),
+        };
+        if (prop.optional || metadata) && !py_type.starts_with("Optional[") {
+            py_type = format!("Optional[{}]", py_type);
+        }
+        if name != prop.name {
+            args.push(format!("alias={}", string_literal(&prop.name)));
+        }
+        if metadata {
+            args.push("exclude=True".to_string());
+        }
+        let checks = Constraints::of(&prop.decorators, &prop.type_ref, constraints);
+        for check in checks.checks() {
+            args.push(match check {
+                Check::MinLength(n) | Check::MinItems(n) => format!("min_length={}", n),
+                Check::MaxLength(n) | Check::MaxItems(n) => format!("max_length={}", n),
+                Check::MinValue(n) => format!("ge={}", number_literal(n)),
+                Check::MaxValue(n) => format!("le={}", number_literal(n)),
+                Check::Pattern(pattern) => format!("pattern={}", string_literal(pattern)),
+            });
+        }
+
+        match default {
+            Some(default) if args.is_empty() => {
+                writeln!(out, "    {}: {} = {}", name, py_type, default)?
+            }
+            Some(default) => writeln!(
+                out,
+                "    {}: {} = Field(default={}, {})",
+                name,
+                py_type,
+                default,
+                args.join(", ")
+            )?,
+            None if args.is_empty() => writeln!(out, "    {}: {}", name, py_type)?,
+            None => writeln!(
+                out,
+                "    {}: {} = Field({})",
+                name,
+                py_type,
+                args.join(", ")
+            )?,
+        }
+        write_docstring(out, "    ", &prop.decorators)?;
+    }
+
+    if is_error_model(model) {
+        let all_properties: Vec<_> = resolve_properties(model, models)
+            .into_iter()
+            .filter(|p| status_literal(p).is_none())
+            .collect();
+        write_error_methods(out, model, &all_properties, models)?;
+    }
+    Ok(())
+}
+
+/// Base of Pydantic models, keeping the `to_dict`/`from_dict`/`validate`
+/// interface of the dataclass models the client and server are written against
+const PYDANTIC_RUNTIME: &str = r#"
+M = TypeVar('M', bound='ApiModel')
+
+
+class ApiModel(BaseModel):
+    """Base of the generated models: fields are read and written under their
+    TypeSpec names, and the schema's constraints are checked on construction"""
+
+    model_config = ConfigDict(
+        populate_by_name=True,
+        protected_namespaces=(),
+        regex_engine="python-re",
+    )
+
+    def to_dict(self) -> Dict[str, Any]:
+        return self.model_dump(mode="json", by_alias=True, exclude_none=True)
+
+    @classmethod
+    def from_dict(cls: type[M], data: Dict[str, Any]) -> M:
+        try:
+            return cls.model_validate(data)
+        except pydantic.ValidationError as e:
+            error = e.errors()[0]
+            raise ValidationError(_field_path(error["loc"]), error["msg"]) from None
+
+    def validate(self, path: str = "") -> None:
+        """Constraints are checked when the model is built"""
+
+
+def _field_path(loc: tuple) -> str:
+    path = ""
+    for part in loc:
+        if isinstance(part, int):
+            path += f"[{part}]"
+        else:
+            path += f".{part}" if path else str(part)
+    return path or "body"
+"#;
+
+/// Write the dataclass of the `@header`s an operation responds with, read
+/// by clients with `from_headers` and sent by servers with `to_headers`
+fn write_response_headers(
+    out: &mut String,
+    iface_name: &str,
+    http_op: &HttpOperation<'_>,
+    response: &Response<'_>,
+    scalars: &ScalarMap,
+) -> Result<(), CodegenError> {
+    let class_name = http_op.headers_name(iface_name, response);
+
+    writeln!(out)?;
+    writeln!(out)?;
+    writeln!(out, "@dataclass")?;
+    writeln!(out, "class {}:", class_name)?;
+    if http_op.responses.len() > 1 {
         writeln!(
             out,
-            "    def from_dict(cls, data: Dict[str, Any]) -> \"{}\":",
-            model.name
+            r#"    """Headers of the `{}` response of `{}.{}`""""#,
+            status_name(response.status),
+            iface_name,
+            http_op.op.name
         )?;
-        writeln!(out, "        return cls(")?;
-        for prop in &all_properties {
-            let name = prop.name.to_case(Case::Snake);
-            let orig = &prop.name;
-            writeln!(out, r#"            {}=data.get("{}"),"#, name, orig)?;
+    } else {
+        writeln!(
+            out,
+            r#"    """Response headers of `{}.{}`""""#,
+            iface_name, http_op.op.name
+        )?;
+    }
+    writeln!(out)?;
+    let mut headers: Vec<_> = response.headers.iter().collect();
+    headers.sort_by_key(|h| h.optional);
+    for header in &headers {
+        let py_type = type_to_python(&header.type_ref, scalars);
+        let name = header.name.to_case(Case::Snake);
+        if header.optional {
+            writeln!(out, "    {}: Optional[{}] = None", name, py_type)?;
+        } else {
+            writeln!(out, "    {}: {}", name, py_type)?;
         }
-        writeln!(out, "        )")?;
+        write_docstring(out, "    ", &header.decorators)?;
     }
 
-    Ok(out)
+    writeln!(out)?;
+    writeln!(out, "    @classmethod")?;
+    writeln!(
+        out,
+        "    def from_headers(cls, headers: Mapping[str, str]) -> \"{}\":",
+        class_name
+    )?;
+    writeln!(out, "        return cls(")?;
+    for header in &headers {
+        let name = header.name.to_case(Case::Snake);
+        let header_name = header_name(&header.decorators, &header.name);
+        let py_type = type_to_python(&header.type_ref, scalars);
+        let convert = matches!(py_type.as_str(), "int" | "float");
+        let value = match (header.optional, convert) {
+            (false, false) => format!(r#"headers["{}"]"#, header_name),
+            (false, true) => format!(r#"{}(headers["{}"])"#, py_type, header_name),
+            (true, false) => format!(r#"headers.get("{}")"#, header_name),
+            (true, true) => format!(
+                r#"{ty}(headers["{name}"]) if headers.get("{name}") is not None else None"#,
+                ty = py_type,
+                name = header_name
+            ),
+        };
+        writeln!(out, "            {}={},", name, value)?;
+    }
+    writeln!(out, "        )")?;
+
+    writeln!(out)?;
+    writeln!(out, "    def to_headers(self) -> Dict[str, str]:")?;
+    writeln!(out, "        headers: Dict[str, str] = {{}}")?;
+    for header in &headers {
+        let name = header.name.to_case(Case::Snake);
+        let header_name = header_name(&header.decorators, &header.name);
+        if header.optional {
+            writeln!(out, "        if self.{} is not None:", name)?;
+            writeln!(
+                out,
+                r#"            headers["{}"] = str(self.{})"#,
+                header_name, name
+            )?;
+        } else {
+            writeln!(
+                out,
+                r#"        headers["{}"] = str(self.{})"#,
+                header_name, name
+            )?;
+        }
+    }
+    writeln!(out, "        return headers")?;
+    Ok(())
+}
+
+/// Write the methods of an `@error` model: `response_status` for servers and
+/// `from_response` for clients. A literal `@statusCode` fixes the status; a
+/// `@statusCode` field is read for the response and set when decoding.
+fn write_error_methods(
+    out: &mut String,
+    model: &Model,
+    properties: &[std::borrow::Cow<'_, Property>],
+    models: &ModelMap<'_>,
+) -> Result<(), CodegenError> {
+    let literal = status_property(model, models)
+        .as_ref()
+        .and_then(status_literal);
+    let field = properties
+        .iter()
+        .find(|p| is_status_code(p))
+        .map(|p| p.name.to_case(Case::Snake));
+
+    writeln!(out)?;
+    writeln!(out, "    def response_status(self) -> int:")?;
+    writeln!(out, r#"        """Status code to respond with""""#)?;
+    match (literal, &field) {
+        (Some(code), _) => writeln!(out, "        return {}", code)?,
+        (None, Some(name)) => writeln!(out, "        return self.{} or 500", name)?,
+        (None, None) => writeln!(out, "        return 500")?,
+    }
+
+    writeln!(out)?;
+    writeln!(out, "    @classmethod")?;
+    writeln!(
+        out,
+        "    def from_response(cls, status: int, data: Dict[str, Any]) -> Optional[\"{}\"]:",
+        model.name
+    )?;
+    writeln!(
+        out,
+        r#"        """Decode the body of an error response, if it is this error""""#
+    )?;
+    if let Some(code) = literal {
+        writeln!(out, "        if status != {}:", code)?;
+        writeln!(out, "            return None")?;
+    }
+    writeln!(out, "        error = cls.from_dict(data)")?;
+    if let (None, Some(name)) = (literal, &field) {
+        writeln!(out, "        error.{} = status", name)?;
+    }
+    writeln!(out, "        return error")?;
+    Ok(())
+}
+
+/// Write the `validate` method of a model: a check per constraint and a
+/// `validate` call per property holding a validated model.
+fn write_validate(
+    out: &mut String,
+    properties: &[std::borrow::Cow<'_, Property>],
+    constraints: &ConstraintMap,
+    models: &ModelMap<'_>,
+    validated: &HashSet<String>,
+) -> std::fmt::Result {
+    writeln!(out)?;
+    writeln!(out, r#"    def validate(self, path: str = "") -> None:"#)?;
+    writeln!(
+        out,
+        r#"        """Check the schema's constraints, raising `ValidationError` on the first violation""""#
+    )?;
+    for prop in properties {
+        let name = prop.name.to_case(Case::Snake);
+        let value = format!("self.{}", name);
+        let optional = prop.optional || matches!(prop.type_ref, TypeRef::Optional(_));
+
+        let checks = Constraints::of(&prop.decorators, &prop.type_ref, constraints);
+        for check in checks.checks() {
+            let condition = match check {
+                Check::MinLength(n) => format!("len({}) < {}", value, n),
+                Check::MaxLength(n) => format!("len({}) > {}", value, n),
+                Check::MinValue(n) => format!("{} < {}", value, number_literal(n)),
+                Check::MaxValue(n) => format!("{} > {}", value, number_literal(n)),
+                Check::MinItems(n) => format!("len({}) < {}", value, n),
+                Check::MaxItems(n) => format!("len({}) > {}", value, n),
+                Check::Pattern(pattern) => {
+                    format!("re.search({}, {}) is None", string_literal(pattern), value)
+                }
+            };
+            if optional {
+                writeln!(out, "        if {} is not None and {}:", value, condition)?;
+            } else {
+                writeln!(out, "        if {}:", condition)?;
+            }
+            writeln!(
+                out,
+                r#"            raise ValidationError(f"{{path}}{}", {})"#,
+                prop.name,
+                string_literal(&check.message())
+            )?;
+        }
+
+        let Some(nested) = nested_model(&prop.type_ref, models)
+            .filter(|nested| validated.contains(&nested.model.name))
+        else {
+            continue;
+        };
+        if nested.array {
+            let items = if optional {
+                format!("{} or []", value)
+            } else {
+                value
+            };
+            writeln!(out, "        for i, item in enumerate({}):", items)?;
+            writeln!(
+                out,
+                r#"            item.validate(f"{{path}}{}[{{i}}].")"#,
+                prop.name
+            )?;
+        } else if optional {
+            writeln!(out, "        if {} is not None:", value)?;
+            writeln!(
+                out,
+                r#"            {}.validate(f"{{path}}{}.")"#,
+                value, prop.name
+            )?;
+        } else {
+            writeln!(
+                out,
+                r#"        {}.validate(f"{{path}}{}.")"#,
+                value, prop.name
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Python string literal for `s` (JSON string syntax is valid Python)
+fn string_literal(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_default()
 }
 
 fn generate_enums(file: &TypeSpecFile) -> Result<String, CodegenError> {
@@ -180,6 +1006,7 @@ DO NOT EDIT.
     for enum_def in file.enums() {
         writeln!(out)?;
         writeln!(out, "class {}(str, Enum):", enum_def.name)?;
+        write_docstring(&mut out, "    ", &enum_def.decorators)?;
 
         for member in &enum_def.members {
             let value = member
@@ -193,14 +1020,21 @@ DO NOT EDIT.
 
             let variant = member.name.to_case(Case::ScreamingSnake);
             writeln!(out, r#"    {} = "{}""#, variant, value)?;
+            write_docstring(&mut out, "    ", &member.decorators)?;
         }
     }
 
     Ok(out)
 }
 
-fn generate_client(file: &TypeSpecFile, scalars: &ScalarMap) -> Result<String, CodegenError> {
+fn generate_client(
+    file: &TypeSpecFile,
+    scalars: &ScalarMap,
+    models: &ModelMap<'_>,
+    style: PythonModels,
+) -> Result<String, CodegenError> {
     let mut out = String::new();
+    let json = JsonConverter::new(file, scalars, models, style);
 
     writeln!(
         out,
@@ -212,8 +1046,10 @@ DO NOT EDIT.
     writeln!(out)?;
     writeln!(out, "from __future__ import annotations")?;
     writeln!(out, "import httpx")?;
-    writeln!(out, "from typing import Any, Optional, Dict")?;
+    writeln!(out, "from typing import Any, Optional, Dict, Tuple")?;
+    writeln!(out, "from .. import models")?;
     writeln!(out, "from ..models import *")?;
+    writeln!(out, "from ..models import _datetime")?;
     writeln!(out, "from ..enums import *")?;
     writeln!(out)?;
 
@@ -222,9 +1058,11 @@ DO NOT EDIT.
         out,
         r#"
 class ApiError(Exception):
-    def __init__(self, status_code: int, message: str):
+    def __init__(self, status_code: int, message: str, error: Any = None):
         self.status_code = status_code
         self.message = message
+        self.error = error
+        """The `@error` model of the response, if the operation declares it"""
         super().__init__(f"{{status_code}}: {{message}}")
 
 
@@ -248,45 +1086,75 @@ class BaseClient:
             headers["Authorization"] = f"Bearer {{self.access_token}}"
         return headers
 
-    async def _request(self, method: str, path: str, **kwargs) -> Any:
+    async def _request(self, method: str, path: str, errors: Tuple[Any, ...] = (), **kwargs) -> Any:
+        _, result = await self._request_response(method, path, errors, **kwargs)
+        return result
+
+    async def _request_response(
+        self, method: str, path: str, errors: Tuple[Any, ...] = (), **kwargs
+    ) -> Tuple[int, Any]:
+        """Send a request, returning the status of the response with its body"""
+        resp = await self._send(method, path, errors, **kwargs)
+        if resp.status_code == 204:
+            return resp.status_code, None
+        return resp.status_code, resp.json()
+
+    async def _send(
+        self, method: str, path: str, errors: Tuple[Any, ...] = (), **kwargs
+    ) -> httpx.Response:
+        """Send a request, raising `ApiError` for error responses"""
         url = f"{{self.base_url}}{{path}}"
-        resp = await self._client.request(method, url, headers=self._headers(), **kwargs)
+        headers = {{**self._headers(), **kwargs.pop("headers", {{}})}}
+        resp = await self._client.request(method, url, headers=headers, **kwargs)
         if resp.status_code >= 400:
-            raise ApiError(resp.status_code, resp.text)
-        if resp.status_code == 204:
-            return None
-        return resp.json()
+            raise self._error(resp, errors)
+        return resp
+
+    def _error(self, resp: httpx.Response, errors: Tuple[Any, ...]) -> ApiError:
+        try:
+            data = resp.json()
+        except ValueError:
+            data = None
+        if isinstance(data, dict):
+            for model in errors:
+                error = model.from_response(resp.status_code, data)
+                if error is not None:
+                    return ApiError(resp.status_code, resp.text, error)
+        return ApiError(resp.status_code, resp.text)
 "#
     )?;
 
     // Service clients
-    for iface in file.interfaces() {
-        let base_path = get_route(&iface.decorators).unwrap_or_default();
+    for iface in &collect_services(file) {
         let class_name = format!("{}Client", iface.name);
 
         writeln!(out)?;
         writeln!(out, "class {}:", class_name)?;
+        write_docstring(&mut out, "    ", &iface.decorators)?;
         writeln!(out, "    def __init__(self, client: BaseClient):")?;
         writeln!(out, "        self._client = client")?;
 
-        for op in &iface.operations {
-            let method = get_http_method(&op.decorators);
-            let op_path = get_route(&op.decorators).unwrap_or_default();
-            let full_path = format!("{}{}", base_path, op_path);
+        for http_op in http_operations(iface, models) {
+            let op = http_op.op;
 
             writeln!(out)?;
             write!(out, "    async def {}(self", op.name.to_case(Case::Snake))?;
 
             // Parameters
-            for param in &op.params {
-                if has_decorator(&param.decorators, "path") {
+            for param in &http_op.params {
+                if param.location == ParamLocation::Path {
                     write!(out, ", {}: str", param.name.to_case(Case::Snake))?;
-                } else if has_decorator(&param.decorators, "body") {
-                    let ty = type_to_python(&param.type_ref, scalars);
-                    write!(out, ", body: {}", ty)?;
-                } else if has_decorator(&param.decorators, "query") {
+                } else {
                     let ty = type_to_python(&param.type_ref, scalars);
-                    if param.optional {
+                    if let Some(default) = &param.default {
+                        write!(
+                            out,
+                            ", {}: {} = {}",
+                            param.name.to_case(Case::Snake),
+                            ty,
+                            value_to_python(default)
+                        )?;
+                    } else if param.optional {
                         write!(
                             out,
                             ", {}: Optional[{}] = None",
@@ -300,30 +1168,25 @@ class BaseClient:
             }
 
             // Extract the actual return type, handling response wrappers
-            let (return_type, _) = extract_return_type(&op.return_type, scalars);
+            let return_type = extract_return_type(&iface.name, &http_op, scalars);
 
             writeln!(out, ") -> {}:", return_type)?;
+            write_docstring(&mut out, "        ", &op.decorators)?;
 
             // Build path with substitutions
-            let mut path_code = format!(r#"        path = "{}""#, full_path);
-            for param in &op.params {
-                if has_decorator(&param.decorators, "path") {
-                    let name = param.name.to_case(Case::Snake);
-                    path_code = format!(
-                        r#"{}
-        path = path.replace("{{{{{}}}}}", str({}))"#,
-                        path_code, param.name, name
-                    );
-                }
+            let mut path_code = format!(r#"        path = "{}""#, http_op.path);
+            for param in http_op.params_at(ParamLocation::Path) {
+                let name = param.name.to_case(Case::Snake);
+                path_code = format!(
+                    r#"{}
+        path = path.replace("{{{}}}", str({}))"#,
+                    path_code, param.name, name
+                );
             }
             writeln!(out, "{}", path_code)?;
 
             // Build query params
-            let query_params: Vec<_> = op
-                .params
-                .iter()
-                .filter(|p| has_decorator(&p.decorators, "query"))
-                .collect();
+            let query_params: Vec<_> = http_op.params_at(ParamLocation::Query).collect();
 
             if !query_params.is_empty() {
                 writeln!(out, "        params = {{}}")?;
@@ -334,49 +1197,147 @@ class BaseClient:
                 }
             }
 
+            // Build headers
+            let header_params: Vec<_> = http_op.params_at(ParamLocation::Header).collect();
+
+            if !header_params.is_empty() {
+                writeln!(out, "        headers = {{}}")?;
+                for param in &header_params {
+                    let name = param.name.to_case(Case::Snake);
+                    let header = header_name(&param.decorators, &param.name);
+                    if param.optional {
+                        writeln!(out, "        if {} is not None:", name)?;
+                        writeln!(out, r#"            headers["{}"] = str({})"#, header, name)?;
+                    } else {
+                        writeln!(out, r#"        headers["{}"] = str({})"#, header, name)?;
+                    }
+                }
+            }
+
+            // Params without a location are sent as the properties of a JSON body
+            let body_properties: Vec<_> = http_op.params_at(ParamLocation::BodyProperty).collect();
+
+            if !body_properties.is_empty() {
+                writeln!(out, "        payload = {{}}")?;
+                for param in &body_properties {
+                    let name = param.name.to_case(Case::Snake);
+                    let value = json
+                        .encode(&param.type_ref, &name)
+                        .unwrap_or_else(|| name.clone());
+                    if param.optional {
+                        writeln!(out, "        if {} is not None:", name)?;
+                        writeln!(out, r#"            payload["{}"] = {}"#, param.name, value)?;
+                    } else {
+                        writeln!(out, r#"        payload["{}"] = {}"#, param.name, value)?;
+                    }
+                }
+            }
+
             // Make request
-            let has_body = op
-                .params
-                .iter()
-                .any(|p| has_decorator(&p.decorators, "body"));
 
-            write!(
-                out,
-                "        result = await self._client._request(\"{}\", path",
-                method
-            )?;
-            if has_body {
-                write!(out, ", json=body.to_dict()")?;
+            // Several success responses are told apart by status, and
+            // response headers are read from the response itself
+            let responses = &http_op.responses;
+            let response_headers = http_op.header_responses().next().is_some();
+            if response_headers {
+                write!(
+                    out,
+                    "        resp = await self._client._send(\"{}\", path",
+                    http_op.method
+                )?;
+            } else if responses.len() > 1 {
+                write!(
+                    out,
+                    "        status, result = await self._client._request_response(\"{}\", path",
+                    http_op.method
+                )?;
+            } else {
+                write!(
+                    out,
+                    "        result = await self._client._request(\"{}\", path",
+                    http_op.method
+                )?;
+            }
+            if let Some(body) = http_op.body() {
+                let name = body.name.to_case(Case::Snake);
+                let type_ref = nullable(&body.type_ref).unwrap_or(&body.type_ref);
+                let value = match json.encode(type_ref, &name) {
+                    Some(value) if body.optional => {
+                        format!("None if {} is None else {}", name, value)
+                    }
+                    Some(value) => value,
+                    None => name,
+                };
+                write!(out, ", json={}", value)?;
+            } else if !body_properties.is_empty() {
+                write!(out, ", json=payload")?;
             }
             if !query_params.is_empty() {
                 write!(out, ", params=params")?;
             }
+            if !header_params.is_empty() {
+                write!(out, ", headers=headers")?;
+            }
+            let errors = error_model_names(&http_op);
+            if !errors.is_empty() {
+                let errors: Vec<_> = errors.iter().map(|e| format!("models.{}", e)).collect();
+                write!(out, ", errors=({},)", errors.join(", "))?;
+            }
             writeln!(out, ")")?;
 
             // Return - extract actual body type from response wrapper
-            let (_, body_type) = extract_return_type(&op.return_type, scalars);
-            if let Some(ty) = body_type {
-                if ty == "None" {
-                    writeln!(out, "        return None")?;
-                } else if is_primitive_type(&ty) {
-                    writeln!(out, "        return result")?;
-                } else if ty.starts_with("List[") {
-                    // Extract inner type from List[X]
-                    let inner = &ty[5..ty.len() - 1];
-                    if is_primitive_type(inner) {
-                        writeln!(out, "        return result")?;
-                    } else {
-                        writeln!(
-                            out,
-                            "        return [{}.from_dict(item) for item in result]",
-                            inner
-                        )?;
-                    }
-                } else {
-                    writeln!(out, "        return {}.from_dict(result)", ty)?;
+            if response_headers {
+                writeln!(out, "        status = resp.status_code")?;
+                writeln!(
+                    out,
+                    "        result = None if status == 204 else resp.json()"
+                )?;
+            }
+            if responses.len() > 1 {
+                for response in responses.iter().filter(|r| r.status.is_some()) {
+                    let status = response.status.unwrap_or_default();
+                    writeln!(out, "        if status == {}:", status)?;
+                    write_response_return(
+                        &mut out,
+                        "            ",
+                        &iface.name,
+                        &http_op,
+                        response,
+                        &json,
+                    )?;
+                }
+                match responses.iter().find(|r| r.status.is_none()) {
+                    Some(response) => write_response_return(
+                        &mut out,
+                        "        ",
+                        &iface.name,
+                        &http_op,
+                        response,
+                        &json,
+                    )?,
+                    None => writeln!(out, "        return result")?,
                 }
+            } else if let Some(response) = responses.first().filter(|_| response_headers) {
+                write_response_return(
+                    &mut out,
+                    "        ",
+                    &iface.name,
+                    &http_op,
+                    response,
+                    &json,
+                )?;
             } else {
-                writeln!(out, "        return result")?;
+                match responses.first() {
+                    Some(response) => write_response_return(
+                        &mut out,
+                        "        ",
+                        &iface.name,
+                        &http_op,
+                        response,
+                        &json,
+                    )?,
+                    None => writeln!(out, "        return None")?,
+                }
             }
         }
     }
@@ -387,7 +1348,7 @@ class BaseClient:
     writeln!(out, "    def __init__(self, *args, **kwargs):")?;
     writeln!(out, "        super().__init__(*args, **kwargs)")?;
 
-    for iface in file.interfaces() {
+    for iface in &collect_services(file) {
         let name = iface.name.to_case(Case::Snake);
         let class_name = format!("{}Client", iface.name);
         writeln!(out, "        self.{} = {}(self)", name, class_name)?;
@@ -396,7 +1357,11 @@ class BaseClient:
     Ok(out)
 }
 
-fn generate_server(file: &TypeSpecFile, scalars: &ScalarMap) -> Result<String, CodegenError> {
+fn generate_server(
+    file: &TypeSpecFile,
+    scalars: &ScalarMap,
+    models: &ModelMap<'_>,
+) -> Result<String, CodegenError> {
     let mut out = String::new();
 
     writeln!(
@@ -410,37 +1375,71 @@ Implement the abstract methods in a subclass.
     )?;
     writeln!(out)?;
     writeln!(out, "from abc import ABC, abstractmethod")?;
-    writeln!(out, "from typing import Any, Optional")?;
+    writeln!(out, "from typing import Any, Dict, Optional, Type, TypeVar")?;
     writeln!(out, "from ..models import *")?;
     writeln!(out, "from ..enums import *")?;
     writeln!(out)?;
+    writeln!(
+        out,
+        r#"
+M = TypeVar("M")
+
+
+class ErrorResponse(Exception):
+    """Raise from a handler to respond with an `@error` model"""
+
+    def __init__(self, error: Any):
+        self.error = error
+        self.status_code: int = error.response_status()
+        super().__init__(f"{{self.status_code}}")
+
+    def to_dict(self) -> Dict[str, Any]:
+        return self.error.to_dict()
 
-    for iface in file.interfaces() {
+
+def parse_body(model: Type[M], data: Dict[str, Any]) -> M:
+    """Build a request body from JSON data.
+
+    Bodies that break the schema's constraints raise `ValidationError`
+    (respond with 422) before they reach a handler.
+    """
+    if not isinstance(data, dict):
+        raise ValidationError("body", "must be a JSON object")
+    body = model.from_dict(data)
+    if hasattr(body, "validate"):
+        body.validate()
+    return body
+"#
+    )?;
+
+    for iface in &collect_services(file) {
         writeln!(out)?;
         writeln!(out, "class {}Handler(ABC):", iface.name)?;
+        write_docstring(&mut out, "    ", &iface.decorators)?;
 
-        for op in &iface.operations {
+        for http_op in http_operations(iface, models) {
+            let op = http_op.op;
             writeln!(out)?;
             writeln!(out, "    @abstractmethod")?;
             write!(out, "    async def {}(self", op.name.to_case(Case::Snake))?;
 
-            for param in &op.params {
+            for param in &http_op.params {
                 let name = param.name.to_case(Case::Snake);
                 let ty = type_to_python(&param.type_ref, scalars);
-                if param.optional {
-                    write!(out, ", {}: Optional[{}]", name, ty)?;
+                if let Some(default) = &param.default {
+                    write!(out, ", {}: {} = {}", name, ty, value_to_python(default))?;
+                } else if param.optional {
+                    write!(out, ", {}: Optional[{}] = None", name, ty)?;
                 } else {
                     write!(out, ", {}: {}", name, ty)?;
                 }
             }
 
-            let return_type = op
-                .return_type
-                .as_ref()
-                .map(|t| type_to_python(t, scalars))
-                .unwrap_or_else(|| "None".to_string());
+            // Errors are raised as `ErrorResponse`, not returned
+            let return_type = extract_return_type(&iface.name, &http_op, scalars);
 
             writeln!(out, ") -> {}:", return_type)?;
+            write_docstring(&mut out, "        ", &op.decorators)?;
             writeln!(out, "        raise NotImplementedError")?;
         }
     }
@@ -448,16 +1447,476 @@ Implement the abstract methods in a subclass.
     Ok(out)
 }
 
-fn generate_init(_package_name: &str) -> Result<String, CodegenError> {
-    Ok(r#""""
+/// Generate the FastAPI app of the server: a router per interface binding
+/// each operation's params and calling its handler, and `create_app`
+/// mounting them with the typed errors and the OpenAPI document.
+fn generate_server_app(
+    file: &TypeSpecFile,
+    scalars: &ScalarMap,
+    models: &ModelMap<'_>,
+    style: PythonModels,
+) -> Result<String, CodegenError> {
+    let mut out = String::new();
+    let services = collect_services(file);
+    let json = JsonConverter::new(file, scalars, models, style);
+
+    writeln!(
+        out,
+        r#""""
+Auto-generated FastAPI app from TypeSpec.
+DO NOT EDIT.
+
+Pass your handlers to `create_app` and serve the app with any ASGI server.
+""""#
+    )?;
+    writeln!(out)?;
+    writeln!(out, "import json")?;
+    writeln!(out, "import pathlib")?;
+    writeln!(out, "from datetime import datetime")?;
+    writeln!(out, "from enum import Enum")?;
+    writeln!(
+        out,
+        "from typing import Annotated, Any, Dict, List, Optional, Type, TypeVar"
+    )?;
+    writeln!(out, "from uuid import UUID")?;
+    writeln!(out)?;
+    writeln!(out, "import fastapi")?;
+    writeln!(out, "import fastapi.responses")?;
+    writeln!(out)?;
+    writeln!(out, "from ..models import *")?;
+    writeln!(out, "from ..models import _datetime, _read")?;
+    writeln!(out, "from ..enums import *")?;
+    writeln!(out, "from . import (")?;
+    writeln!(out, "    ErrorResponse,")?;
+    writeln!(out, "    parse_body,")?;
+    for iface in &services {
+        writeln!(out, "    {}Handler,", iface.name)?;
+    }
+    writeln!(out, ")")?;
+    writeln!(out, "{}", SERVER_APP_RUNTIME)?;
+
+    for iface in &services {
+        let trait_name = format!("{}Handler", iface.name);
+        writeln!(out)?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "def {}_router(handler: {}) -> fastapi.APIRouter:",
+            iface.name.to_case(Case::Snake),
+            trait_name
+        )?;
+        writeln!(out, r#"    """Routes of `{}`""""#, trait_name)?;
+        writeln!(out, "    router = fastapi.APIRouter()")?;
+        for http_op in http_operations(iface, models) {
+            write_route(&mut out, &iface.name, &http_op, &json, models)?;
+        }
+        writeln!(out)?;
+        writeln!(out, "    return router")?;
+    }
+
+    writeln!(out)?;
+    writeln!(out)?;
+    write!(out, "def create_app(")?;
+    if !services.is_empty() {
+        write!(out, "*")?;
+        for iface in &services {
+            write!(
+                out,
+                ", {}: {}Handler",
+                iface.name.to_case(Case::Snake),
+                iface.name
+            )?;
+        }
+    }
+    writeln!(out, ") -> fastapi.FastAPI:")?;
+    writeln!(
+        out,
+        r#"    """The app serving every handler, with its OpenAPI document""""#
+    )?;
+    writeln!(
+        out,
+        r#"    app = fastapi.FastAPI(title=OPENAPI["info"]["title"])"#
+    )?;
+    writeln!(out, "    app.openapi_schema = OPENAPI")?;
+    writeln!(
+        out,
+        "    app.add_exception_handler(ErrorResponse, _error_response)"
+    )?;
+    writeln!(
+        out,
+        "    app.add_exception_handler(ValidationError, _validation_error)"
+    )?;
+    for iface in &services {
+        let name = iface.name.to_case(Case::Snake);
+        writeln!(out, "    app.include_router({}_router({}))", name, name)?;
+    }
+    writeln!(out, "    return app")?;
+
+    Ok(out)
+}
+
+/// Helpers of the FastAPI app: reading JSON bodies, encoding responses and
+/// responding to errors
+const SERVER_APP_RUNTIME: &str = r#"
+M = TypeVar("M")
+
+OPENAPI: Dict[str, Any] = json.loads(
+    (pathlib.Path(__file__).parent / "openapi.json").read_text()
+)
+
+
+def _encode(value: Any) -> Any:
+    """The JSON form of a response body"""
+    if hasattr(value, "to_dict"):
+        return _encode(value.to_dict())
+    if isinstance(value, Enum):
+        return value.value
+    if isinstance(value, datetime):
+        return value.isoformat()
+    if isinstance(value, UUID):
+        return str(value)
+    if isinstance(value, list):
+        return [_encode(item) for item in value]
+    if isinstance(value, dict):
+        return {key: _encode(item) for key, item in value.items()}
+    return value
+
+
+def _respond(
+    status: int, body: Any, headers: Optional[Dict[str, str]] = None
+) -> fastapi.Response:
+    return fastapi.responses.JSONResponse(_encode(body), status_code=status, headers=headers)
+
+
+async def _json(request: fastapi.Request) -> Any:
+    """The JSON body of a request, `None` when it is empty"""
+    raw = await request.body()
+    if not raw:
+        return None
+    try:
+        return json.loads(raw)
+    except ValueError:
+        raise ValidationError("body", "must be valid JSON")
+
+
+async def _object(request: fastapi.Request) -> Dict[str, Any]:
+    """The JSON object holding the properties of an implicit body"""
+    data = await _json(request)
+    if not isinstance(data, dict):
+        raise ValidationError("body", "must be a JSON object")
+    return data
+
+
+def _required(value: Any, field: str) -> Any:
+    if value is None:
+        raise ValidationError(field, "is required")
+    return value
+
+
+def _decode(model: Type[M], value: Any) -> Optional[M]:
+    """Build a model from JSON data"""
+    if value is None:
+        return None
+    return parse_body(model, value)
+
+
+def _decode_list(model: Type[M], value: Any) -> Optional[List[M]]:
+    """Build a list of models from JSON data, checking each item"""
+    if value is None:
+        return None
+    items = []
+    for i, item in enumerate(_list(value)):
+        try:
+            items.append(parse_body(model, item))
+        except ValidationError as e:
+            path = f"[{i}]" if e.field == "body" else f"[{i}].{e.field}"
+            raise ValidationError(path, e.message) from None
+    return items
+
+
+def _list(value: Any) -> List[Any]:
+    if not isinstance(value, list):
+        raise ValidationError("body", "must be a JSON array")
+    return value
+
+
+def _str(value: Any) -> str:
+    if not isinstance(value, str):
+        raise TypeError("not a string")
+    return value
+
+
+def _int(value: Any) -> int:
+    # `bool` is an `int` in Python, not in JSON
+    if isinstance(value, bool) or not isinstance(value, int):
+        raise TypeError("not an integer")
+    return value
+
+
+def _float(value: Any) -> float:
+    if isinstance(value, bool) or not isinstance(value, (int, float)):
+        raise TypeError("not a number")
+    return float(value)
+
+
+def _bool(value: Any) -> bool:
+    if not isinstance(value, bool):
+        raise TypeError("not a boolean")
+    return value
+
+
+async def _error_response(request: fastapi.Request, exc: ErrorResponse) -> fastapi.Response:
+    return _respond(exc.status_code, exc.to_dict())
+
+
+async def _validation_error(request: fastapi.Request, exc: ValidationError) -> fastapi.Response:
+    return _respond(422, {"field": exc.field, "message": exc.message})"#;
+
+/// The server helper decoding a body holding `nested`, by its declared type
+fn decoder(nested: NestedModel<'_>) -> &'static str {
+    if nested.array {
+        "_decode_list"
+    } else {
+        "_decode"
+    }
+}
+
+/// Write the route of an operation inside its interface's router: FastAPI
+/// binds its path, query and header params, the body is decoded into the
+/// generated dataclasses, and the result is sent with its status
+fn write_route(
+    out: &mut String,
+    iface_name: &str,
+    http_op: &HttpOperation<'_>,
+    json: &JsonConverter<'_>,
+    models: &ModelMap<'_>,
+) -> Result<(), CodegenError> {
+    let (op, scalars) = (http_op.op, json.scalars);
+    let fn_name = op.name.to_case(Case::Snake);
+
+    // Path params are named in snake case, like the arguments binding them
+    let mut path = http_op.path.clone();
+    for name in path_params(&http_op.path) {
+        path = path.replace(
+            &format!("{{{}}}", name),
+            &format!("{{{}}}", name.to_case(Case::Snake)),
+        );
+    }
+
+    let mut args = Vec::new();
+    if http_op.has_body() {
+        args.push("request: fastapi.Request".to_string());
+    }
+    let mut bound = Vec::new();
+    for param in &http_op.params {
+        let name = param.name.to_case(Case::Snake);
+        let ty = type_to_python(&param.type_ref, scalars);
+        let binding = match param.location {
+            ParamLocation::Path => "fastapi.Path()".to_string(),
+            ParamLocation::Query if name != param.name => {
+                format!("fastapi.Query(alias=\"{}\")", param.name)
+            }
+            ParamLocation::Query => "fastapi.Query()".to_string(),
+            ParamLocation::Header => format!(
+                "fastapi.Header(alias=\"{}\")",
+                header_name(&param.decorators, &param.name)
+            ),
+            ParamLocation::Body | ParamLocation::BodyProperty => continue,
+        };
+        let arg = if let Some(default) = &param.default {
+            format!(
+                "{}: Annotated[{}, {}] = {}",
+                name,
+                ty,
+                binding,
+                value_to_python(default)
+            )
+        } else if param.optional {
+            format!("{}: Annotated[Optional[{}], {}] = None", name, ty, binding)
+        } else {
+            format!("{}: Annotated[{}, {}]", name, ty, binding)
+        };
+        bound.push(arg);
+    }
+    if !bound.is_empty() {
+        args.push("*".to_string());
+        args.extend(bound);
+    }
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        r#"    @router.{}("{}")"#,
+        http_op.method.to_lowercase(),
+        path
+    )?;
+    writeln!(
+        out,
+        "    async def handle_{}({}) -> fastapi.Response:",
+        fn_name,
+        args.join(", ")
+    )?;
+
+    // Bodies are decoded into the generated dataclasses and validated
+    if let Some(body) = http_op.body() {
+        let mut value = "await _json(request)".to_string();
+        if !body.optional && body.default.is_none() {
+            value = format!("_required({}, \"{}\")", value, body.name);
+        }
+        if let Some(nested) = nested_model(&body.type_ref, models) {
+            value = format!("{}({}, {})", decoder(nested), nested.model.name, value);
+        }
+        writeln!(
+            out,
+            "        {} = {}",
+            body.name.to_case(Case::Snake),
+            value
+        )?;
+    }
+    // Properties of implicit bodies are converted like the fields of a model
+    let body_properties: Vec<_> = http_op.params_at(ParamLocation::BodyProperty).collect();
+    if !body_properties.is_empty() {
+        writeln!(out, "        data = await _object(request)")?;
+        for param in &body_properties {
+            let decoded = match nested_model(&param.type_ref, models) {
+                Some(nested) => Some(format!("{}({}, value)", decoder(nested), nested.model.name)),
+                None => json.check(
+                    nullable(&param.type_ref).unwrap_or(&param.type_ref),
+                    "value",
+                ),
+            };
+            let required = !param.optional && param.default.is_none();
+            let default = param.default.as_ref().map(value_to_python);
+            writeln!(
+                out,
+                "        {} = {}",
+                param.name.to_case(Case::Snake),
+                JsonConverter::read("data", &param.name, decoded, required, default)
+            )?;
+        }
+    }
+
+    let call_args: Vec<_> = http_op
+        .params
+        .iter()
+        .map(|p| {
+            let name = p.name.to_case(Case::Snake);
+            format!("{}={}", name, name)
+        })
+        .collect();
+    let call = format!("await handler.{}({})", fn_name, call_args.join(", "));
+
+    match http_op.responses.as_slice() {
+        [response] => {
+            let status = response.status.unwrap_or(200);
+            if !response.headers.is_empty() {
+                writeln!(out, "        result = {}", call)?;
+                writeln!(
+                    out,
+                    "        return _respond({}, result.body, result.headers.to_headers())",
+                    status
+                )?;
+            } else if response.body.is_none() {
+                writeln!(out, "        {}", call)?;
+                writeln!(
+                    out,
+                    "        return fastapi.Response(status_code={})",
+                    status
+                )?;
+            } else {
+                writeln!(out, "        result = {}", call)?;
+                writeln!(out, "        return _respond({}, result)", status)?;
+            }
+        }
+        responses => {
+            // The status of the response is told by the type of its
+            // headers, or else by the type of its body
+            writeln!(out, "        result = {}", call)?;
+            for response in http_op.header_responses() {
+                writeln!(
+                    out,
+                    "        if isinstance(result, WithHeaders) and isinstance(result.headers, {}):",
+                    http_op.headers_name(iface_name, response)
+                )?;
+                writeln!(
+                    out,
+                    "            return _respond({}, result.body, result.headers.to_headers())",
+                    response.status.unwrap_or(200)
+                )?;
+            }
+            let mut fallback = None;
+            for response in responses.iter().filter(|r| r.headers.is_empty()) {
+                let status = response.status.unwrap_or(200);
+                let Some(body) = &response.body else {
+                    writeln!(out, "        if result is None:")?;
+                    writeln!(
+                        out,
+                        "            return fastapi.Response(status_code={})",
+                        status
+                    )?;
+                    continue;
+                };
+                let ty = type_to_python(body, scalars);
+                if is_primitive_type(&ty) || ty.contains('[') || ty.contains(" | ") {
+                    fallback.get_or_insert(status);
+                    continue;
+                }
+                writeln!(out, "        if isinstance(result, {}):", ty)?;
+                writeln!(out, "            return _respond({}, result)", status)?;
+            }
+            writeln!(
+                out,
+                "        return _respond({}, result)",
+                fallback.unwrap_or(200)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn generate_init(_package_name: &str, side: Side) -> Result<String, CodegenError> {
+    let mut out = r#""""
 Auto-generated from TypeSpec.
-""""
+"""
 
 from .models import *
 from .enums import *
-from .client import Client, ApiError
 "#
-    .to_string())
+    .to_string();
+    if matches!(side, Side::Client | Side::Both) {
+        out.push_str("from .client import Client, ApiError\n");
+    }
+    Ok(out)
+}
+
+/// Python expression of a default value, e.g. `"desc"` or `Status.ACTIVE`
+fn value_to_python(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
+        Value::Int(n) => n.to_string(),
+        Value::Float(n) => format!("{:?}", n),
+        Value::Bool(true) => "True".to_string(),
+        Value::Bool(false) => "False".to_string(),
+        Value::Ident(name) => name.clone(),
+        Value::QualifiedIdent(parts) => match parts.as_slice() {
+            [.., enum_name, member] => {
+                format!("{}.{}", enum_name, member.to_case(Case::ScreamingSnake))
+            }
+            _ => parts.join("."),
+        },
+        Value::Array(items) => {
+            let items: Vec<_> = items.iter().map(value_to_python).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Object(fields) => {
+            let mut fields: Vec<_> = fields
+                .iter()
+                .map(|(k, v)| format!("\"{}\": {}", k, value_to_python(v)))
+                .collect();
+            fields.sort();
+            format!("{{{}}}", fields.join(", "))
+        }
+    }
 }
 
 /// Convert TypeSpec type to Python type string
@@ -466,7 +1925,9 @@ pub fn type_to_python(type_ref: &TypeRef, scalars: &ScalarMap) -> String {
         TypeRef::Builtin(name) => builtin_to_python(name),
         TypeRef::Named(name) => {
             // Check if this is a custom scalar type
-            if let Some(base_type) = scalars.get(name) {
+            if name == "uuid" {
+                "UUID".to_string()
+            } else if let Some(base_type) = scalars.get(name) {
                 builtin_to_python(base_type)
             } else {
                 name.clone()
@@ -532,93 +1993,112 @@ fn builtin_to_python(name: &str) -> String {
     }
 }
 
-fn get_description(decorators: &[Decorator]) -> Option<String> {
-    decorators
-        .iter()
-        .find(|d| d.name == "doc")
-        .and_then(|d| d.get_string_arg(0).map(|s| s.to_string()))
-}
+/// Write the description of an item as a docstring
+fn write_docstring(out: &mut String, indent: &str, decorators: &[Decorator]) -> std::fmt::Result {
+    let Some(desc) = description(decorators) else {
+        return Ok(());
+    };
+    let mut text = desc.replace('\\', "\\\\").replace("\"\"\"", "\\\"\\\"\\\"");
+    if text.ends_with('"') {
+        text.pop();
+        text.push_str("\\\"");
+    }
 
-fn get_route(decorators: &[Decorator]) -> Option<String> {
-    decorators
-        .iter()
-        .find(|d| d.name == "route")
-        .and_then(|d| d.get_string_arg(0).map(|s| s.to_string()))
+    let mut lines = text.lines();
+    let first = lines.next().unwrap_or_default();
+    let rest: Vec<_> = lines.collect();
+    if rest.is_empty() {
+        return writeln!(out, r#"{}"""{}""""#, indent, first);
+    }
+    writeln!(out, r#"{}"""{}"#, indent, first)?;
+    for line in rest {
+        if line.is_empty() {
+            writeln!(out)?;
+        } else {
+            writeln!(out, "{}{}", indent, line)?;
+        }
+    }
+    writeln!(out, r#"{}""""#, indent)
 }
 
-fn get_http_method(decorators: &[Decorator]) -> &'static str {
-    for d in decorators {
-        match d.name.as_str() {
-            "get" => return "GET",
-            "post" => return "POST",
-            "put" => return "PUT",
-            "patch" => return "PATCH",
-            "delete" => return "DELETE",
-            _ => {}
+/// Extract the return type of an operation from its response wrappers like
+/// `{ @statusCode _: 200; @body body: T } | ApiError`: the body type, or the
+/// union of the bodies of several success responses. A body sent with
+/// `@header`s is returned `WithHeaders`.
+fn extract_return_type(
+    iface_name: &str,
+    http_op: &HttpOperation<'_>,
+    scalars: &ScalarMap,
+) -> String {
+    let mut types: Vec<String> = Vec::new();
+    for response in &http_op.responses {
+        let mut ty = body_type(response, scalars);
+        if !response.headers.is_empty() {
+            ty = format!(
+                "WithHeaders[{}, {}]",
+                ty,
+                http_op.headers_name(iface_name, response)
+            );
+        }
+        if !types.contains(&ty) {
+            types.push(ty);
         }
     }
-    "GET"
+    if types.is_empty() {
+        return "None".to_string();
+    }
+    types.join(" | ")
 }
 
-fn has_decorator(decorators: &[Decorator], name: &str) -> bool {
-    decorators.iter().any(|d| d.name == name)
+/// The Python type of a response body, `None` for no content
+fn body_type(response: &Response<'_>, scalars: &ScalarMap) -> String {
+    response
+        .body
+        .as_ref()
+        .map(|body| type_to_python(body, scalars))
+        .unwrap_or_else(|| "None".to_string())
 }
 
-/// Extract return type from response wrappers like `{ @statusCode: 200; @body body: T } | ApiError`
-/// Returns (display_type, body_type) where body_type is the actual type to deserialize
-fn extract_return_type(
-    type_ref: &Option<TypeRef>,
-    scalars: &ScalarMap,
-) -> (String, Option<String>) {
-    match type_ref {
-        None => ("None".to_string(), None),
-        Some(TypeRef::Union(variants)) => {
-            // Look for response wrapper with @body
-            for variant in variants {
-                if let TypeRef::AnonymousModel(props) = variant {
-                    // Find @body property
-                    for prop in props {
-                        if has_decorator(&prop.decorators, "body") {
-                            let body_type = type_to_python(&prop.type_ref, scalars);
-                            return (body_type.clone(), Some(body_type));
-                        }
-                    }
-                    // Check for statusCode: 204 (no content)
-                    for prop in props {
-                        if has_decorator(&prop.decorators, "statusCode") {
-                            if let Some(Value::Int(204)) = &prop.default {
-                                return ("None".to_string(), Some("None".to_string()));
-                            }
-                            if let TypeRef::IntLiteral(204) = &prop.type_ref {
-                                return ("None".to_string(), Some("None".to_string()));
-                            }
-                        }
-                    }
-                }
-            }
-            // No body found, return generic type
-            let types: Vec<_> = variants
-                .iter()
-                .map(|v| type_to_python(v, scalars))
-                .collect();
-            let combined = types.join(" | ");
-            (combined.clone(), None)
-        }
-        Some(TypeRef::AnonymousModel(props)) => {
-            // Single response wrapper
-            for prop in props {
-                if has_decorator(&prop.decorators, "body") {
-                    let body_type = type_to_python(&prop.type_ref, scalars);
-                    return (body_type.clone(), Some(body_type));
-                }
+/// Write the `return` of a client method for one of its responses, decoding
+/// its JSON `result`, with the `@header`s of `resp` when it has any
+fn write_response_return(
+    out: &mut String,
+    indent: &str,
+    iface_name: &str,
+    http_op: &HttpOperation<'_>,
+    response: &Response<'_>,
+    json: &JsonConverter<'_>,
+) -> std::fmt::Result {
+    let body = match &response.body {
+        Some(_) if body_type(response, json.scalars) == "None" => "None".to_string(),
+        Some(body) => json
+            .decode(body, "result")
+            .unwrap_or_else(|| "result".to_string()),
+        None => "None".to_string(),
+    };
+    if response.headers.is_empty() {
+        return writeln!(out, "{}return {}", indent, body);
+    }
+    writeln!(
+        out,
+        "{}return WithHeaders({}, {}.from_headers(resp.headers))",
+        indent,
+        body,
+        http_op.headers_name(iface_name, response)
+    )
+}
+
+/// Names of the `@error` models an operation responds with, fixed statuses first
+fn error_model_names(http_op: &HttpOperation<'_>) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for response in &http_op.errors {
+        if let Some(model) = response.error {
+            if !names.contains(&model.name) {
+                names.push(model.name.clone());
             }
-            ("Any".to_string(), None)
-        }
-        Some(tr) => {
-            let ty = type_to_python(tr, scalars);
-            (ty.clone(), Some(ty))
         }
     }
+    names
 }
 
 /// Check if a Python type is primitive (doesn't need from_dict)
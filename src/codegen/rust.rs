@@ -1,12 +1,22 @@
 //! Rust Code Generator
 
 use crate::ast::*;
-use crate::codegen::{
-    build_model_map, build_scalar_map, resolve_properties, CodegenError, ModelMap, ScalarMap, Side,
+use crate::codegen::validation::{
+    build_constraint_map, nested_model, validated_models, Check, ConstraintMap, Constraints,
+};
+use crate::codegen::{description, CodegenError, Side};
+use crate::semantic::http::{
+    header_name, http_operations, is_error_model, is_metadata, path_params, status_literal,
+    status_name, status_property, HttpOperation, ParamLocation, Response,
+};
+use crate::semantic::inheritance::lookup_model;
+use crate::semantic::{
+    build_model_map, build_scalar_map, collect_services, resolve_properties, ModelMap, ScalarMap,
 };
 use convert_case::{Case, Casing};
+use std::borrow::Cow;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 use std::fs;
 use std::path::Path;
@@ -53,12 +63,20 @@ pub fn generate(
     let mut generated = Vec::new();
     let scalars = build_scalar_map(file);
     let models = build_model_map(file);
+    let constraints = build_constraint_map(file);
 
     let src_dir = output_dir.join("src");
     fs::create_dir_all(&src_dir)?;
 
     // Generate Cargo.toml
-    let cargo_content = generate_cargo_toml(package_name, side)?;
+    let uses_regex = file.models().any(|m| {
+        resolve_properties(m, &models).iter().any(|p| {
+            Constraints::of(&p.decorators, &p.type_ref, &constraints)
+                .pattern
+                .is_some()
+        })
+    });
+    let cargo_content = generate_cargo_toml(package_name, side, uses_regex)?;
     let cargo_path = output_dir.join("Cargo.toml");
     fs::write(&cargo_path, cargo_content)?;
     generated.push(cargo_path.display().to_string());
@@ -70,7 +88,7 @@ pub fn generate(
     generated.push(lib_path.display().to_string());
 
     // Generate models
-    let models_content = generate_models(file, &scalars, &models)?;
+    let models_content = generate_models(file, &scalars, &constraints, &models)?;
     let models_path = src_dir.join("models.rs");
     fs::write(&models_path, models_content)?;
     generated.push(models_path.display().to_string());
@@ -83,7 +101,7 @@ pub fn generate(
 
     // Generate client
     if matches!(side, Side::Client | Side::Both) {
-        let client_content = generate_client(file, &scalars)?;
+        let client_content = generate_client(file, &scalars, &models)?;
         let client_path = src_dir.join("client.rs");
         fs::write(&client_path, client_content)?;
         generated.push(client_path.display().to_string());
@@ -91,7 +109,7 @@ pub fn generate(
 
     // Generate server
     if matches!(side, Side::Server | Side::Both) {
-        let server_content = generate_server(file, &scalars)?;
+        let server_content = generate_server(file, &scalars, &constraints, &models)?;
         let server_path = src_dir.join("server.rs");
         fs::write(&server_path, server_content)?;
         generated.push(server_path.display().to_string());
@@ -100,7 +118,11 @@ pub fn generate(
     Ok(generated)
 }
 
-fn generate_cargo_toml(package_name: &str, side: Side) -> Result<String, CodegenError> {
+fn generate_cargo_toml(
+    package_name: &str,
+    side: Side,
+    uses_regex: bool,
+) -> Result<String, CodegenError> {
     let mut out = String::new();
 
     writeln!(out, "[package]")?;
@@ -124,6 +146,10 @@ fn generate_cargo_toml(package_name: &str, side: Side) -> Result<String, Codegen
     )?;
     writeln!(out, r#"thiserror = "2""#)?;
 
+    if uses_regex {
+        writeln!(out, r#"regex = "1""#)?;
+    }
+
     if matches!(side, Side::Client | Side::Both) {
         writeln!(
             out,
@@ -163,10 +189,12 @@ fn generate_lib(side: Side) -> Result<String, CodegenError> {
 fn generate_models(
     file: &TypeSpecFile,
     scalars: &ScalarMap,
+    constraints: &ConstraintMap,
     models: &ModelMap<'_>,
 ) -> Result<String, CodegenError> {
     let mut out = String::new();
     let ctx = CodegenContext::new();
+    let validated = validated_models(file, models, constraints);
 
     writeln!(out, "//! Auto-generated models from TypeSpec.")?;
     writeln!(out, "//! DO NOT EDIT.")?;
@@ -180,20 +208,123 @@ fn generate_models(
     writeln!(out, "use uuid::Uuid;")?;
     writeln!(out)?;
 
+    // Validation runtime
+    writeln!(
+        out,
+        r#"
+/// A value that breaks a constraint of the TypeSpec schema
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{{field}}: {{message}}")]
+pub struct ValidationError {{
+    /// Path of the offending field, e.g. `items[0].title`
+    pub field: String,
+    pub message: String,
+}}
+
+impl ValidationError {{
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {{
+        Self {{
+            field: field.into(),
+            message: message.into(),
+        }}
+    }}
+
+    /// Prefix the field with the path of the value holding it
+    pub fn at(self, parent: &str) -> Self {{
+        Self {{
+            field: format!("{{}}.{{}}", parent, self.field),
+            ..self
+        }}
+    }}
+}}
+
+/// Check a value against the constraints of the TypeSpec schema
+pub trait Validate {{
+    fn validate(&self) -> Result<(), ValidationError> {{
+        Ok(())
+    }}
+}}
+
+/// A list is valid when each of its items is, e.g. `[0].title`
+impl<T: Validate> Validate for Vec<T> {{
+    fn validate(&self) -> Result<(), ValidationError> {{
+        for (i, item) in self.iter().enumerate() {{
+            item.validate().map_err(|e| e.at(&format!("[{{}}]", i)))?;
+        }}
+        Ok(())
+    }}
+}}
+
+/// An `@error` model, sent with its HTTP status rather than in the body
+pub trait ErrorResponse: Sized {{
+    /// Status code to respond with
+    fn status(&self) -> u16;
+
+    /// Decode the body of an error response, if it is this error
+    fn from_response(status: u16, body: &serde_json::Value) -> Option<Self>;
+}}
+
+/// One of several success responses, decoded by its status
+pub trait FromResponse: Sized {{
+    /// Decode the body of a response, with its headers looked up by name
+    fn from_response(
+        status: u16,
+        get: impl Fn(&str) -> Option<String>,
+        body: serde_json::Value,
+    ) -> Result<Self, serde_json::Error>;
+}}
+
+/// A response body with the `@header`s sent along with it. Serialized as
+/// its body alone.
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct WithHeaders<T, H> {{
+    pub body: T,
+    #[serde(skip)]
+    pub headers: H,
+}}
+
+/// The typed `@header`s of a response
+pub trait ResponseHeaders: Sized {{
+    /// Read the headers, looked up by name. Fails with the name of a missing
+    /// or invalid header.
+    fn from_headers(get: impl Fn(&str) -> Option<String>) -> Result<Self, String>;
+
+    /// The headers to send, by name
+    fn to_headers(&self) -> Vec<(&'static str, String)>;
+}}
+
+/// Operations without `@error` models have no typed errors
+impl ErrorResponse for () {{
+    fn status(&self) -> u16 {{
+        500
+    }}
+
+    fn from_response(_status: u16, _body: &serde_json::Value) -> Option<Self> {{
+        None
+    }}
+}}"#
+    )?;
+
     // First pass: collect all structs and inline enums
     let mut struct_defs = String::new();
 
     for model in file.models() {
         // Skip generic models - they need special handling
         if !model.type_params.is_empty() {
-            write_generic_model(&mut struct_defs, model, scalars, models)?;
+            write_generic_model(
+                &mut struct_defs,
+                model,
+                scalars,
+                constraints,
+                models,
+                &validated,
+            )?;
             continue;
         }
 
         writeln!(struct_defs)?;
-        if let Some(desc) = get_description(&model.decorators) {
-            writeln!(struct_defs, "/// {}", desc)?;
-        }
+        write_doc(&mut struct_defs, "", &model.decorators)?;
         writeln!(
             struct_defs,
             "#[derive(Debug, Clone, Serialize, Deserialize)]"
@@ -204,7 +335,7 @@ fn generate_models(
         // Resolve spread references and get all properties
         let all_properties = resolve_properties(model, models);
 
-        for prop in all_properties {
+        for prop in &all_properties {
             let rust_type = type_to_rust_with_context(
                 &prop.type_ref,
                 prop.optional,
@@ -215,7 +346,15 @@ fn generate_models(
             );
             let name = prop.name.to_case(Case::Snake);
 
-            if prop.optional {
+            // The status of a response is not part of its body
+            if status_literal(prop).is_some() {
+                continue;
+            }
+
+            write_doc(&mut struct_defs, "    ", &prop.decorators)?;
+            if is_metadata(prop) {
+                writeln!(struct_defs, "    #[serde(skip)]")?;
+            } else if prop.optional {
                 writeln!(
                     struct_defs,
                     "    #[serde(skip_serializing_if = \"Option::is_none\")]"
@@ -233,6 +372,35 @@ fn generate_models(
         }
 
         writeln!(struct_defs, "}}")?;
+        write_validate_impl(
+            &mut struct_defs,
+            model,
+            &all_properties,
+            constraints,
+            models,
+            &validated,
+        )?;
+        if is_error_model(model) {
+            write_error_response_impl(&mut struct_defs, model, models)?;
+        }
+    }
+
+    // Responses of operations with several success responses or with
+    // `@header`s, and errors of operations with several `@error` models
+    for iface in &collect_services(file) {
+        for http_op in http_operations(iface, models) {
+            let op = http_op.op;
+            for response in http_op.header_responses() {
+                write_response_headers(&mut struct_defs, &iface.name, &http_op, response, scalars)?;
+            }
+            if http_op.responses.len() > 1 {
+                write_response_enum(&mut struct_defs, &iface.name, &http_op, scalars)?;
+            }
+            let errors = error_models(&http_op);
+            if errors.len() > 1 {
+                write_error_enum(&mut struct_defs, &iface.name, op, &errors)?;
+            }
+        }
     }
 
     // Generate inline enums first
@@ -261,14 +429,14 @@ fn write_generic_model(
     out: &mut String,
     model: &Model,
     scalars: &ScalarMap,
+    constraints: &ConstraintMap,
     models: &ModelMap<'_>,
+    validated: &HashSet<String>,
 ) -> Result<(), CodegenError> {
     let ctx = CodegenContext::new();
 
     writeln!(out)?;
-    if let Some(desc) = get_description(&model.decorators) {
-        writeln!(out, "/// {}", desc)?;
-    }
+    write_doc(out, "", &model.decorators)?;
     writeln!(out, "#[derive(Debug, Clone, Serialize, Deserialize)]")?;
     writeln!(out, "#[serde(rename_all = \"camelCase\")]")?;
 
@@ -278,7 +446,7 @@ fn write_generic_model(
 
     let all_properties = resolve_properties(model, models);
 
-    for prop in all_properties {
+    for prop in &all_properties {
         let rust_type = type_to_rust_with_context(
             &prop.type_ref,
             prop.optional,
@@ -289,7 +457,15 @@ fn write_generic_model(
         );
         let name = prop.name.to_case(Case::Snake);
 
-        if prop.optional {
+        // The status of a response is not part of its body
+        if status_literal(prop).is_some() {
+            continue;
+        }
+
+        write_doc(out, "    ", &prop.decorators)?;
+        if is_metadata(prop) {
+            writeln!(out, "    #[serde(skip)]")?;
+        } else if prop.optional {
             writeln!(
                 out,
                 "    #[serde(skip_serializing_if = \"Option::is_none\")]"
@@ -306,135 +482,883 @@ fn write_generic_model(
     }
 
     writeln!(out, "}}")?;
+    write_validate_impl(out, model, &all_properties, constraints, models, validated)?;
     Ok(())
 }
 
-fn generate_enums(file: &TypeSpecFile) -> Result<String, CodegenError> {
-    let mut out = String::new();
+/// Write the `Validate` impl of a model: a check per constraint and a
+/// `validate()` call per property holding a validated model. Models without
+/// either get the trait's default, which accepts every value.
+fn write_validate_impl(
+    out: &mut String,
+    model: &Model,
+    properties: &[Cow<'_, Property>],
+    constraints: &ConstraintMap,
+    models: &ModelMap<'_>,
+    validated: &HashSet<String>,
+) -> Result<(), CodegenError> {
+    let header = if model.type_params.is_empty() {
+        format!("impl Validate for {}", model.name)
+    } else {
+        let type_params = model.type_params.join(", ");
+        format!(
+            "impl<{}> Validate for {}<{}>",
+            type_params, model.name, type_params
+        )
+    };
 
-    writeln!(out, "//! Auto-generated enums from TypeSpec.")?;
-    writeln!(out, "//! DO NOT EDIT.")?;
-    writeln!(out)?;
-    writeln!(out, "use serde::{{Deserialize, Serialize}};")?;
     writeln!(out)?;
+    if !validated.contains(&model.name) {
+        writeln!(out, "{} {{}}", header)?;
+        return Ok(());
+    }
 
-    for enum_def in file.enums() {
-        writeln!(out)?;
-        writeln!(
-            out,
-            "#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]"
-        )?;
-        writeln!(out, "pub enum {} {{", enum_def.name)?;
-
-        for member in &enum_def.members {
-            // Get the serialization value - either explicit or snake_case of name
-            let value = member
-                .value
-                .as_ref()
-                .map(|v| match v {
-                    Value::String(s) => s.clone(),
-                    _ => member.name.to_case(Case::Snake),
-                })
-                .unwrap_or_else(|| member.name.to_case(Case::Snake));
+    writeln!(out, "{} {{", header)?;
+    write_validate_fn(out, properties, constraints, models, validated)?;
+    writeln!(out, "}}")?;
+    Ok(())
+}
 
-            let variant = member.name.to_case(Case::Pascal);
+/// Whether `validate()` checks a property: it has constraints or holds a
+/// validated model
+fn is_checked(
+    prop: &Property,
+    constraints: &ConstraintMap,
+    models: &ModelMap<'_>,
+    validated: &HashSet<String>,
+) -> bool {
+    !Constraints::of(&prop.decorators, &prop.type_ref, constraints)
+        .checks()
+        .is_empty()
+        || nested_model(&prop.type_ref, models)
+            .is_some_and(|nested| validated.contains(&nested.model.name))
+}
 
-            // Always add rename attribute for explicit serialization
-            writeln!(out, r#"    #[serde(rename = "{}")]"#, value)?;
-            writeln!(out, "    {},", variant)?;
+/// Write the `validate()` of a struct with `properties` as its fields
+fn write_validate_fn(
+    out: &mut String,
+    properties: &[Cow<'_, Property>],
+    constraints: &ConstraintMap,
+    models: &ModelMap<'_>,
+    validated: &HashSet<String>,
+) -> Result<(), CodegenError> {
+    writeln!(
+        out,
+        "    fn validate(&self) -> Result<(), ValidationError> {{"
+    )?;
+    for prop in properties {
+        if !is_checked(prop, constraints, models, validated) {
+            continue;
         }
+        let checks = Constraints::of(&prop.decorators, &prop.type_ref, constraints);
+        let nested = nested_model(&prop.type_ref, models)
+            .filter(|nested| validated.contains(&nested.model.name));
 
-        writeln!(out, "}}")?;
-    }
+        let name = prop.name.to_case(Case::Snake);
+        let field = if is_rust_keyword(&name) {
+            format!("r#{}", name)
+        } else {
+            name
+        };
+        let optional = prop.optional || matches!(prop.type_ref, TypeRef::Optional(_));
 
-    Ok(out)
+        // Checks read the field through `value: &T`
+        let indent = if optional {
+            writeln!(out, "        if let Some(value) = &self.{} {{", field)?;
+            "            "
+        } else {
+            writeln!(out, "        let value = &self.{};", field)?;
+            "        "
+        };
+        for check in checks.checks() {
+            write_check(out, indent, &prop.name, &check)?;
+        }
+        if let Some(nested) = nested {
+            if nested.array {
+                writeln!(
+                    out,
+                    "{}for (i, item) in value.iter().enumerate() {{",
+                    indent
+                )?;
+                writeln!(
+                    out,
+                    r#"{}    item.validate().map_err(|e| e.at(&format!("{}[{{}}]", i)))?;"#,
+                    indent, prop.name
+                )?;
+                writeln!(out, "{}}}", indent)?;
+            } else {
+                writeln!(
+                    out,
+                    r#"{}value.validate().map_err(|e| e.at("{}"))?;"#,
+                    indent, prop.name
+                )?;
+            }
+        }
+        if optional {
+            writeln!(out, "        }}")?;
+        }
+    }
+    writeln!(out, "        Ok(())")?;
+    writeln!(out, "    }}")?;
+    Ok(())
 }
 
-fn generate_client(file: &TypeSpecFile, scalars: &ScalarMap) -> Result<String, CodegenError> {
-    let mut out = String::new();
+/// Write the `ErrorResponse` impl of an `@error` model. A literal
+/// `@statusCode` fixes the status; a `@statusCode` field is read for the
+/// response and set from the status when decoding.
+fn write_error_response_impl(
+    out: &mut String,
+    model: &Model,
+    models: &ModelMap<'_>,
+) -> Result<(), CodegenError> {
+    if !model.type_params.is_empty() {
+        return Ok(());
+    }
+    let status_prop = status_property(model, models);
+    let literal = status_prop.as_ref().and_then(status_literal);
+    let field = status_prop
+        .as_ref()
+        .filter(|p| literal.is_none() && is_integer(&p.type_ref))
+        .map(|p| (p.name.to_case(Case::Snake), p.optional));
 
-    writeln!(out, "//! Auto-generated API client from TypeSpec.")?;
-    writeln!(out, "//! DO NOT EDIT.")?;
-    writeln!(out)?;
-    writeln!(out, "#![allow(unused_imports)]")?;
     writeln!(out)?;
-    writeln!(out, "use crate::models::*;")?;
-    writeln!(out, "use crate::enums::*;")?;
-    writeln!(out, "use reqwest::{{Client, Method}};")?;
-    writeln!(out, "use serde::{{de::DeserializeOwned, Serialize}};")?;
-    writeln!(out, "use thiserror::Error;")?;
-    writeln!(out, "use uuid::Uuid;")?;
+    writeln!(out, "impl ErrorResponse for {} {{", model.name)?;
+    writeln!(out, "    fn status(&self) -> u16 {{")?;
+    match (&literal, &field) {
+        (Some(code), _) => writeln!(out, "        {}", code)?,
+        (None, Some((name, false))) => {
+            writeln!(out, "        u16::try_from(self.{}).unwrap_or(500)", name)?
+        }
+        (None, Some((name, true))) => writeln!(
+            out,
+            "        self.{}.and_then(|s| u16::try_from(s).ok()).unwrap_or(500)",
+            name
+        )?,
+        (None, None) => writeln!(out, "        500")?,
+    }
+    writeln!(out, "    }}")?;
     writeln!(out)?;
-
-    // Error type
     writeln!(
         out,
-        r#"
-#[derive(Debug, Error)]
-pub enum ApiError {{
-    #[error("HTTP error: {{0}}")]
-    Http(#[from] reqwest::Error),
-
-    #[error("API error: {{status}} - {{message}}")]
-    Api {{ status: u16, code: String, message: String }},
-}}
-"#
+        "    fn from_response(status: u16, body: &serde_json::Value) -> Option<Self> {{"
     )?;
+    match (&literal, &field) {
+        (Some(code), _) => {
+            writeln!(out, "        if status != {} {{", code)?;
+            writeln!(out, "            return None;")?;
+            writeln!(out, "        }}")?;
+            writeln!(out, "        Self::deserialize(body).ok()")?;
+        }
+        (None, Some((name, optional))) => {
+            writeln!(
+                out,
+                "        let mut error = Self::deserialize(body).ok()?;"
+            )?;
+            if *optional {
+                writeln!(out, "        error.{} = Some(status as _);", name)?;
+            } else {
+                writeln!(out, "        error.{} = status as _;", name)?;
+            }
+            writeln!(out, "        Some(error)")?;
+        }
+        (None, None) => writeln!(out, "        Self::deserialize(body).ok()")?,
+    }
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+    Ok(())
+}
 
-    // Base client
-    writeln!(
-        out,
-        r#"
-pub struct BaseClient {{
-    client: Client,
-    base_url: String,
-    access_token: Option<String>,
-}}
+/// Write the response enum of an operation with several success responses,
+/// e.g. `TaskServiceCreateResponse::Created(Task)`, with a variant per status.
+/// A response sent with `@header`s holds them `WithHeaders`.
+fn write_response_enum(
+    out: &mut String,
+    iface_name: &str,
+    http_op: &HttpOperation<'_>,
+    scalars: &ScalarMap,
+) -> Result<(), CodegenError> {
+    let (op, responses) = (http_op.op, &http_op.responses);
+    let enum_name = response_enum_name(iface_name, op);
 
-impl BaseClient {{
-    pub fn new(base_url: impl Into<String>) -> Self {{
-        Self {{
-            client: Client::new(),
-            base_url: base_url.into().trim_end_matches('/').to_string(),
-            access_token: None,
-        }}
-    }}
+    writeln!(out)?;
+    writeln!(out, "/// Responses of `{}.{}`", iface_name, op.name)?;
+    writeln!(out, "#[derive(Debug, Clone, Serialize)]")?;
+    writeln!(out, "#[serde(untagged)]")?;
+    writeln!(out, "pub enum {} {{", enum_name)?;
+    for response in responses {
+        let variant = status_name(response.status);
+        match variant_type(iface_name, http_op, response, scalars) {
+            Some(ty) => writeln!(out, "    {}({}),", variant, ty)?,
+            None => writeln!(out, "    {},", variant)?,
+        }
+    }
+    writeln!(out, "}}")?;
 
-    pub fn with_token(mut self, token: impl Into<String>) -> Self {{
-        self.access_token = Some(token.into());
-        self
-    }}
+    writeln!(out)?;
+    writeln!(out, "impl {} {{", enum_name)?;
+    writeln!(out, "    /// Status code to respond with")?;
+    writeln!(out, "    pub fn status(&self) -> u16 {{")?;
+    writeln!(out, "        match self {{")?;
+    for response in responses {
+        let pattern = variant_pattern(response);
+        writeln!(
+            out,
+            "            Self::{} => {},",
+            pattern,
+            response.status.unwrap_or(200)
+        )?;
+    }
+    writeln!(out, "        }}")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
 
-    pub fn set_token(&mut self, token: impl Into<String>) {{
-        self.access_token = Some(token.into());
-    }}
+    writeln!(out)?;
+    writeln!(out, "impl FromResponse for {} {{", enum_name)?;
+    writeln!(out, "    fn from_response(")?;
+    writeln!(out, "        status: u16,")?;
+    writeln!(out, "        get: impl Fn(&str) -> Option<String>,")?;
+    writeln!(out, "        body: serde_json::Value,")?;
+    writeln!(out, "    ) -> Result<Self, serde_json::Error> {{")?;
+    writeln!(out, "        match status {{")?;
+    for response in responses.iter().filter(|r| r.status.is_some()) {
+        let status = response.status.unwrap_or_default().to_string();
+        write_variant_decoder(out, iface_name, http_op, response, &status)?;
+    }
+    match responses.iter().find(|r| r.status.is_none()) {
+        Some(response) => write_variant_decoder(out, iface_name, http_op, response, "_")?,
+        None => writeln!(
+            out,
+            r#"            _ => Err(serde::de::Error::custom(format!("unexpected status {{}}", status))),"#
+        )?,
+    }
+    writeln!(out, "        }}")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+    Ok(())
+}
 
-    async fn request<T, B>(
-        &self,
-        method: Method,
-        path: &str,
-        body: Option<&B>,
-    ) -> Result<T, ApiError>
-    where
-        T: DeserializeOwned,
-        B: Serialize,
-    {{
-        let url = format!("{{}}{{}}", self.base_url, path);
-        let mut req = self.client.request(method, &url);
+/// The type a variant of a response enum holds: its body, with its
+/// `@header`s when it has any. `None` for a response without either.
+fn variant_type(
+    iface_name: &str,
+    http_op: &HttpOperation<'_>,
+    response: &Response<'_>,
+    scalars: &ScalarMap,
+) -> Option<String> {
+    let body = response
+        .body
+        .as_ref()
+        .map(|body| type_to_rust(body, false, scalars));
+    if response.headers.is_empty() {
+        return body;
+    }
+    Some(format!(
+        "WithHeaders<{}, {}>",
+        body.as_deref().unwrap_or("()"),
+        http_op.headers_name(iface_name, response)
+    ))
+}
 
-        if let Some(token) = &self.access_token {{
-            req = req.header("Authorization", format!("Bearer {{}}", token));
-        }}
+/// Write the match arm of `FromResponse` decoding the variant of `response`
+fn write_variant_decoder(
+    out: &mut String,
+    iface_name: &str,
+    http_op: &HttpOperation<'_>,
+    response: &Response<'_>,
+    pattern: &str,
+) -> Result<(), CodegenError> {
+    let variant = status_name(response.status);
+    if !response.headers.is_empty() {
+        let body = if response.body.is_some() {
+            "serde_json::from_value(body)?"
+        } else {
+            "()"
+        };
+        writeln!(
+            out,
+            "            {} => Ok(Self::{}(WithHeaders {{",
+            pattern, variant
+        )?;
+        writeln!(out, "                body: {},", body)?;
+        writeln!(
+            out,
+            "                headers: {}::from_headers(get).map_err(serde::de::Error::custom)?,",
+            http_op.headers_name(iface_name, response)
+        )?;
+        writeln!(out, "            }})),")?;
+    } else if response.body.is_some() {
+        writeln!(
+            out,
+            "            {} => serde_json::from_value(body).map(Self::{}),",
+            pattern, variant
+        )?;
+    } else {
+        writeln!(out, "            {} => Ok(Self::{}),", pattern, variant)?;
+    }
+    Ok(())
+}
 
-        if let Some(body) = body {{
-            req = req.json(body);
-        }}
+/// Pattern matching the variant of a response, ignoring what it holds
+fn variant_pattern(response: &Response<'_>) -> String {
+    let variant = status_name(response.status);
+    if response.body.is_some() || !response.headers.is_empty() {
+        format!("{}(_)", variant)
+    } else {
+        variant
+    }
+}
 
-        let resp = req.send().await?;
+fn response_enum_name(iface_name: &str, op: &Operation) -> String {
+    format!("{}{}Response", iface_name, op.name.to_case(Case::Pascal))
+}
+
+/// Write the struct of the `@header`s an operation responds with, e.g.
+/// `location` of `{ @statusCode _: 201; @header location: string; @body task: Task }`
+fn write_response_headers(
+    out: &mut String,
+    iface_name: &str,
+    http_op: &HttpOperation<'_>,
+    response: &Response<'_>,
+    scalars: &ScalarMap,
+) -> Result<(), CodegenError> {
+    let struct_name = http_op.headers_name(iface_name, response);
+
+    writeln!(out)?;
+    if http_op.responses.len() > 1 {
+        writeln!(
+            out,
+            "/// Headers of the `{}` response of `{}.{}`",
+            status_name(response.status),
+            iface_name,
+            http_op.op.name
+        )?;
+    } else {
+        writeln!(
+            out,
+            "/// Response headers of `{}.{}`",
+            iface_name, http_op.op.name
+        )?;
+    }
+    writeln!(out, "#[derive(Debug, Clone, Default)]")?;
+    writeln!(out, "pub struct {} {{", struct_name)?;
+    for header in &response.headers {
+        write_doc(out, "    ", &header.decorators)?;
+        writeln!(
+            out,
+            "    pub {}: {},",
+            field_name(&header.name),
+            type_to_rust(&header.type_ref, header.optional, scalars)
+        )?;
+    }
+    writeln!(out, "}}")?;
+
+    writeln!(out)?;
+    writeln!(out, "impl ResponseHeaders for {} {{", struct_name)?;
+    writeln!(
+        out,
+        "    fn from_headers(get: impl Fn(&str) -> Option<String>) -> Result<Self, String> {{"
+    )?;
+    writeln!(out, "        Ok(Self {{")?;
+    for header in &response.headers {
+        let name = header_name(&header.decorators, &header.name);
+        let parse = format!(
+            r#".map(|v| v.parse()).transpose().map_err(|_| "invalid header {}".to_string())?"#,
+            name
+        );
+        if header.optional {
+            writeln!(
+                out,
+                r#"            {}: get("{}"){},"#,
+                field_name(&header.name),
+                name,
+                parse
+            )?;
+        } else {
+            writeln!(
+                out,
+                r#"            {}: get("{}"){}.ok_or_else(|| "missing header {}".to_string())?,"#,
+                field_name(&header.name),
+                name,
+                parse,
+                name
+            )?;
+        }
+    }
+    writeln!(out, "        }})")?;
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    fn to_headers(&self) -> Vec<(&'static str, String)> {{"
+    )?;
+    writeln!(out, "        let mut headers = Vec::new();")?;
+    for header in &response.headers {
+        let name = header_name(&header.decorators, &header.name);
+        let field = field_name(&header.name);
+        if header.optional {
+            writeln!(out, "        if let Some(value) = &self.{} {{", field)?;
+            writeln!(
+                out,
+                r#"            headers.push(("{}", value.to_string()));"#,
+                name
+            )?;
+            writeln!(out, "        }}")?;
+        } else {
+            writeln!(
+                out,
+                r#"        headers.push(("{}", self.{}.to_string()));"#,
+                name, field
+            )?;
+        }
+    }
+    writeln!(out, "        headers")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+/// Name of the struct field of a property or parameter, escaping keywords
+fn field_name(name: &str) -> String {
+    let name = name.to_case(Case::Snake);
+    if is_rust_keyword(&name) {
+        format!("r#{}", name)
+    } else {
+        name
+    }
+}
+
+/// Write the error enum of an operation with several `@error` models,
+/// e.g. `TaskServiceCreateError::NotFound(NotFound)`
+fn write_error_enum(
+    out: &mut String,
+    iface_name: &str,
+    op: &Operation,
+    errors: &[&Model],
+) -> Result<(), CodegenError> {
+    let enum_name = error_enum_name(iface_name, op);
+
+    writeln!(out)?;
+    writeln!(out, "/// Error responses of `{}.{}`", iface_name, op.name)?;
+    writeln!(out, "#[derive(Debug, Clone, Serialize)]")?;
+    writeln!(out, "#[serde(untagged)]")?;
+    writeln!(out, "pub enum {} {{", enum_name)?;
+    for model in errors {
+        write_doc(out, "    ", &model.decorators)?;
+        writeln!(out, "    {}({}),", model.name, model.name)?;
+    }
+    writeln!(out, "}}")?;
+
+    writeln!(out)?;
+    writeln!(out, "impl ErrorResponse for {} {{", enum_name)?;
+    writeln!(out, "    fn status(&self) -> u16 {{")?;
+    writeln!(out, "        match self {{")?;
+    for model in errors {
+        writeln!(
+            out,
+            "            Self::{}(error) => error.status(),",
+            model.name
+        )?;
+    }
+    writeln!(out, "        }}")?;
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    fn from_response(status: u16, body: &serde_json::Value) -> Option<Self> {{"
+    )?;
+    for model in errors {
+        writeln!(
+            out,
+            "        if let Some(error) = {}::from_response(status, body) {{",
+            model.name
+        )?;
+        writeln!(out, "            return Some(Self::{}(error));", model.name)?;
+        writeln!(out, "        }}")?;
+    }
+    writeln!(out, "        None")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+
+    for model in errors {
+        writeln!(out)?;
+        writeln!(out, "impl From<{}> for {} {{", model.name, enum_name)?;
+        writeln!(out, "    fn from(error: {}) -> Self {{", model.name)?;
+        writeln!(out, "        Self::{}(error)", model.name)?;
+        writeln!(out, "    }}")?;
+        writeln!(out, "}}")?;
+    }
+    Ok(())
+}
+
+/// The `@error` models an operation responds with, fixed statuses first
+fn error_models<'a>(http_op: &HttpOperation<'a>) -> Vec<&'a Model> {
+    let mut errors: Vec<&Model> = Vec::new();
+    for response in &http_op.errors {
+        if let Some(model) = response.error.filter(|m| m.type_params.is_empty()) {
+            if !errors.iter().any(|e| e.name == model.name) {
+                errors.push(model);
+            }
+        }
+    }
+    errors
+}
+
+fn error_enum_name(iface_name: &str, op: &Operation) -> String {
+    format!("{}{}Error", iface_name, op.name.to_case(Case::Pascal))
+}
+
+/// The typed error of an operation: its `@error` model, or the enum of its
+/// `@error` models when it has several
+fn error_type(iface_name: &str, http_op: &HttpOperation<'_>) -> Option<String> {
+    let errors = error_models(http_op);
+    match errors.as_slice() {
+        [] => None,
+        [model] => Some(format!("crate::models::{}", model.name)),
+        _ => Some(format!(
+            "crate::models::{}",
+            error_enum_name(iface_name, http_op.op)
+        )),
+    }
+}
+
+/// The Rust type of an operation's success: its body, with its `@header`s
+/// when it has any, or the enum of its responses when it has several
+fn success_type(iface_name: &str, http_op: &HttpOperation<'_>, scalars: &ScalarMap) -> String {
+    let responses = &http_op.responses;
+    if responses.len() > 1 {
+        return response_enum_name(iface_name, http_op.op);
+    }
+    let Some(response) = responses.first() else {
+        return "()".to_string();
+    };
+    let body = response
+        .body
+        .as_ref()
+        .map(|body| type_to_rust(body, false, scalars))
+        .unwrap_or_else(|| "()".to_string());
+    if response.headers.is_empty() {
+        body
+    } else {
+        format!(
+            "WithHeaders<{}, {}>",
+            body,
+            http_op.headers_name(iface_name, response)
+        )
+    }
+}
+
+fn is_integer(type_ref: &TypeRef) -> bool {
+    match type_ref {
+        TypeRef::Builtin(name) => matches!(
+            name.as_str(),
+            "int8" | "int16" | "int32" | "int64" | "uint8" | "uint16" | "uint32" | "uint64"
+        ),
+        TypeRef::Optional(inner) => is_integer(inner),
+        _ => false,
+    }
+}
+
+/// Write a check of `value` that returns a `ValidationError` if it fails
+fn write_check(out: &mut String, indent: &str, field: &str, check: &Check<'_>) -> std::fmt::Result {
+    let condition = match check {
+        Check::MinLength(n) => format!("value.chars().count() < {}", n),
+        Check::MaxLength(n) => format!("value.chars().count() > {}", n),
+        Check::MinValue(n) => format!("(*value as f64) < {:?}", n),
+        Check::MaxValue(n) => format!("(*value as f64) > {:?}", n),
+        Check::MinItems(n) => format!("value.len() < {}", n),
+        Check::MaxItems(n) => format!("value.len() > {}", n),
+        Check::Pattern(pattern) => {
+            writeln!(out, "{}{{", indent)?;
+            writeln!(
+                out,
+                "{}    static PATTERN: std::sync::LazyLock<regex::Regex> =",
+                indent
+            )?;
+            writeln!(
+                out,
+                "{}        std::sync::LazyLock::new(|| regex::Regex::new({:?}).unwrap());",
+                indent, pattern
+            )?;
+            writeln!(out, "{}    if !PATTERN.is_match(value) {{", indent)?;
+            writeln!(
+                out,
+                "{}        return Err(ValidationError::new({:?}, {:?}));",
+                indent,
+                field,
+                check.message()
+            )?;
+            writeln!(out, "{}    }}", indent)?;
+            return writeln!(out, "{}}}", indent);
+        }
+    };
+    writeln!(out, "{}if {} {{", indent, condition)?;
+    writeln!(
+        out,
+        "{}    return Err(ValidationError::new({:?}, {:?}));",
+        indent,
+        field,
+        check.message()
+    )?;
+    writeln!(out, "{}}}", indent)
+}
+
+fn generate_enums(file: &TypeSpecFile) -> Result<String, CodegenError> {
+    let mut out = String::new();
+
+    writeln!(out, "//! Auto-generated enums from TypeSpec.")?;
+    writeln!(out, "//! DO NOT EDIT.")?;
+    writeln!(out)?;
+    writeln!(out, "use serde::{{Deserialize, Serialize}};")?;
+    writeln!(out)?;
+
+    for enum_def in file.enums() {
+        writeln!(out)?;
+        write_doc(&mut out, "", &enum_def.decorators)?;
+        writeln!(
+            out,
+            "#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]"
+        )?;
+        writeln!(out, "pub enum {} {{", enum_def.name)?;
+
+        for member in &enum_def.members {
+            // Get the serialization value - either explicit or snake_case of name
+            let value = member
+                .value
+                .as_ref()
+                .map(|v| match v {
+                    Value::String(s) => s.clone(),
+                    _ => member.name.to_case(Case::Snake),
+                })
+                .unwrap_or_else(|| member.name.to_case(Case::Snake));
+
+            let variant = member.name.to_case(Case::Pascal);
+
+            // Always add rename attribute for explicit serialization
+            write_doc(&mut out, "    ", &member.decorators)?;
+            writeln!(out, r#"    #[serde(rename = "{}")]"#, value)?;
+            writeln!(out, "    {},", variant)?;
+        }
+
+        writeln!(out, "}}")?;
+    }
+
+    Ok(out)
+}
+
+fn generate_client(
+    file: &TypeSpecFile,
+    scalars: &ScalarMap,
+    models: &ModelMap<'_>,
+) -> Result<String, CodegenError> {
+    let mut out = String::new();
+
+    writeln!(out, "//! Auto-generated API client from TypeSpec.")?;
+    writeln!(out, "//! DO NOT EDIT.")?;
+    writeln!(out)?;
+    writeln!(out, "#![allow(unused_imports)]")?;
+    writeln!(out)?;
+    writeln!(out, "use crate::models::*;")?;
+    writeln!(out, "use crate::enums::*;")?;
+    writeln!(out, "use chrono::{{DateTime, Utc}};")?;
+    writeln!(out, "use reqwest::{{Client, Method}};")?;
+    writeln!(out, "use serde::{{de::DeserializeOwned, Serialize}};")?;
+    writeln!(out, "use thiserror::Error;")?;
+    writeln!(out, "use uuid::Uuid;")?;
+    writeln!(out)?;
+
+    // Error type
+    writeln!(
+        out,
+        r#"
+/// A `@query` or `@header` value as sent: strings as they are, other values
+/// as their JSON
+fn param_value<T: Serialize>(value: &T) -> String {{
+    match serde_json::to_value(value) {{
+        Ok(serde_json::Value::String(s)) => s,
+        Ok(value) => value.to_string(),
+        Err(_) => String::new(),
+    }}
+}}
+
+/// Error of a request. `E` is the operation's `@error` model, or the enum of
+/// its `@error` models when it has several.
+#[derive(Debug, Error)]
+pub enum ApiError<E = ()> {{
+    #[error("HTTP error: {{0}}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("API error: {{status}}")]
+    Typed {{ status: u16, error: E }},
+
+    #[error("API error: {{status}} - {{message}}")]
+    Api {{ status: u16, code: String, message: String }},
+
+    #[error("Invalid response: {{0}}")]
+    Decode(serde_json::Error),
+
+    #[error("Invalid response: {{0}}")]
+    Header(String),
+}}
+"#
+    )?;
+
+    // Base client
+    writeln!(
+        out,
+        r#"
+pub struct BaseClient {{
+    client: Client,
+    base_url: String,
+    access_token: Option<String>,
+}}
+
+impl BaseClient {{
+    pub fn new(base_url: impl Into<String>) -> Self {{
+        Self {{
+            client: Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            access_token: None,
+        }}
+    }}
+
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {{
+        self.access_token = Some(token.into());
+        self
+    }}
+
+    pub fn set_token(&mut self, token: impl Into<String>) {{
+        self.access_token = Some(token.into());
+    }}
+
+    async fn request<T, B, E>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        headers: &[(&str, String)],
+        body: Option<&B>,
+    ) -> Result<T, ApiError<E>>
+    where
+        T: DeserializeOwned,
+        B: Serialize,
+        E: ErrorResponse,
+    {{
+        let resp = self.send(method, path, query, headers, body).await?;
+
+        if resp.status() == reqwest::StatusCode::NO_CONTENT {{
+            return Ok(serde_json::from_value(serde_json::Value::Null).unwrap());
+        }}
+
+        Ok(resp.json().await?)
+    }}
+
+    /// Send a request whose response headers are returned with its body
+    #[allow(dead_code)]
+    async fn request_with_headers<T, H, B, E>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        headers: &[(&str, String)],
+        body: Option<&B>,
+    ) -> Result<WithHeaders<T, H>, ApiError<E>>
+    where
+        T: DeserializeOwned,
+        H: ResponseHeaders,
+        B: Serialize,
+        E: ErrorResponse,
+    {{
+        let resp = self.send(method, path, query, headers, body).await?;
+        let response_headers = H::from_headers(|name| {{
+            resp.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        }})
+        .map_err(ApiError::Header)?;
+
+        let body = if resp.status() == reqwest::StatusCode::NO_CONTENT {{
+            serde_json::from_value(serde_json::Value::Null).map_err(ApiError::Decode)?
+        }} else {{
+            resp.json().await?
+        }};
+        Ok(WithHeaders {{
+            body,
+            headers: response_headers,
+        }})
+    }}
+
+    /// Send a request whose response is decoded by its status
+    #[allow(dead_code)]
+    async fn request_responses<R, B, E>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        headers: &[(&str, String)],
+        body: Option<&B>,
+    ) -> Result<R, ApiError<E>>
+    where
+        R: FromResponse,
+        B: Serialize,
+        E: ErrorResponse,
+    {{
+        let resp = self.send(method, path, query, headers, body).await?;
+        let status = resp.status();
+        let response_headers = resp.headers().clone();
+
+        let body = if status == reqwest::StatusCode::NO_CONTENT {{
+            serde_json::Value::Null
+        }} else {{
+            resp.json().await?
+        }};
+        let get = |name: &str| {{
+            response_headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        }};
+        R::from_response(status.as_u16(), get, body).map_err(ApiError::Decode)
+    }}
+
+    async fn send<B, E>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        headers: &[(&str, String)],
+        body: Option<&B>,
+    ) -> Result<reqwest::Response, ApiError<E>>
+    where
+        B: Serialize,
+        E: ErrorResponse,
+    {{
+        let url = format!("{{}}{{}}", self.base_url, path);
+        let mut req = self.client.request(method, &url).query(query);
+
+        if let Some(token) = &self.access_token {{
+            req = req.header("Authorization", format!("Bearer {{}}", token));
+        }}
+
+        for (name, value) in headers {{
+            req = req.header(*name, value);
+        }}
+
+        if let Some(body) = body {{
+            req = req.json(body);
+        }}
+
+        let resp = req.send().await?;
         let status = resp.status();
 
         if !status.is_success() {{
             let err: serde_json::Value = resp.json().await.unwrap_or_default();
+            if let Some(error) = E::from_response(status.as_u16(), &err) {{
+                return Err(ApiError::Typed {{
+                    status: status.as_u16(),
+                    error,
+                }});
+            }}
             return Err(ApiError::Api {{
                 status: status.as_u16(),
                 code: err["code"].as_str().unwrap_or("ERROR").to_string(),
@@ -442,22 +1366,18 @@ impl BaseClient {{
             }});
         }}
 
-        if status == reqwest::StatusCode::NO_CONTENT {{
-            return Ok(serde_json::from_value(serde_json::Value::Null).unwrap());
-        }}
-
-        Ok(resp.json().await?)
+        Ok(resp)
     }}
 }}
 "#
     )?;
 
     // Service clients
-    for iface in file.interfaces() {
-        let base_path = get_route(&iface.decorators).unwrap_or_default();
+    for iface in &collect_services(file) {
         let struct_name = format!("{}Client", iface.name);
 
         writeln!(out)?;
+        write_doc(&mut out, "", &iface.decorators)?;
         writeln!(out, "pub struct {}<'a> {{", struct_name)?;
         writeln!(out, "    client: &'a BaseClient,")?;
         writeln!(out, "}}")?;
@@ -467,80 +1387,104 @@ impl BaseClient {{
         writeln!(out, "        Self {{ client }}")?;
         writeln!(out, "    }}")?;
 
-        for op in &iface.operations {
-            let method = get_http_method(&op.decorators);
-            let op_path = get_route(&op.decorators).unwrap_or_default();
-            let full_path = format!("{}{}", base_path, op_path);
+        for http_op in rust_operations(iface, models) {
+            let op = http_op.op;
             let fn_name = op.name.to_case(Case::Snake);
 
             writeln!(out)?;
+            write_doc(&mut out, "    ", &op.decorators)?;
             write!(out, "    pub async fn {}(&self", fn_name)?;
 
             // Parameters
-            for param in &op.params {
-                // Skip spread params without explicit names
-                if param.spread && param.name.is_empty() {
-                    continue;
-                }
-                let name = param.name.to_case(Case::Snake);
-                if has_decorator(&param.decorators, "path") {
-                    write!(out, ", {}: &str", name)?;
-                } else if has_decorator(&param.decorators, "body") {
-                    let ty = type_to_rust(&param.type_ref, false, scalars);
-                    write!(out, ", body: &{}", ty)?;
-                } else if has_decorator(&param.decorators, "query") {
-                    let ty = type_to_rust(&param.type_ref, param.optional, scalars);
-                    write!(out, ", {}: {}", name, ty)?;
+            for param in &http_op.params {
+                let name = field_name(&param.name);
+                match param.location {
+                    ParamLocation::Path => write!(out, ", {}: &str", name)?,
+                    ParamLocation::Body => {
+                        let ty = type_to_rust(&param.type_ref, false, scalars);
+                        write!(out, ", body: &{}", ty)?;
+                    }
+                    ParamLocation::Query | ParamLocation::Header | ParamLocation::BodyProperty => {
+                        let ty = param_type(param, param.optional, scalars);
+                        write!(out, ", {}: {}", name, ty)?;
+                    }
                 }
             }
 
-            let return_type = op
-                .return_type
-                .as_ref()
-                .map(|t| type_to_rust(t, false, scalars))
-                .unwrap_or_else(|| "()".to_string());
+            let return_type = success_type(&iface.name, &http_op, scalars);
+            let error = match error_type(&iface.name, &http_op) {
+                Some(error) => format!("ApiError<{}>", error),
+                None => "ApiError".to_string(),
+            };
 
-            writeln!(out, ") -> Result<{}, ApiError> {{", return_type)?;
+            writeln!(out, ") -> Result<{}, {}> {{", return_type, error)?;
 
             // Build path
-            let mut path_expr = format!(r#"let path = format!("{}"#, full_path);
-            for param in &op.params {
-                if has_decorator(&param.decorators, "path") {
-                    path_expr = path_expr.replace(&format!("{{{}}}", param.name), "{}");
-                }
+            let path_params: Vec<_> = http_op.params_at(ParamLocation::Path).collect();
+            let mut path_expr = format!(r#"let path = format!("{}"#, http_op.path);
+            for param in &path_params {
+                path_expr = path_expr.replace(&format!("{{{}}}", param.name), "{}");
             }
-            let path_args: Vec<_> = op
-                .params
-                .iter()
-                .filter(|p| has_decorator(&p.decorators, "path"))
-                .map(|p| p.name.to_case(Case::Snake))
-                .collect();
+            let path_args: Vec<_> = path_params.iter().map(|p| field_name(&p.name)).collect();
 
             if path_args.is_empty() {
-                writeln!(out, r#"        let path = "{}";"#, full_path)?;
+                writeln!(out, r#"        let path = "{}";"#, http_op.path)?;
             } else {
-                writeln!(out, "{}\"", path_expr)?;
+                write!(out, "        {}\"", path_expr)?;
                 for arg in &path_args {
                     write!(out, ", {}", arg)?;
                 }
                 writeln!(out, ");")?;
             }
 
+            // `@query` and `@header` params
+            let query: Vec<_> = http_op
+                .params_at(ParamLocation::Query)
+                .map(|p| (p.name.clone(), &**p))
+                .collect();
+            let headers: Vec<_> = http_op
+                .params_at(ParamLocation::Header)
+                .map(|p| (header_name(&p.decorators, &p.name), &**p))
+                .collect();
+            write_param_pairs(&mut out, "query", &query)?;
+            write_param_pairs(&mut out, "headers", &headers)?;
+
+            // Params without a location are sent as the properties of a JSON body
+            let body_properties: Vec<_> = http_op
+                .params_at(ParamLocation::BodyProperty)
+                .map(|p| (p.name.clone(), &**p))
+                .collect();
+            write_body_properties(&mut out, &body_properties)?;
+
             // Make request
-            let has_body = op
-                .params
-                .iter()
-                .any(|p| has_decorator(&p.decorators, "body"));
+            let body = if http_op.body().is_some() {
+                "Some(body)"
+            } else if !body_properties.is_empty() {
+                "Some(&payload)"
+            } else {
+                "None::<&()>"
+            };
 
+            let responses = &http_op.responses;
+            let request = if responses.len() > 1 {
+                "request_responses"
+            } else if responses.iter().any(|r| !r.headers.is_empty()) {
+                "request_with_headers"
+            } else {
+                "request"
+            };
             writeln!(
                 out,
-                "        self.client.request(Method::{}, &path, {}).await",
-                method,
-                if has_body {
-                    "Some(body)"
+                "        self.client.{}(Method::{}, &path, {}, {}, {}).await",
+                request,
+                http_op.method,
+                if query.is_empty() { "&[]" } else { "&query" },
+                if headers.is_empty() {
+                    "&[]"
                 } else {
-                    "None::<&()>"
-                }
+                    "&headers"
+                },
+                body
             )?;
 
             writeln!(out, "    }}")?;
@@ -552,15 +1496,88 @@ impl BaseClient {{
     Ok(out)
 }
 
-fn generate_server(file: &TypeSpecFile, scalars: &ScalarMap) -> Result<String, CodegenError> {
+/// Write the `Vec` of `(name, value)` pairs of an operation's `@query` or
+/// `@header` params, leaving out optional params that are `None`
+fn write_param_pairs(
+    out: &mut String,
+    var: &str,
+    params: &[(String, &OperationParam)],
+) -> Result<(), CodegenError> {
+    if params.is_empty() {
+        return Ok(());
+    }
+    writeln!(out, "        let mut {} = Vec::new();", var)?;
+    for (name, param) in params {
+        let arg = field_name(&param.name);
+        if param.optional {
+            writeln!(out, "        if let Some(value) = &{} {{", arg)?;
+            writeln!(
+                out,
+                r#"            {}.push(("{}", param_value(value)));"#,
+                var, name
+            )?;
+            writeln!(out, "        }}")?;
+        } else {
+            writeln!(
+                out,
+                r#"        {}.push(("{}", param_value(&{})));"#,
+                var, name, arg
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Write the implicit JSON body of an operation, `payload`, from its params
+/// without a location, leaving out optional params that are `None`
+fn write_body_properties(
+    out: &mut String,
+    params: &[(String, &OperationParam)],
+) -> Result<(), CodegenError> {
+    if params.is_empty() {
+        return Ok(());
+    }
+    writeln!(out, "        let mut payload = serde_json::Map::new();")?;
+    for (name, param) in params {
+        let arg = field_name(&param.name);
+        if param.optional {
+            writeln!(out, "        if let Some(value) = &{} {{", arg)?;
+            writeln!(
+                out,
+                r#"            payload.insert("{}".to_string(), serde_json::json!(value));"#,
+                name
+            )?;
+            writeln!(out, "        }}")?;
+        } else {
+            writeln!(
+                out,
+                r#"        payload.insert("{}".to_string(), serde_json::json!({}));"#,
+                name, arg
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn generate_server(
+    file: &TypeSpecFile,
+    scalars: &ScalarMap,
+    constraints: &ConstraintMap,
+    models: &ModelMap<'_>,
+) -> Result<String, CodegenError> {
     let mut out = String::new();
+    let validated = validated_models(file, models, constraints);
 
     writeln!(out, "//! Auto-generated server handlers from TypeSpec.")?;
     writeln!(out, "//! DO NOT EDIT.")?;
     writeln!(out, "//!")?;
     writeln!(
         out,
-        "//! Implement the trait to provide your business logic."
+        "//! Implement the trait to provide your business logic, and serve it"
+    )?;
+    writeln!(
+        out,
+        "//! with the interface's `router`, e.g. `tasks::router(Arc::new(handler))`."
     )?;
     writeln!(out)?;
     writeln!(out, "#![allow(unused_imports)]")?;
@@ -568,6 +1585,7 @@ fn generate_server(file: &TypeSpecFile, scalars: &ScalarMap) -> Result<String, C
     writeln!(out, "use crate::models::*;")?;
     writeln!(out, "use crate::enums::*;")?;
     writeln!(out, "use async_trait::async_trait;")?;
+    writeln!(out, "use chrono::{{DateTime, Utc}};")?;
     writeln!(
         out,
         "use axum::{{extract::{{Path, Query, State}}, http::StatusCode, Json, Router}};"
@@ -593,47 +1611,752 @@ impl axum::response::IntoResponse for ApiError {{
         (status, Json(self)).into_response()
     }}
 }}
+
+/// JSON request body that passed [`Validate`]. Malformed bodies are rejected
+/// with their JSON error, invalid ones with 422, before a handler is called.
+pub struct ValidatedJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> axum::extract::FromRequest<S> for ValidatedJson<T>
+where
+    T: serde::de::DeserializeOwned + Validate + Send,
+    S: Send + Sync,
+{{
+    type Rejection = ApiError;
+
+    async fn from_request(req: axum::extract::Request, state: &S) -> Result<Self, Self::Rejection> {{
+        let Json(value) = Json::<T>::from_request(req, state).await.map_err(|e| ApiError {{
+            status: e.status().as_u16(),
+            code: "INVALID_BODY".to_string(),
+            message: e.body_text(),
+        }})?;
+        value.validate().map_err(|e| ApiError {{
+            status: StatusCode::UNPROCESSABLE_ENTITY.as_u16(),
+            code: "VALIDATION_ERROR".to_string(),
+            message: e.to_string(),
+        }})?;
+        Ok(Self(value))
+    }}
+}}
+
+/// Responds with the body and its `@header`s
+impl<T, H> axum::response::IntoResponse for WithHeaders<T, H>
+where
+    T: serde::Serialize,
+    H: ResponseHeaders,
+{{
+    fn into_response(self) -> axum::response::Response {{
+        let mut response = Json(self.body).into_response();
+        for (name, value) in self.headers.to_headers() {{
+            if let (Ok(name), Ok(value)) = (
+                axum::http::HeaderName::from_bytes(name.as_bytes()),
+                axum::http::HeaderValue::from_str(&value),
+            ) {{
+                response.headers_mut().insert(name, value);
+            }}
+        }}
+        response
+    }}
+}}
 "#
     )?;
 
+    let services = collect_services(file);
+    let services: Vec<_> = services
+        .iter()
+        .map(|iface| (iface, rust_operations(iface, models)))
+        .collect();
+    let operations = || {
+        services
+            .iter()
+            .flat_map(|(iface, ops)| ops.iter().map(move |op| (iface, op)))
+    };
+
+    // Extractors of `@header` params
+    if operations().any(|(_, op)| op.params_at(ParamLocation::Header).next().is_some()) {
+        writeln!(out, "{}", REQUEST_HEADER_RUNTIME)?;
+    }
+    for (iface, http_op) in operations() {
+        if http_op.params_at(ParamLocation::Header).next().is_some() {
+            write_request_headers(&mut out, &iface.name, http_op, scalars)?;
+        }
+    }
+
+    // `@query` params, read with `Query<...>`, and the properties of
+    // implicit bodies, read with `ValidatedJson<...>`
+    for location in [ParamLocation::Query, ParamLocation::BodyProperty] {
+        for (iface, http_op) in operations() {
+            if http_op.params_at(location).next().is_some() {
+                write_request_params(&mut out, &iface.name, http_op, location, scalars)?;
+            }
+            if location == ParamLocation::BodyProperty && http_op.body().is_none() {
+                write_body_validate_impl(
+                    &mut out,
+                    &iface.name,
+                    http_op,
+                    scalars,
+                    constraints,
+                    models,
+                    &validated,
+                )?;
+            }
+        }
+    }
+
+    // `@error` models respond with their status
+    let mut error_types: Vec<String> = file
+        .models()
+        .filter(|m| is_error_model(m) && m.type_params.is_empty())
+        .map(|m| m.name.clone())
+        .collect();
+    for (iface, http_op) in operations() {
+        if error_models(http_op).len() > 1 {
+            error_types.push(error_enum_name(&iface.name, http_op.op));
+        }
+    }
+    // Responses of operations with several success responses
+    for (iface, http_op) in operations() {
+        if http_op.responses.len() > 1 {
+            write_response_into_response(&mut out, &iface.name, http_op.op, &http_op.responses)?;
+        }
+    }
+
+    for error_type in &error_types {
+        writeln!(out)?;
+        writeln!(
+            out,
+            "impl axum::response::IntoResponse for crate::models::{} {{",
+            error_type
+        )?;
+        writeln!(
+            out,
+            "    fn into_response(self) -> axum::response::Response {{"
+        )?;
+        writeln!(
+            out,
+            "        let status = StatusCode::from_u16(self.status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);"
+        )?;
+        writeln!(out, "        (status, Json(self)).into_response()")?;
+        writeln!(out, "    }}")?;
+        writeln!(out, "}}")?;
+    }
+
     // Handler traits
-    for iface in file.interfaces() {
+    for (iface, ops) in &services {
         let trait_name = format!("{}Handler", iface.name);
 
         writeln!(out)?;
+        write_doc(&mut out, "", &iface.decorators)?;
         writeln!(out, "#[async_trait]")?;
         writeln!(out, "pub trait {}: Send + Sync + 'static {{", trait_name)?;
 
-        for op in &iface.operations {
+        for http_op in ops {
+            let op = http_op.op;
             let fn_name = op.name.to_case(Case::Snake);
 
+            write_doc(&mut out, "    ", &op.decorators)?;
             write!(out, "    async fn {}(&self", fn_name)?;
 
-            for param in &op.params {
-                // Skip spread params without explicit names (they expand into multiple params)
-                if param.spread && param.name.is_empty() {
-                    continue;
-                }
-                let name = param.name.to_case(Case::Snake);
-                let ty = type_to_rust(&param.type_ref, param.optional, scalars);
+            for param in &http_op.params {
+                let name = field_name(&param.name);
+                let optional = param.optional && param_default(param, scalars).is_none();
+                let ty = param_type(param, optional, scalars);
                 write!(out, ", {}: {}", name, ty)?;
             }
 
-            let return_type = op
-                .return_type
-                .as_ref()
-                .map(|t| type_to_rust(t, false, scalars))
-                .unwrap_or_else(|| "()".to_string());
+            let return_type = success_type(&iface.name, http_op, scalars);
+            let error = error_type(&iface.name, http_op).unwrap_or_else(|| "ApiError".to_string());
 
-            writeln!(out, ") -> Result<{}, ApiError>;", return_type)?;
+            writeln!(out, ") -> Result<{}, {}>;", return_type, error)?;
         }
 
         writeln!(out, "}}")?;
+
+        write_router(&mut out, iface, ops, scalars, models)?;
     }
 
     Ok(out)
 }
 
+/// Write the module routing the operations of an interface to its handler
+/// trait, e.g. `tasks::router(handler)`, with an axum handler per operation
+/// extracting its params and responding with the status of its result
+fn write_router(
+    out: &mut String,
+    iface: &Interface,
+    ops: &[HttpOperation<'_>],
+    scalars: &ScalarMap,
+    models: &ModelMap<'_>,
+) -> Result<(), CodegenError> {
+    let trait_name = format!("{}Handler", iface.name);
+
+    // Operations sharing a path are routed together, e.g. `get(..).delete(..)`
+    let mut routes: Vec<(String, Vec<&HttpOperation<'_>>)> = Vec::new();
+    for http_op in ops {
+        let path = axum_path(&http_op.path);
+        match routes.iter_mut().find(|(p, _)| *p == path) {
+            Some((_, route_ops)) => route_ops.push(http_op),
+            None => routes.push((path, vec![http_op])),
+        }
+    }
+
+    writeln!(out)?;
+    writeln!(out, "/// Routes of [`{}`]", trait_name)?;
+    writeln!(out, "pub mod {} {{", field_name(&iface.name))?;
+    writeln!(out, "    use super::*;")?;
+    writeln!(out, "    use axum::response::IntoResponse;")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    /// Route every operation of [`{}`] to `handler`",
+        trait_name
+    )?;
+    writeln!(
+        out,
+        "    pub fn router<H: {}>(handler: Arc<H>) -> Router {{",
+        trait_name
+    )?;
+    writeln!(out, "        Router::new()")?;
+    for (path, route_ops) in &routes {
+        let methods: Vec<_> = route_ops
+            .iter()
+            .map(|http_op| {
+                format!(
+                    "{}({}::<H>)",
+                    http_op.method.to_lowercase(),
+                    http_op.op.name.to_case(Case::Snake)
+                )
+            })
+            .collect();
+        writeln!(
+            out,
+            r#"            .route("{}", axum::routing::{})"#,
+            path,
+            methods.join(".")
+        )?;
+    }
+    writeln!(out, "            .with_state(handler)")?;
+    writeln!(out, "    }}")?;
+
+    for http_op in ops {
+        write_route_handler(out, &iface.name, http_op, scalars, models)?;
+    }
+
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+/// Write the axum handler of an operation: extractors for its path, query,
+/// header and body params, then the trait call, responding with the status
+/// of its success response or the typed error
+fn write_route_handler(
+    out: &mut String,
+    iface_name: &str,
+    http_op: &HttpOperation<'_>,
+    scalars: &ScalarMap,
+    models: &ModelMap<'_>,
+) -> Result<(), CodegenError> {
+    let op = http_op.op;
+    let fn_name = op.name.to_case(Case::Snake);
+    let param_ty = |param: &OperationParam| {
+        let optional = param.optional && param_default(param, scalars).is_none();
+        param_type(param, optional, scalars)
+    };
+
+    let mut extractors = vec!["State(handler): State<Arc<H>>".to_string()];
+
+    // Path params in the order of the route
+    let path: Vec<_> = path_params(&http_op.path)
+        .into_iter()
+        .filter_map(|name| {
+            http_op
+                .params_at(ParamLocation::Path)
+                .find(|p| p.name == name)
+        })
+        .collect();
+    match path.as_slice() {
+        [] => {}
+        [param] => extractors.push(format!(
+            "Path({}): Path<{}>",
+            field_name(&param.name),
+            param_ty(param)
+        )),
+        _ => {
+            let names: Vec<_> = path.iter().map(|p| field_name(&p.name)).collect();
+            let types: Vec<_> = path.iter().map(|p| param_ty(p)).collect();
+            extractors.push(format!(
+                "Path(({})): Path<({})>",
+                names.join(", "),
+                types.join(", ")
+            ));
+        }
+    }
+    if http_op.params_at(ParamLocation::Query).next().is_some() {
+        extractors.push(format!(
+            "Query(query): Query<{}>",
+            request_params_name(iface_name, op, ParamLocation::Query)
+        ));
+    }
+    if http_op.params_at(ParamLocation::Header).next().is_some() {
+        extractors.push(format!(
+            "headers: {}",
+            request_params_name(iface_name, op, ParamLocation::Header)
+        ));
+    }
+
+    // The body is extracted last. Models, and lists of them, are validated
+    // before the handler runs.
+    if let Some(body) = http_op.body() {
+        let ty = param_type(body, false, scalars);
+        let extractor = match nested_model(&body.type_ref, models) {
+            Some(_) => "ValidatedJson",
+            None => "Json",
+        };
+        let name = field_name(&body.name);
+        if body.optional {
+            extractors.push(format!("{}: Option<{}<{}>>", name, extractor, ty));
+        } else {
+            extractors.push(format!("{}({}): {}<{}>", extractor, name, extractor, ty));
+        }
+    } else if http_op
+        .params_at(ParamLocation::BodyProperty)
+        .next()
+        .is_some()
+    {
+        extractors.push(format!(
+            "ValidatedJson(payload): ValidatedJson<{}>",
+            request_params_name(iface_name, op, ParamLocation::BodyProperty)
+        ));
+    }
+
+    let args: Vec<_> = http_op
+        .params
+        .iter()
+        .map(|param| {
+            let field = field_name(&param.name);
+            match param.location {
+                ParamLocation::Path => field,
+                ParamLocation::Query => format!("query.{}", field),
+                ParamLocation::Header => format!("headers.{}", field),
+                ParamLocation::Body if param.optional => format!("{}.map(|b| b.0)", field),
+                ParamLocation::Body => field,
+                ParamLocation::BodyProperty => format!("payload.{}", field),
+            }
+        })
+        .collect();
+
+    writeln!(out)?;
+    writeln!(out, "    async fn {}<H: {}Handler>(", fn_name, iface_name)?;
+    for extractor in &extractors {
+        writeln!(out, "        {},", extractor)?;
+    }
+    writeln!(out, "    ) -> axum::response::Response {{")?;
+    writeln!(
+        out,
+        "        match handler.{}({}).await {{",
+        fn_name,
+        args.join(", ")
+    )?;
+    match http_op.responses.as_slice() {
+        [response] => {
+            let status = status_code(response.status.unwrap_or(200));
+            if response.body.is_none() && response.headers.is_empty() {
+                writeln!(out, "            Ok(()) => {}.into_response(),", status)?;
+            } else if response.headers.is_empty() {
+                writeln!(
+                    out,
+                    "            Ok(body) => ({}, Json(body)).into_response(),",
+                    status
+                )?;
+            } else {
+                writeln!(
+                    out,
+                    "            Ok(response) => ({}, response).into_response(),",
+                    status
+                )?;
+            }
+        }
+        _ => writeln!(out, "            Ok(response) => response.into_response(),")?,
+    }
+    writeln!(out, "            Err(error) => error.into_response(),")?;
+    writeln!(out, "        }}")?;
+    writeln!(out, "    }}")?;
+    Ok(())
+}
+
+/// The path of a route in axum's syntax, e.g. `/tasks/:id` for `/tasks/{id}`
+fn axum_path(path: &str) -> String {
+    path.replace('{', ":").replace('}', "")
+}
+
+/// The `StatusCode` of a status, e.g. `StatusCode::CREATED` for 201
+fn status_code(status: u16) -> String {
+    let name = status_name(Some(status));
+    if name.starts_with("Status") {
+        format!("StatusCode::from_u16({}).unwrap_or(StatusCode::OK)", status)
+    } else {
+        format!("StatusCode::{}", name.to_case(Case::UpperSnake))
+    }
+}
+
+/// Readers of request headers, used by the `@header` extractors
+const REQUEST_HEADER_RUNTIME: &str = r#"
+/// Read a request header, rejecting the request with 400 when it is invalid
+pub fn request_header<T: std::str::FromStr>(
+    headers: &axum::http::HeaderMap,
+    name: &str,
+) -> Result<Option<T>, ApiError> {
+    let Some(value) = headers.get(name) else {
+        return Ok(None);
+    };
+    let value = value.to_str().ok().and_then(|v| v.parse().ok());
+    value.map(Some).ok_or_else(|| ApiError {
+        status: StatusCode::BAD_REQUEST.as_u16(),
+        code: "INVALID_HEADER".to_string(),
+        message: format!("invalid header {}", name),
+    })
+}
+
+/// Read a request header, rejecting the request with 400 without it
+pub fn required_header<T: std::str::FromStr>(
+    headers: &axum::http::HeaderMap,
+    name: &str,
+) -> Result<T, ApiError> {
+    request_header(headers, name)?.ok_or_else(|| ApiError {
+        status: StatusCode::BAD_REQUEST.as_u16(),
+        code: "MISSING_HEADER".to_string(),
+        message: format!("missing header {}", name),
+    })
+}"#;
+
+/// Write the extractor of an operation's `@header` params, e.g.
+/// `TasksCreateHeaders { request_id }` for `@header("X-Request-ID") requestId`
+fn write_request_headers(
+    out: &mut String,
+    iface_name: &str,
+    http_op: &HttpOperation<'_>,
+    scalars: &ScalarMap,
+) -> Result<(), CodegenError> {
+    let op = http_op.op;
+    let struct_name = request_params_name(iface_name, op, ParamLocation::Header);
+    let headers: Vec<_> = http_op.params_at(ParamLocation::Header).collect();
+
+    writeln!(out)?;
+    writeln!(out, "/// Request headers of `{}.{}`", iface_name, op.name)?;
+    writeln!(out, "#[derive(Debug, Clone)]")?;
+    writeln!(out, "pub struct {} {{", struct_name)?;
+    for param in &headers {
+        let optional = param.optional && param_default(param, scalars).is_none();
+        writeln!(
+            out,
+            "    pub {}: {},",
+            field_name(&param.name),
+            param_type(param, optional, scalars)
+        )?;
+    }
+    writeln!(out, "}}")?;
+
+    writeln!(out)?;
+    writeln!(out, "#[async_trait]")?;
+    writeln!(
+        out,
+        "impl<S: Send + Sync> axum::extract::FromRequestParts<S> for {} {{",
+        struct_name
+    )?;
+    writeln!(out, "    type Rejection = ApiError;")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    async fn from_request_parts(parts: &mut axum::http::request::Parts, _state: &S) -> Result<Self, Self::Rejection> {{"
+    )?;
+    writeln!(out, "        Ok(Self {{")?;
+    for param in &headers {
+        let name = header_name(&param.decorators, &param.name);
+        let field = field_name(&param.name);
+        match param_default(param, scalars) {
+            Some(default) if param.optional => writeln!(
+                out,
+                r#"            {}: request_header(&parts.headers, "{}")?.unwrap_or_else(|| {}),"#,
+                field, name, default
+            )?,
+            _ => writeln!(
+                out,
+                r#"            {}: {}(&parts.headers, "{}")?,"#,
+                field,
+                if param.optional {
+                    "request_header"
+                } else {
+                    "required_header"
+                },
+                name
+            )?,
+        }
+    }
+    writeln!(out, "        }})")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+/// Write the struct of an operation's `@query` params, e.g. `TasksListQuery`
+/// read with `Query<TasksListQuery>`, or of the properties of its implicit
+/// body, e.g. `TasksUpdateBody`. Params with a default take it when left out.
+fn write_request_params(
+    out: &mut String,
+    iface_name: &str,
+    http_op: &HttpOperation<'_>,
+    location: ParamLocation,
+    scalars: &ScalarMap,
+) -> Result<(), CodegenError> {
+    let op = http_op.op;
+    let struct_name = request_params_name(iface_name, op, location);
+    let params: Vec<_> = http_op.params_at(location).collect();
+
+    writeln!(out)?;
+    if location == ParamLocation::Query {
+        writeln!(out, "/// Query params of `{}.{}`", iface_name, op.name)?;
+    } else {
+        writeln!(out, "/// Request body of `{}.{}`", iface_name, op.name)?;
+    }
+    writeln!(out, "#[derive(Debug, Clone, serde::Deserialize)]")?;
+    writeln!(out, "pub struct {} {{", struct_name)?;
+    let mut defaults = Vec::new();
+    for param in &params {
+        let field = field_name(&param.name);
+        if field.trim_start_matches("r#") != param.name {
+            writeln!(out, r#"    #[serde(rename = "{}")]"#, param.name)?;
+        }
+        let default = param_default(param, scalars).filter(|_| param.optional);
+        let ty = param_type(param, param.optional && default.is_none(), scalars);
+        if let Some(default) = default {
+            let default_fn = format!("default_{}", param.name.to_case(Case::Snake));
+            writeln!(
+                out,
+                r#"    #[serde(default = "{}::{}")]"#,
+                struct_name, default_fn
+            )?;
+            defaults.push((default_fn, ty.clone(), default));
+        }
+        writeln!(out, "    pub {}: {},", field, ty)?;
+    }
+    writeln!(out, "}}")?;
+
+    if !defaults.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "impl {} {{", struct_name)?;
+        for (default_fn, ty, default) in &defaults {
+            writeln!(out, "    fn {}() -> {} {{", default_fn, ty)?;
+            writeln!(out, "        {}", default)?;
+            writeln!(out, "    }}")?;
+        }
+        writeln!(out, "}}")?;
+    }
+    Ok(())
+}
+
+/// Write the `Validate` impl of the struct of an operation's implicit body,
+/// checking its properties like the fields of a model
+fn write_body_validate_impl(
+    out: &mut String,
+    iface_name: &str,
+    http_op: &HttpOperation<'_>,
+    scalars: &ScalarMap,
+    constraints: &ConstraintMap,
+    models: &ModelMap<'_>,
+    validated: &HashSet<String>,
+) -> Result<(), CodegenError> {
+    let properties: Vec<Cow<'_, Property>> = http_op
+        .params_at(ParamLocation::BodyProperty)
+        .map(|param| {
+            // Params with a default are never left out
+            let optional = param.optional && param_default(param, scalars).is_none();
+            Cow::Owned(Property {
+                name: param.name.clone(),
+                decorators: param.decorators.clone(),
+                type_ref: param.type_ref.clone(),
+                optional,
+                default: param.default.clone(),
+                span: param.span,
+            })
+        })
+        .collect();
+    if properties.is_empty() {
+        return Ok(());
+    }
+
+    let header = format!(
+        "impl Validate for {}",
+        request_params_name(iface_name, http_op.op, ParamLocation::BodyProperty)
+    );
+    writeln!(out)?;
+    let checked = properties
+        .iter()
+        .any(|prop| is_checked(prop, constraints, models, validated));
+    if !checked {
+        writeln!(out, "{} {{}}", header)?;
+        return Ok(());
+    }
+    writeln!(out, "{} {{", header)?;
+    write_validate_fn(out, &properties, constraints, models, validated)?;
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+/// Name of the struct of an operation's params at `location`, e.g.
+/// `TasksListQuery` or `TasksListHeaders`
+fn request_params_name(iface_name: &str, op: &Operation, location: ParamLocation) -> String {
+    let suffix = match location {
+        ParamLocation::Query => "Query",
+        ParamLocation::Header => "Headers",
+        _ => "Body",
+    };
+    format!("{}{}{}", iface_name, op.name.to_case(Case::Pascal), suffix)
+}
+
+/// The HTTP operations of an interface, with the string literal unions of
+/// params spread from a model typed as the model's inline enum, e.g.
+/// `PaginationParamsOrder` for `order` of `...PaginationParams`
+fn rust_operations<'a>(iface: &'a Interface, models: &ModelMap<'a>) -> Vec<HttpOperation<'a>> {
+    let mut operations = http_operations(iface, models);
+    for http_op in &mut operations {
+        let op = http_op.op;
+        // Generic models have no inline enums of their own
+        let spread: Vec<_> = op
+            .params
+            .iter()
+            .filter(|p| p.spread)
+            .filter_map(|p| lookup_model(&p.type_ref, models))
+            .filter(|m| m.type_params.is_empty())
+            .collect();
+        for param in &mut http_op.params {
+            if !is_string_literals(&param.type_ref)
+                || op.params.iter().any(|p| !p.spread && p.name == param.name)
+            {
+                continue;
+            }
+            let model = spread.iter().find(|m| {
+                resolve_properties(m, models)
+                    .iter()
+                    .any(|p| p.name == param.name)
+            });
+            if let Some(model) = model {
+                let name = format!("{}{}", model.name, param.name.to_case(Case::Pascal));
+                param.param.to_mut().type_ref = TypeRef::Named(name);
+            }
+        }
+    }
+    operations
+}
+
+/// Whether a type is a union of string literals, e.g. `"asc" | "desc"`
+fn is_string_literals(type_ref: &TypeRef) -> bool {
+    match type_ref {
+        TypeRef::Union(variants) => variants
+            .iter()
+            .all(|v| matches!(v, TypeRef::StringLiteral(_))),
+        _ => false,
+    }
+}
+
+/// Rust type of an operation param. String literal unions, e.g.
+/// `"asc" | "desc"`, are taken as `String`, but for those spread from a
+/// model (see [`rust_operations`]).
+fn param_type(param: &OperationParam, optional: bool, scalars: &ScalarMap) -> String {
+    if !is_string_literals(&param.type_ref) {
+        return type_to_rust(&param.type_ref, optional, scalars);
+    }
+    if optional {
+        "Option<String>".to_string()
+    } else {
+        "String".to_string()
+    }
+}
+
+/// Rust expression of a param's default value, e.g. `1` for `page?: int32 = 1`
+fn param_default(param: &OperationParam, scalars: &ScalarMap) -> Option<String> {
+    let ty = param_type(param, false, scalars);
+    let expr = match param.default.as_ref()? {
+        // A variant of the inline enum of a spread param
+        Value::String(s) if matches!(&param.type_ref, TypeRef::Named(name) if *name == ty) => {
+            format!("{}::{}", ty, s.to_case(Case::Pascal))
+        }
+        Value::String(s) => format!("{:?}.to_string()", s),
+        Value::Int(n) if ty == "f32" || ty == "f64" => format!("{}.0", n),
+        Value::Int(n) => n.to_string(),
+        Value::Float(n) => format!("{:?}", n),
+        Value::Bool(b) => b.to_string(),
+        Value::QualifiedIdent(parts) => match parts.as_slice() {
+            [.., enum_name, member] => {
+                format!("{}::{}", enum_name, member.to_case(Case::Pascal))
+            }
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(expr)
+}
+
+/// Write the `IntoResponse` impl of an operation's response enum: the body
+/// with the status of its variant and its `@header`s, or just the status for
+/// no content
+fn write_response_into_response(
+    out: &mut String,
+    iface_name: &str,
+    op: &Operation,
+    responses: &[Response<'_>],
+) -> Result<(), CodegenError> {
+    writeln!(out)?;
+    writeln!(
+        out,
+        "impl axum::response::IntoResponse for crate::models::{} {{",
+        response_enum_name(iface_name, op)
+    )?;
+    writeln!(
+        out,
+        "    fn into_response(self) -> axum::response::Response {{"
+    )?;
+    writeln!(
+        out,
+        "        let status = StatusCode::from_u16(self.status()).unwrap_or(StatusCode::OK);"
+    )?;
+    let empty: Vec<_> = responses
+        .iter()
+        .filter(|r| r.body.is_none() && r.headers.is_empty())
+        .map(|r| format!("Self::{}", status_name(r.status)))
+        .collect();
+    let with_headers: Vec<_> = responses.iter().filter(|r| !r.headers.is_empty()).collect();
+    if empty.is_empty() && with_headers.is_empty() {
+        writeln!(out, "        (status, Json(self)).into_response()")?;
+    } else {
+        writeln!(out, "        match self {{")?;
+        if !empty.is_empty() {
+            writeln!(
+                out,
+                "            {} => status.into_response(),",
+                empty.join(" | ")
+            )?;
+        }
+        // Each response has its own type of headers
+        for response in &with_headers {
+            writeln!(
+                out,
+                "            Self::{}(response) => (status, response).into_response(),",
+                status_name(response.status)
+            )?;
+        }
+        if empty.len() + with_headers.len() < responses.len() {
+            writeln!(
+                out,
+                "            body => (status, Json(body)).into_response(),"
+            )?;
+        }
+        writeln!(out, "        }}")?;
+    }
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
 /// Convert TypeSpec type to Rust type string
 pub fn type_to_rust(type_ref: &TypeRef, optional: bool, scalars: &ScalarMap) -> String {
     let base = match type_ref {
@@ -785,36 +2508,18 @@ fn builtin_to_rust(name: &str) -> String {
     }
 }
 
-fn get_description(decorators: &[Decorator]) -> Option<String> {
-    decorators
-        .iter()
-        .find(|d| d.name == "doc")
-        .and_then(|d| d.get_string_arg(0).map(|s| s.to_string()))
-}
-
-fn get_route(decorators: &[Decorator]) -> Option<String> {
-    decorators
-        .iter()
-        .find(|d| d.name == "route")
-        .and_then(|d| d.get_string_arg(0).map(|s| s.to_string()))
-}
-
-fn get_http_method(decorators: &[Decorator]) -> &'static str {
-    for d in decorators {
-        match d.name.as_str() {
-            "get" => return "GET",
-            "post" => return "POST",
-            "put" => return "PUT",
-            "patch" => return "PATCH",
-            "delete" => return "DELETE",
-            _ => {}
+/// Write the description of an item as `///` lines
+fn write_doc(out: &mut String, indent: &str, decorators: &[Decorator]) -> std::fmt::Result {
+    if let Some(desc) = description(decorators) {
+        for line in desc.lines() {
+            if line.is_empty() {
+                writeln!(out, "{}///", indent)?;
+            } else {
+                writeln!(out, "{}/// {}", indent, line)?;
+            }
         }
     }
-    "GET"
-}
-
-fn has_decorator(decorators: &[Decorator], name: &str) -> bool {
-    decorators.iter().any(|d| d.name == name)
+    Ok(())
 }
 
 fn is_rust_keyword(name: &str) -> bool {
@@ -1,10 +1,22 @@
 //! TypeScript Code Generator
 
 use crate::ast::*;
-use crate::codegen::{
-    build_model_map, build_scalar_map, resolve_properties, CodegenError, ModelMap, ScalarMap, Side,
+use crate::codegen::validation::{
+    build_constraint_map, nested_model, number_literal, validated_models, Check, ConstraintMap,
+    Constraints,
+};
+use crate::codegen::{description, CodegenError, Side};
+use crate::semantic::http::{
+    header_name, http_operations, is_error_model, is_metadata, status_literal, status_name,
+    status_property, HttpOperation, HttpParam, ParamLocation, Response,
+};
+use crate::semantic::inheritance::lookup_model;
+use crate::semantic::{
+    build_model_map, build_scalar_map, collect_services, own_properties, resolve_properties,
+    ModelMap, ScalarMap,
 };
 use convert_case::{Case, Casing};
+use std::collections::HashSet;
 use std::fmt::Write;
 use std::fs;
 use std::path::Path;
@@ -18,11 +30,12 @@ pub fn generate(
     let mut generated = Vec::new();
     let scalars = build_scalar_map(file);
     let models = build_model_map(file);
+    let constraints = build_constraint_map(file);
 
     fs::create_dir_all(output_dir)?;
 
     // Generate models
-    let models_content = generate_models(file, &scalars, &models)?;
+    let models_content = generate_models(file, &scalars, &constraints, &models)?;
     let models_path = output_dir.join("models.ts");
     fs::write(&models_path, models_content)?;
     generated.push(models_path.display().to_string());
@@ -35,7 +48,7 @@ pub fn generate(
 
     // Generate client
     if matches!(side, Side::Client | Side::Both) {
-        let client_content = generate_client(file)?;
+        let client_content = generate_client(file, &models)?;
         let client_path = output_dir.join("client.ts");
         fs::write(&client_path, client_content)?;
         generated.push(client_path.display().to_string());
@@ -43,14 +56,14 @@ pub fn generate(
 
     // Generate server
     if matches!(side, Side::Server | Side::Both) {
-        let server_content = generate_server(file)?;
+        let server_content = generate_server(file, &constraints, &models)?;
         let server_path = output_dir.join("server.ts");
         fs::write(&server_path, server_content)?;
         generated.push(server_path.display().to_string());
     }
 
     // Generate index
-    let index_content = generate_index(side)?;
+    let index_content = generate_index(file, side)?;
     let index_path = output_dir.join("index.ts");
     fs::write(&index_path, index_content)?;
     generated.push(index_path.display().to_string());
@@ -61,9 +74,11 @@ pub fn generate(
 fn generate_models(
     file: &TypeSpecFile,
     _scalars: &ScalarMap,
+    constraints: &ConstraintMap,
     models: &ModelMap<'_>,
 ) -> Result<String, CodegenError> {
     let mut out = String::new();
+    let validated = validated_models(file, models, constraints);
 
     writeln!(out, "/**")?;
     writeln!(out, " * Auto-generated models from TypeSpec.")?;
@@ -81,11 +96,24 @@ fn generate_models(
         )?;
     }
 
+    writeln!(out)?;
+    writeln!(
+        out,
+        "/** A value that breaks a constraint of the TypeSpec schema */"
+    )?;
+    writeln!(out, "export class ValidationError extends Error {{")?;
+    writeln!(out, "  readonly field: string;")?;
+    writeln!(out)?;
+    writeln!(out, "  constructor(field: string, message: string) {{")?;
+    writeln!(out, "    super(`${{field}}: ${{message}}`);")?;
+    writeln!(out, "    this.name = 'ValidationError';")?;
+    writeln!(out, "    this.field = field;")?;
+    writeln!(out, "  }}")?;
+    writeln!(out, "}}")?;
+
     for model in file.models() {
         writeln!(out)?;
-        if let Some(desc) = get_description(&model.decorators) {
-            writeln!(out, "/** {} */", desc)?;
-        }
+        write_tsdoc(&mut out, "", &model.decorators)?;
 
         // Add type parameters if present
         let type_params = if model.type_params.is_empty() {
@@ -93,23 +121,237 @@ fn generate_models(
         } else {
             format!("<{}>", model.type_params.join(", "))
         };
-        writeln!(out, "export interface {}{} {{", model.name, type_params)?;
+        let extends = model
+            .extends
+            .as_ref()
+            .map(|base| format!(" extends {}", type_to_typescript(base)))
+            .unwrap_or_default();
+        writeln!(
+            out,
+            "export interface {}{}{} {{",
+            model.name, type_params, extends
+        )?;
 
-        // Resolve spread references and get all properties
-        let all_properties = resolve_properties(model, models);
+        // Inherited properties come from the extended interface. A literal
+        // `@statusCode` is implied by the model; other `@statusCode`s and
+        // `@header`s are not part of the body, so it need not carry them.
+        let properties = own_properties(model, models)
+            .into_iter()
+            .filter(|p| status_literal(p).is_none());
 
-        for prop in all_properties {
+        for prop in properties {
             let ts_type = type_to_typescript(&prop.type_ref);
-            let optional = if prop.optional { "?" } else { "" };
+            let optional = if prop.optional || is_metadata(&prop) {
+                "?"
+            } else {
+                ""
+            };
+            write_tsdoc(&mut out, "  ", &prop.decorators)?;
             writeln!(out, "  {}{}: {};", prop.name, optional, ts_type)?;
         }
 
         writeln!(out, "}}")?;
+
+        if validated.contains(&model.name) {
+            write_validator(&mut out, model, constraints, models, &validated)?;
+        }
+    }
+
+    // Operations with several success responses return a union discriminated
+    // by status
+    for iface in &collect_services(file) {
+        for http_op in http_operations(iface, models) {
+            let (op, responses) = (http_op.op, &http_op.responses);
+            if responses.len() < 2 {
+                continue;
+            }
+            writeln!(out)?;
+            writeln!(out, "/** Responses of `{}.{}` */", iface.name, op.name)?;
+            writeln!(out, "export type {} =", response_type_name(&iface.name, op))?;
+            for (i, response) in responses.iter().enumerate() {
+                let status = response
+                    .status
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| "number".to_string());
+                let end = if i + 1 == responses.len() { ";" } else { "" };
+                let mut fields = vec![format!("status: {}", status)];
+                if let Some(body) = &response.body {
+                    fields.push(format!("body: {}", type_to_typescript(body)));
+                }
+                if !response.headers.is_empty() {
+                    fields.push(format!(
+                        "headers: {}",
+                        http_op.headers_name(&iface.name, response)
+                    ));
+                }
+                writeln!(out, "  | {{ {} }}{}", fields.join("; "), end)?;
+            }
+        }
+    }
+
+    // Bodies sent with `@header`s are returned with them
+    let header_responses = response_headers(file, models);
+    if header_responses.iter().any(|(_, _, several, _)| !several) {
+        writeln!(out)?;
+        writeln!(
+            out,
+            "/** A response body with the `@header`s sent along with it */"
+        )?;
+        writeln!(out, "export interface WithHeaders<T, H> {{")?;
+        writeln!(out, "  body: T;")?;
+        writeln!(out, "  headers: H;")?;
+        writeln!(out, "}}")?;
+    }
+    for (name, doc, _, headers) in &header_responses {
+        writeln!(out)?;
+        writeln!(out, "/** {} */", doc)?;
+        writeln!(out, "export interface {} {{", name)?;
+        for header in headers {
+            write_tsdoc(&mut out, "  ", &header.decorators)?;
+            let optional = if header.optional { "?" } else { "" };
+            writeln!(
+                out,
+                "  {}{}: {};",
+                header.name,
+                optional,
+                type_to_typescript(&header.type_ref)
+            )?;
+        }
+        writeln!(out, "}}")?;
     }
 
     Ok(out)
 }
 
+/// The `@header`s of the responses that have any: the name of their type,
+/// its doc, whether its operation has several responses, and the headers
+fn response_headers(
+    file: &TypeSpecFile,
+    models: &ModelMap<'_>,
+) -> Vec<(String, String, bool, Vec<Property>)> {
+    let mut headers = Vec::new();
+    for iface in &collect_services(file) {
+        for http_op in http_operations(iface, models) {
+            let op = http_op.op;
+            for response in http_op.header_responses() {
+                let doc = if http_op.responses.len() > 1 {
+                    format!(
+                        "Headers of the `{}` response of `{}.{}`",
+                        status_name(response.status),
+                        iface.name,
+                        op.name
+                    )
+                } else {
+                    format!("Response headers of `{}.{}`", iface.name, op.name)
+                };
+                headers.push((
+                    http_op.headers_name(&iface.name, response),
+                    doc,
+                    http_op.responses.len() > 1,
+                    response.headers.clone(),
+                ));
+            }
+        }
+    }
+    headers
+}
+
+/// Write the `validate<Model>` guard of a model: a check per constraint and
+/// a validator call per property holding a validated model.
+fn write_validator(
+    out: &mut String,
+    model: &Model,
+    constraints: &ConstraintMap,
+    models: &ModelMap<'_>,
+    validated: &HashSet<String>,
+) -> std::fmt::Result {
+    let type_params = if model.type_params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", model.type_params.join(", "))
+    };
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        "/** Check the constraints of `{}`, throwing `ValidationError` on the first violation */",
+        model.name
+    )?;
+    writeln!(
+        out,
+        "export function validate{}{}(value: {}{}, path = ''): void {{",
+        model.name, type_params, model.name, type_params
+    )?;
+    for prop in resolve_properties(model, models) {
+        let value = format!("value.{}", prop.name);
+        let optional = prop.optional || matches!(prop.type_ref, TypeRef::Optional(_));
+
+        let checks = Constraints::of(&prop.decorators, &prop.type_ref, constraints);
+        for check in checks.checks() {
+            let condition = match check {
+                // Strings are measured in code points, not UTF-16 units
+                Check::MinLength(n) => format!("[...{}].length < {}", value, n),
+                Check::MaxLength(n) => format!("[...{}].length > {}", value, n),
+                Check::MinItems(n) => format!("{}.length < {}", value, n),
+                Check::MaxItems(n) => format!("{}.length > {}", value, n),
+                Check::MinValue(n) => format!("{} < {}", value, number_literal(n)),
+                Check::MaxValue(n) => format!("{} > {}", value, number_literal(n)),
+                Check::Pattern(pattern) => {
+                    format!("!new RegExp({}).test({})", string_literal(pattern), value)
+                }
+            };
+            if optional {
+                writeln!(out, "  if ({} != null && {}) {{", value, condition)?;
+            } else {
+                writeln!(out, "  if ({}) {{", condition)?;
+            }
+            writeln!(
+                out,
+                "    throw new ValidationError(`${{path}}{}`, {});",
+                prop.name,
+                string_literal(&check.message())
+            )?;
+            writeln!(out, "  }}")?;
+        }
+
+        let Some(nested) = nested_model(&prop.type_ref, models)
+            .filter(|nested| validated.contains(&nested.model.name))
+        else {
+            continue;
+        };
+        if nested.array {
+            writeln!(
+                out,
+                "  {}{}.forEach((item, i) => validate{}(item, `${{path}}{}[${{i}}].`));",
+                value,
+                if optional { "?" } else { "" },
+                nested.model.name,
+                prop.name
+            )?;
+        } else if optional {
+            writeln!(out, "  if ({} != null) {{", value)?;
+            writeln!(
+                out,
+                "    validate{}({}, `${{path}}{}.`);",
+                nested.model.name, value, prop.name
+            )?;
+            writeln!(out, "  }}")?;
+        } else {
+            writeln!(
+                out,
+                "  validate{}({}, `${{path}}{}.`);",
+                nested.model.name, value, prop.name
+            )?;
+        }
+    }
+    writeln!(out, "}}")
+}
+
+/// TypeScript string literal for `s`
+fn string_literal(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_default()
+}
+
 fn generate_enums(file: &TypeSpecFile) -> Result<String, CodegenError> {
     let mut out = String::new();
 
@@ -121,6 +363,7 @@ fn generate_enums(file: &TypeSpecFile) -> Result<String, CodegenError> {
 
     for enum_def in file.enums() {
         writeln!(out)?;
+        write_tsdoc(&mut out, "", &enum_def.decorators)?;
         writeln!(out, "export enum {} {{", enum_def.name)?;
 
         for member in &enum_def.members {
@@ -134,6 +377,7 @@ fn generate_enums(file: &TypeSpecFile) -> Result<String, CodegenError> {
                 .unwrap_or_else(|| member.name.to_case(Case::Snake));
 
             let variant = member.name.to_case(Case::Pascal);
+            write_tsdoc(&mut out, "  ", &member.decorators)?;
             writeln!(out, r#"  {} = "{}","#, variant, value)?;
         }
 
@@ -143,7 +387,7 @@ fn generate_enums(file: &TypeSpecFile) -> Result<String, CodegenError> {
     Ok(out)
 }
 
-fn generate_client(file: &TypeSpecFile) -> Result<String, CodegenError> {
+fn generate_client(file: &TypeSpecFile, models: &ModelMap<'_>) -> Result<String, CodegenError> {
     let mut out = String::new();
 
     writeln!(out, "/**")?;
@@ -152,8 +396,19 @@ fn generate_client(file: &TypeSpecFile) -> Result<String, CodegenError> {
     writeln!(out, " */")?;
     writeln!(out)?;
 
-    // Collect all model and enum names for imports
-    let model_names: Vec<_> = file.models().map(|m| m.name.as_str()).collect();
+    // Collect all model and enum names for imports. Models named like a
+    // class of the client are imported as `<Name>Model`.
+    let local_names = client_names(file);
+    let model_names: Vec<_> = model_exports(file, models)
+        .into_iter()
+        .map(|name| {
+            if local_names.contains(&name) {
+                format!("{} as {}", name, model_alias(&name, &local_names))
+            } else {
+                name
+            }
+        })
+        .collect();
     let enum_names: Vec<_> = file.enums().map(|e| e.name.as_str()).collect();
 
     if !model_names.is_empty() {
@@ -176,16 +431,33 @@ fn generate_client(file: &TypeSpecFile) -> Result<String, CodegenError> {
     writeln!(
         out,
         r#"
-export class ApiError extends Error {{
+/**
+ * Error of a request. `error` holds the `@error` model of the response when
+ * the operation declares one for its status.
+ */
+export class ApiError<E = unknown> extends Error {{
   constructor(
     public statusCode: number,
     public code: string,
-    message: string
+    message: string,
+    public error?: E
   ) {{
     super(message);
   }}
 }}
 
+/** Decode the body of an error response, if it is a given `@error` model */
+type ErrorDecoder = (status: number, body: any) => unknown;
+
+interface RequestOptions {{
+  body?: unknown;
+  query?: Record<string, unknown>;
+  headers?: Record<string, unknown>;
+  errors?: ErrorDecoder[];
+  /** Readers of the `@header`s of responses, by status or `default` */
+  responseHeaders?: Record<string, (headers: Headers) => unknown>;
+}}
+
 export interface ClientConfig {{
   baseUrl: string;
   accessToken?: string;
@@ -207,11 +479,41 @@ export class BaseClient {{
     this.accessToken = token;
   }}
 
-  protected async request<T>(
+  protected async request<T>(method: string, path: string, options: RequestOptions = {{}}): Promise<T> {{
+    const resp = await this.send(method, path, options);
+    if (resp.status === 204) return undefined as T;
+    return resp.json();
+  }}
+
+  /** Send a request whose response is returned with its status */
+  protected async requestResponse<T>(
     method: string,
     path: string,
-    options: {{ body?: unknown; query?: Record<string, unknown> }} = {{}}
+    options: RequestOptions = {{}}
   ): Promise<T> {{
+    const resp = await this.send(method, path, options);
+    const response: Record<string, unknown> = {{ status: resp.status }};
+    if (resp.status !== 204) response.body = await resp.json();
+    const readHeaders =
+      options.responseHeaders?.[resp.status] ?? options.responseHeaders?.['default'];
+    if (readHeaders) response.headers = readHeaders(resp.headers);
+    return response as T;
+  }}
+
+  /** Send a request whose response headers are returned with its body */
+  protected async requestWithHeaders<T, H>(
+    method: string,
+    path: string,
+    readHeaders: (headers: Headers) => H,
+    options: RequestOptions = {{}}
+  ): Promise<{{ body: T; headers: H }}> {{
+    const resp = await this.send(method, path, options);
+    const headers = readHeaders(resp.headers);
+    if (resp.status === 204) return {{ body: undefined as T, headers }};
+    return {{ body: await resp.json(), headers }};
+  }}
+
+  private async send(method: string, path: string, options: RequestOptions): Promise<Response> {{
     const url = new URL(path, this.baseUrl);
     if (options.query) {{
       for (const [k, v] of Object.entries(options.query)) {{
@@ -223,6 +525,9 @@ export class BaseClient {{
     if (this.accessToken) {{
       headers['Authorization'] = `Bearer ${{this.accessToken}}`;
     }}
+    for (const [k, v] of Object.entries(options.headers ?? {{}})) {{
+      if (v !== undefined) headers[k] = String(v);
+    }}
 
     const resp = await this.fetchFn(url.toString(), {{
       method,
@@ -232,94 +537,207 @@ export class BaseClient {{
 
     if (!resp.ok) {{
       const err = await resp.json().catch(() => ({{}}));
-      throw new ApiError(resp.status, err.code ?? 'ERROR', err.message ?? resp.statusText);
+      const message = err.message ?? resp.statusText;
+      for (const decode of options.errors ?? []) {{
+        const error = decode(resp.status, err);
+        if (error !== undefined) {{
+          throw new ApiError(resp.status, err.code ?? 'ERROR', message, error);
+        }}
+      }}
+      throw new ApiError(resp.status, err.code ?? 'ERROR', message);
     }}
 
-    if (resp.status === 204) return undefined as T;
-    return resp.json();
+    return resp;
   }}
 }}
 "#
     )?;
 
+    // Decoders of `@error` models
+    for model in file
+        .models()
+        .filter(|m| is_error_model(m) && m.type_params.is_empty())
+    {
+        let model_type = model_alias(&model.name, &local_names);
+        let status_prop = status_property(model, models);
+        writeln!(out)?;
+        writeln!(
+            out,
+            "function decode{}(status: number, body: any): {} | undefined {{",
+            model.name, model_type
+        )?;
+        match status_prop.as_ref().map(|p| (status_literal(p), p)) {
+            Some((Some(code), _)) => {
+                writeln!(out, "  return status === {} ? body : undefined;", code)?
+            }
+            Some((None, prop)) => writeln!(out, "  return {{ ...body, {}: status }};", prop.name)?,
+            None => writeln!(out, "  return body;")?,
+        }
+        writeln!(out, "}}")?;
+    }
+
+    // Readers of response `@header`s
+    for (name, _, _, headers) in &response_headers(file, models) {
+        writeln!(out)?;
+        writeln!(out, "function read{}(headers: Headers): {} {{", name, name)?;
+        writeln!(out, "  return {{")?;
+        for header in headers {
+            let header_name = header_name(&header.decorators, &header.name);
+            let number = type_to_typescript(&header.type_ref) == "number";
+            let value = match (header.optional, number) {
+                (false, false) => format!("headers.get('{}') ?? ''", header_name),
+                (false, true) => format!("Number(headers.get('{}'))", header_name),
+                (true, false) => format!("headers.get('{}') ?? undefined", header_name),
+                (true, true) => format!(
+                    "headers.has('{name}') ? Number(headers.get('{name}')) : undefined",
+                    name = header_name
+                ),
+            };
+            writeln!(out, "    {}: {},", header.name, value)?;
+        }
+        writeln!(out, "  }};")?;
+        writeln!(out, "}}")?;
+    }
+
     // Service clients
-    for iface in file.interfaces() {
-        let base_path = get_route(&iface.decorators).unwrap_or_default();
+    for iface in &collect_services(file) {
         let class_name = format!("{}Client", iface.name);
 
         writeln!(out)?;
+        write_tsdoc(&mut out, "", &iface.decorators)?;
         writeln!(out, "export class {} extends BaseClient {{", class_name)?;
 
-        for op in &iface.operations {
-            let method = get_http_method(&op.decorators);
-            let op_path = get_route(&op.decorators).unwrap_or_default();
-            let full_path = format!("{}{}", base_path, op_path);
+        for http_op in http_operations(iface, models) {
+            let (op, method) = (http_op.op, http_op.method);
 
             writeln!(out)?;
+            write_tsdoc(&mut out, "  ", &op.decorators)?;
             write!(out, "  async {}(", op.name.to_case(Case::Camel))?;
 
             // Parameters
-            let mut params = Vec::new();
-            for param in &op.params {
+            let mut args = Vec::new();
+            for param in &http_op.params {
                 let name = param.name.to_case(Case::Camel);
                 let ty = type_to_typescript(&param.type_ref);
-                let optional = if param.optional { "?" } else { "" };
-                params.push(format!("{}{}: {}", name, optional, ty));
+                match &param.default {
+                    Some(default) => args.push(format!(
+                        "{}: {} = {}",
+                        name,
+                        ty,
+                        value_to_typescript(default)
+                    )),
+                    None => {
+                        let optional = if param.optional { "?" } else { "" };
+                        args.push(format!("{}{}: {}", name, optional, ty));
+                    }
+                }
             }
-            write!(out, "{}", params.join(", "))?;
+            write!(out, "{}", args.join(", "))?;
 
-            let return_type = op
-                .return_type
-                .as_ref()
-                .map(|t| type_to_typescript(t))
-                .unwrap_or_else(|| "void".to_string());
+            let return_type = success_type(&iface.name, &http_op);
 
             writeln!(out, "): Promise<{}> {{", return_type)?;
 
             // Build path
-            let mut path_expr = format!("`{}`", full_path);
-            for param in &op.params {
-                if has_decorator(&param.decorators, "path") {
-                    let name = param.name.to_case(Case::Camel);
-                    path_expr = path_expr
-                        .replace(&format!("{{{}}}", param.name), &format!("${{{}}}", name));
-                }
+            let mut path_expr = format!("`{}`", http_op.path);
+            for param in http_op.params_at(ParamLocation::Path) {
+                let name = param.name.to_case(Case::Camel);
+                path_expr =
+                    path_expr.replace(&format!("{{{}}}", param.name), &format!("${{{}}}", name));
             }
             writeln!(out, "    const path = {};", path_expr)?;
 
             // Query params
-            let query_params: Vec<_> = op
-                .params
-                .iter()
-                .filter(|p| has_decorator(&p.decorators, "query"))
-                .collect();
+            let query_params: Vec<_> = http_op.params_at(ParamLocation::Query).collect();
 
             // Body param
-            let body_param = op
-                .params
-                .iter()
-                .find(|p| has_decorator(&p.decorators, "body"));
-
-            write!(out, "    return this.request('{}', path", method)?;
+            let body_param = http_op.body();
+
+            // Params without a location are sent as the properties of a JSON body
+            let body_properties: Vec<_> = http_op.params_at(ParamLocation::BodyProperty).collect();
+
+            // Header params
+            let header_params: Vec<_> = http_op.params_at(ParamLocation::Header).collect();
+
+            let responses = &http_op.responses;
+            if responses.len() > 1 {
+                write!(out, "    return this.requestResponse('{}', path", method)?;
+            } else if let Some(response) = http_op.header_responses().next() {
+                write!(
+                    out,
+                    "    return this.requestWithHeaders('{}', path, read{}",
+                    method,
+                    http_op.headers_name(&iface.name, response)
+                )?;
+            } else {
+                write!(out, "    return this.request('{}', path", method)?;
+            }
 
-            if body_param.is_some() || !query_params.is_empty() {
-                write!(out, ", {{")?;
-                if let Some(bp) = body_param {
-                    write!(out, " body: {}", bp.name.to_case(Case::Camel))?;
-                }
-                if !query_params.is_empty() {
-                    if body_param.is_some() {
-                        write!(out, ",")?;
-                    }
-                    write!(out, " query: {{ ")?;
-                    let qp_strs: Vec<_> = query_params
-                        .iter()
-                        .map(|p| p.name.to_case(Case::Camel))
-                        .collect();
-                    write!(out, "{}", qp_strs.join(", "))?;
-                    write!(out, " }}")?;
+            let mut options = Vec::new();
+            if let Some(bp) = body_param {
+                options.push(format!("body: {}", bp.name.to_case(Case::Camel)));
+            } else if !body_properties.is_empty() {
+                let props: Vec<_> = body_properties
+                    .iter()
+                    .map(|p| {
+                        let name = p.name.to_case(Case::Camel);
+                        if name == p.name {
+                            name
+                        } else {
+                            format!("{}: {}", p.name, name)
+                        }
+                    })
+                    .collect();
+                options.push(format!("body: {{ {} }}", props.join(", ")));
+            }
+            if !query_params.is_empty() {
+                let qp_strs: Vec<_> = query_params
+                    .iter()
+                    .map(|p| p.name.to_case(Case::Camel))
+                    .collect();
+                options.push(format!("query: {{ {} }}", qp_strs.join(", ")));
+            }
+            if !header_params.is_empty() {
+                let hp_strs: Vec<_> = header_params
+                    .iter()
+                    .map(|p| {
+                        format!(
+                            "'{}': {}",
+                            header_name(&p.decorators, &p.name),
+                            p.name.to_case(Case::Camel)
+                        )
+                    })
+                    .collect();
+                options.push(format!("headers: {{ {} }}", hp_strs.join(", ")));
+            }
+            let decoders: Vec<_> = error_model_names(&http_op)
+                .iter()
+                .map(|name| format!("decode{}", name))
+                .collect();
+            if !decoders.is_empty() {
+                options.push(format!("errors: [{}]", decoders.join(", ")));
+            }
+            if responses.len() > 1 {
+                let readers: Vec<_> = http_op
+                    .header_responses()
+                    .map(|response| {
+                        let status = response
+                            .status
+                            .map(|s| s.to_string())
+                            .unwrap_or_else(|| "default".to_string());
+                        format!(
+                            "{}: read{}",
+                            status,
+                            http_op.headers_name(&iface.name, response)
+                        )
+                    })
+                    .collect();
+                if !readers.is_empty() {
+                    options.push(format!("responseHeaders: {{ {} }}", readers.join(", ")));
                 }
-                write!(out, " }}")?;
+            }
+            if !options.is_empty() {
+                write!(out, ", {{ {} }}", options.join(", "))?;
             }
 
             writeln!(out, ");")?;
@@ -333,7 +751,7 @@ export class BaseClient {{
     writeln!(out)?;
     writeln!(out, "export class Client extends BaseClient {{")?;
 
-    for iface in file.interfaces() {
+    for iface in &collect_services(file) {
         let name = iface.name.to_case(Case::Camel);
         let class_name = format!("{}Client", iface.name);
         writeln!(out, "  readonly {}: {};", name, class_name)?;
@@ -343,7 +761,7 @@ export class BaseClient {{
     writeln!(out, "  constructor(config: ClientConfig) {{")?;
     writeln!(out, "    super(config);")?;
 
-    for iface in file.interfaces() {
+    for iface in &collect_services(file) {
         let name = iface.name.to_case(Case::Camel);
         let class_name = format!("{}Client", iface.name);
         writeln!(out, "    this.{} = new {}(config);", name, class_name)?;
@@ -355,19 +773,32 @@ export class BaseClient {{
     Ok(out)
 }
 
-fn generate_server(file: &TypeSpecFile) -> Result<String, CodegenError> {
+fn generate_server(
+    file: &TypeSpecFile,
+    constraints: &ConstraintMap,
+    models: &ModelMap<'_>,
+) -> Result<String, CodegenError> {
     let mut out = String::new();
+    let validated = validated_models(file, models, constraints);
+    let services = collect_services(file);
 
     writeln!(out, "/**")?;
     writeln!(out, " * Auto-generated server handlers from TypeSpec.")?;
     writeln!(out, " * DO NOT EDIT.")?;
     writeln!(out, " *")?;
-    writeln!(out, " * Implement the abstract methods in a subclass.")?;
+    writeln!(
+        out,
+        " * Implement the abstract methods in a subclass, and serve it with the"
+    )?;
+    writeln!(
+        out,
+        " * interface's routes, e.g. `fetchHandler(tasksRoutes(handler))`."
+    )?;
     writeln!(out, " */")?;
     writeln!(out)?;
 
     // Collect all model and enum names for imports
-    let model_names: Vec<_> = file.models().map(|m| m.name.as_str()).collect();
+    let model_names = model_exports(file, models);
     let enum_names: Vec<_> = file.enums().map(|e| e.name.as_str()).collect();
 
     if !model_names.is_empty() {
@@ -384,23 +815,67 @@ fn generate_server(file: &TypeSpecFile) -> Result<String, CodegenError> {
             enum_names.join(", ")
         )?;
     }
+    // Validators of the bodies the routes read
+    let mut validators = vec!["ValidationError".to_string()];
+    for iface in &services {
+        for http_op in http_operations(iface, models) {
+            for param in &http_op.params {
+                let Some(nested) = nested_model(&param.type_ref, models) else {
+                    continue;
+                };
+                let validator = format!("validate{}", nested.model.name);
+                if validated.contains(&nested.model.name) && !validators.contains(&validator) {
+                    validators.push(validator);
+                }
+            }
+        }
+    }
+    writeln!(
+        out,
+        "import {{ {} }} from './models';",
+        validators.join(", ")
+    )?;
     writeln!(out)?;
+    writeln!(
+        out,
+        r#"/**
+ * Read a JSON request body, checking it with the body model's validator.
+ * Bodies that break the schema's constraints throw `ValidationError`
+ * (respond with 422) before they reach a handler.
+ */
+export function parseBody<T>(data: unknown, validate?: (value: T) => void): T {{
+  const body = data as T;
+  validate?.(body);
+  return body;
+}}
 
-    for iface in file.interfaces() {
+/**
+ * Throw from a handler to respond with an `@error` model and its status.
+ */
+export class ErrorResponse<E = unknown> extends Error {{
+  constructor(
+    public status: number,
+    public body: E
+  ) {{
+    super(`Error response ${{status}}`);
+  }}
+}}"#
+    )?;
+
+    for iface in &services {
         writeln!(out)?;
+        write_tsdoc(&mut out, "", &iface.decorators)?;
         writeln!(out, "export abstract class {}Handler {{", iface.name)?;
 
-        for op in &iface.operations {
-            let return_type = op
-                .return_type
-                .as_ref()
-                .map(|t| type_to_typescript(t))
-                .unwrap_or_else(|| "void".to_string());
+        for http_op in http_operations(iface, models) {
+            let op = http_op.op;
+            let return_type = success_type(&iface.name, &http_op);
 
             writeln!(out)?;
+            write_tsdoc(&mut out, "  ", &op.decorators)?;
             write!(out, "  abstract {}(", op.name.to_case(Case::Camel))?;
 
-            let params: Vec<_> = op
+            let params: Vec<_> = http_op
                 .params
                 .iter()
                 .map(|p| {
@@ -418,10 +893,531 @@ fn generate_server(file: &TypeSpecFile) -> Result<String, CodegenError> {
         writeln!(out, "}}")?;
     }
 
+    // Routes, served by the Fetch API and Express adapters
+    writeln!(out, "{}", SERVER_ROUTES_RUNTIME)?;
+    for (name, _, _, headers) in &response_headers(file, models) {
+        writeln!(out)?;
+        writeln!(
+            out,
+            "function write{}(headers: {}): Record<string, string> {{",
+            name, name
+        )?;
+        writeln!(out, "  const result: Record<string, string> = {{}};")?;
+        for header in headers {
+            let header_name = header_name(&header.decorators, &header.name);
+            if header.optional {
+                writeln!(out, "  if (headers.{} !== undefined) {{", header.name)?;
+                writeln!(
+                    out,
+                    "    result['{}'] = String(headers.{});",
+                    header_name, header.name
+                )?;
+                writeln!(out, "  }}")?;
+            } else {
+                writeln!(
+                    out,
+                    "  result['{}'] = String(headers.{});",
+                    header_name, header.name
+                )?;
+            }
+        }
+        writeln!(out, "  return result;")?;
+        writeln!(out, "}}")?;
+    }
+    for iface in &services {
+        write_routes(&mut out, iface, models, &validated)?;
+    }
+
     Ok(out)
 }
 
-fn generate_index(side: Side) -> Result<String, CodegenError> {
+/// Routes of the server and the adapters serving them: a route reads its
+/// params from a framework-agnostic request and calls its handler, and
+/// `fetchHandler` and `expressHandler` match requests to routes and turn
+/// `ErrorResponse`s and `ValidationError`s into responses
+const SERVER_ROUTES_RUNTIME: &str = r#"
+/** A request as read by a route, whatever serves it */
+export interface RouteRequest {
+  /** Path params by name, e.g. `id` of `/tasks/{id}` */
+  params: Record<string, string>;
+  query: URLSearchParams;
+  headers: { get(name: string): string | null };
+  /** The JSON body, `undefined` when it is empty */
+  json(): Promise<unknown>;
+}
+
+/** A response of a route */
+export interface RouteResponse {
+  status: number;
+  body?: unknown;
+  headers?: Record<string, string>;
+}
+
+/** An operation served at a method and path, e.g. `GET /tasks/{id}` */
+export interface Route {
+  method: string;
+  path: string;
+  handle(req: RouteRequest): Promise<RouteResponse>;
+}
+
+function required<T>(value: T | null | undefined, name: string): T {
+  if (value === null || value === undefined) {
+    throw new ValidationError(name, 'is required');
+  }
+  return value;
+}
+
+function toNumber(value: string, name: string): number {
+  const number = Number(value);
+  if (value === '' || Number.isNaN(number)) {
+    throw new ValidationError(name, 'must be a number');
+  }
+  return number;
+}
+
+function toBoolean(value: string, name: string): boolean {
+  if (value !== 'true' && value !== 'false') {
+    throw new ValidationError(name, 'must be true or false');
+  }
+  return value === 'true';
+}
+
+/**
+ * A property of a JSON body checked to be a `type`, or an array of them.
+ * Missing properties are left to `required` and defaults.
+ */
+function checkJson(
+  value: unknown,
+  type: 'string' | 'number' | 'boolean',
+  name: string,
+  array = false
+): unknown {
+  if (value === null || value === undefined) {
+    return value;
+  }
+  if (array) {
+    if (!Array.isArray(value)) {
+      throw new ValidationError(name, 'must be an array');
+    }
+    value.forEach((item, i) => checkJson(item, type, `${name}[${i}]`));
+  } else if (typeof value !== type) {
+    throw new ValidationError(name, `must be a ${type}`);
+  }
+  return value;
+}
+
+/** A body less the properties of its model sent as headers or status */
+function withoutMetadata(body: unknown, names: string[]): unknown {
+  if (typeof body !== 'object' || body === null) {
+    return body;
+  }
+  const result: Record<string, unknown> = {};
+  for (const [name, value] of Object.entries(body)) {
+    if (names.indexOf(name) === -1) {
+      result[name] = value;
+    }
+  }
+  return result;
+}
+
+async function objectBody(req: RouteRequest): Promise<Record<string, unknown>> {
+  const data = await req.json();
+  if (typeof data !== 'object' || data === null || Array.isArray(data)) {
+    throw new ValidationError('body', 'must be a JSON object');
+  }
+  return data as Record<string, unknown>;
+}
+
+function parseJson(text: string): unknown {
+  if (text === '') {
+    return undefined;
+  }
+  try {
+    return JSON.parse(text);
+  } catch {
+    throw new ValidationError('body', 'must be valid JSON');
+  }
+}
+
+/** Params of `path` read from `pathname`, if it matches */
+function matchPath(path: string, pathname: string): Record<string, string> | undefined {
+  const expected = path.split('/');
+  const actual = pathname.split('/');
+  if (expected.length !== actual.length) {
+    return undefined;
+  }
+  const params: Record<string, string> = {};
+  for (let i = 0; i < expected.length; i++) {
+    const name = /^\{(\w+)\}$/.exec(expected[i])?.[1];
+    if (name !== undefined && actual[i] !== '') {
+      params[name] = decodeURIComponent(actual[i]);
+    } else if (expected[i] !== actual[i]) {
+      return undefined;
+    }
+  }
+  return params;
+}
+
+function findRoute(
+  routes: Route[],
+  method: string,
+  pathname: string
+): { route: Route; params: Record<string, string> } | undefined {
+  for (const route of routes) {
+    const params = route.method === method ? matchPath(route.path, pathname) : undefined;
+    if (params) {
+      return { route, params };
+    }
+  }
+  return undefined;
+}
+
+/** Handle a request, responding to `ErrorResponse`s and `ValidationError`s */
+async function handleRoute(route: Route, req: RouteRequest): Promise<RouteResponse> {
+  try {
+    return await route.handle(req);
+  } catch (err) {
+    if (err instanceof ErrorResponse) {
+      return { status: err.status, body: err.body };
+    }
+    if (err instanceof ValidationError) {
+      return { status: 422, body: { field: err.field, message: err.message } };
+    }
+    throw err;
+  }
+}
+
+/**
+ * Serve routes with the Fetch API, e.g. from Node's `http` through an
+ * adapter, Hono, Deno, Bun or workers. Requests matching no route get 404.
+ */
+export function fetchHandler(
+  routes: Route[]
+): (req: globalThis.Request) => Promise<globalThis.Response> {
+  return async (req) => {
+    const url = new URL(req.url);
+    const match = findRoute(routes, req.method, url.pathname);
+    if (!match) {
+      return globalThis.Response.json({ message: 'Not Found' }, { status: 404 });
+    }
+    const response = await handleRoute(match.route, {
+      params: match.params,
+      query: url.searchParams,
+      headers: req.headers,
+      json: async () => parseJson(await req.text()),
+    });
+    if (response.body === undefined) {
+      return new globalThis.Response(null, { status: response.status, headers: response.headers });
+    }
+    return globalThis.Response.json(response.body, {
+      status: response.status,
+      headers: response.headers,
+    });
+  };
+}
+
+/** The parts of an Express request the adapter reads */
+export interface ExpressRequest {
+  method: string;
+  path: string;
+  originalUrl: string;
+  body?: unknown;
+  get(name: string): string | undefined;
+}
+
+/** The parts of an Express response the adapter writes */
+export interface ExpressResponse {
+  status(code: number): ExpressResponse;
+  set(headers: Record<string, string>): ExpressResponse;
+  json(body: unknown): unknown;
+  end(): unknown;
+}
+
+/**
+ * Serve routes as Express middleware, after `express.json()` has parsed
+ * the body: `app.use(expressHandler(routes))`. Requests matching no route
+ * are passed on.
+ */
+export function expressHandler(
+  routes: Route[]
+): (req: ExpressRequest, res: ExpressResponse, next: (err?: unknown) => void) => void {
+  return (req, res, next) => {
+    const match = findRoute(routes, req.method, req.path);
+    if (!match) {
+      next();
+      return;
+    }
+    handleRoute(match.route, {
+      params: match.params,
+      query: new URL(req.originalUrl, 'http://localhost').searchParams,
+      headers: { get: (name) => req.get(name) ?? null },
+      json: async () => req.body,
+    }).then((response) => {
+      res.status(response.status).set(response.headers ?? {});
+      if (response.body === undefined) {
+        res.end();
+      } else {
+        res.json(response.body);
+      }
+    }, next);
+  };
+}"#;
+
+/// Write the routes of an interface, e.g. `tasksRoutes(handler)`: a route
+/// per operation reading its path, query and header params and its JSON
+/// body, calling the handler and responding with the status of the result
+fn write_routes(
+    out: &mut String,
+    iface: &Interface,
+    models: &ModelMap<'_>,
+    validated: &HashSet<String>,
+) -> Result<(), CodegenError> {
+    writeln!(out)?;
+    writeln!(out, "/** Routes of `{}Handler` */", iface.name)?;
+    writeln!(
+        out,
+        "export function {}Routes(handler: {}Handler): Route[] {{",
+        iface.name.to_case(Case::Camel),
+        iface.name
+    )?;
+    writeln!(out, "  return [")?;
+    for http_op in http_operations(iface, models) {
+        let op = http_op.op;
+        writeln!(out, "    {{")?;
+        writeln!(out, "      method: '{}',", http_op.method)?;
+        writeln!(out, "      path: '{}',", http_op.path)?;
+        writeln!(out, "      handle: async (req) => {{")?;
+
+        let body_properties: Vec<_> = http_op.params_at(ParamLocation::BodyProperty).collect();
+        if !body_properties.is_empty() {
+            writeln!(out, "        const data = await objectBody(req);")?;
+        }
+        for param in &http_op.params {
+            let name = param.name.to_case(Case::Camel);
+            let value = param_value(param, models, validated);
+            writeln!(out, "        const {} = {};", name, value)?;
+        }
+
+        let args: Vec<_> = http_op
+            .params
+            .iter()
+            .map(|p| p.name.to_case(Case::Camel))
+            .collect();
+        let call = format!(
+            "await handler.{}({})",
+            op.name.to_case(Case::Camel),
+            args.join(", ")
+        );
+        match http_op.responses.as_slice() {
+            [response] => {
+                let status = response.status.unwrap_or(200);
+                if !response.headers.is_empty() {
+                    writeln!(out, "        const result = {};", call)?;
+                    writeln!(
+                        out,
+                        "        return {{ status: {}, body: {}, headers: write{}(result.headers) }};",
+                        status,
+                        body_value(response, models, "result.body"),
+                        http_op.headers_name(&iface.name, response)
+                    )?;
+                } else if response.body.is_none() {
+                    writeln!(out, "        {};", call)?;
+                    writeln!(out, "        return {{ status: {} }};", status)?;
+                } else {
+                    writeln!(
+                        out,
+                        "        return {{ status: {}, body: {} }};",
+                        status,
+                        body_value(response, models, &call)
+                    )?;
+                }
+            }
+            responses => {
+                // Several responses carry their status, and their headers
+                // when they have any
+                writeln!(out, "        const result = {};", call)?;
+                let mut header_responses: Vec<_> = http_op.header_responses().collect();
+                header_responses.sort_by_key(|r| r.status.is_none());
+                for response in header_responses {
+                    let condition = match response.status {
+                        Some(status) => format!("result.status === {}", status),
+                        None => "'headers' in result".to_string(),
+                    };
+                    let name = http_op.headers_name(&iface.name, response);
+                    writeln!(out, "        if ({}) {{", condition)?;
+                    writeln!(
+                        out,
+                        "          const headers = write{}((result as {{ headers: {} }}).headers);",
+                        name, name
+                    )?;
+                    if response.body.is_some() {
+                        writeln!(
+                            out,
+                            "          return {{ status: result.status, body: {}, headers }};",
+                            body_value(response, models, "(result as { body?: unknown }).body")
+                        )?;
+                    } else {
+                        writeln!(
+                            out,
+                            "          return {{ status: result.status, headers }};"
+                        )?;
+                    }
+                    writeln!(out, "        }}")?;
+                }
+                if responses.iter().any(|r| r.headers.is_empty()) {
+                    writeln!(
+                        out,
+                        "        return {{ status: result.status, body: (result as {{ body?: unknown }}).body }};"
+                    )?;
+                } else {
+                    // Every declared status is handled above
+                    writeln!(
+                        out,
+                        "        throw new Error(`No response declared for status ${{(result as {{ status: number }}).status}}`);"
+                    )?;
+                }
+            }
+        }
+        writeln!(out, "      }},")?;
+        writeln!(out, "    }},")?;
+    }
+    writeln!(out, "  ];")?;
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+/// Expression of the body of a response: the value of the handler's result,
+/// less the `@header`s and `@statusCode` of a named model sent as its body
+fn body_value(response: &Response<'_>, models: &ModelMap<'_>, value: &str) -> String {
+    let metadata: Vec<_> = response
+        .body
+        .as_ref()
+        .and_then(|body| lookup_model(body, models))
+        .map(|model| resolve_properties(model, models))
+        .unwrap_or_default()
+        .iter()
+        .filter(|p| is_metadata(p))
+        .map(|p| format!("'{}'", p.name))
+        .collect();
+    if metadata.is_empty() {
+        value.to_string()
+    } else {
+        format!("withoutMetadata({}, [{}])", value, metadata.join(", "))
+    }
+}
+
+/// Expression reading a param from the `req` of a route, converting query
+/// and header strings to numbers and booleans and checking bodies
+fn param_value(
+    param: &HttpParam<'_>,
+    models: &ModelMap<'_>,
+    validated: &HashSet<String>,
+) -> String {
+    let ty = type_to_typescript(&param.type_ref);
+    let required = !param.optional && param.default.is_none();
+    let fallback = param
+        .default
+        .as_ref()
+        .map(value_to_typescript)
+        .unwrap_or_else(|| "undefined".to_string());
+    let convert = |value: &str| match ty.as_str() {
+        "number" => format!("toNumber({}, '{}')", value, param.name),
+        "boolean" => format!("toBoolean({}, '{}')", value, param.name),
+        _ => value.to_string(),
+    };
+
+    let raw = match param.location {
+        ParamLocation::Path => return convert(&format!("req.params['{}']", param.name)),
+        ParamLocation::Query => {
+            if let Some(item) = ty.strip_suffix("[]") {
+                let map = match item {
+                    "number" => format!(".map((v) => toNumber(v, '{}'))", param.name),
+                    "boolean" => format!(".map((v) => toBoolean(v, '{}'))", param.name),
+                    _ => String::new(),
+                };
+                return format!("req.query.getAll('{}'){} as {}", param.name, map, ty);
+            }
+            format!("req.query.get('{}')", param.name)
+        }
+        ParamLocation::Header => format!(
+            "req.headers.get('{}')",
+            header_name(&param.decorators, &param.name)
+        ),
+        ParamLocation::Body => "await req.json()".to_string(),
+        // JSON strings, numbers and booleans are checked, models validated below
+        ParamLocation::BodyProperty => {
+            let (item, array) = match ty.strip_suffix("[]") {
+                Some(item) => (item, ", true"),
+                None => (ty.as_str(), ""),
+            };
+            match item {
+                "string" | "number" | "boolean" => format!(
+                    "checkJson(data['{}'], '{}', '{}'{})",
+                    param.name, item, param.name, array
+                ),
+                _ => format!("data['{}']", param.name),
+            }
+        }
+    };
+
+    // Query and header params are strings, `null` when missing
+    if matches!(param.location, ParamLocation::Query | ParamLocation::Header) {
+        let cast = match ty.as_str() {
+            "number" | "boolean" | "string" => String::new(),
+            _ => format!(" as {}", ty),
+        };
+        if required {
+            return format!(
+                "{}{}",
+                convert(&format!("required({}, '{}')", raw, param.name)),
+                cast
+            );
+        }
+        if convert("value") == "value" && cast.is_empty() {
+            return format!("{} ?? {}", raw, fallback);
+        }
+        return format!(
+            "((value) => (value === null ? {} : {}{}))({})",
+            fallback,
+            convert("value"),
+            cast,
+            raw
+        );
+    }
+
+    // Bodies are JSON, `undefined` when missing, and models are validated,
+    // lists of them item by item
+    let validator = nested_model(&param.type_ref, models)
+        .filter(|nested| validated.contains(&nested.model.name))
+        .map(|nested| {
+            if nested.array {
+                format!(
+                    ", (items) => items.forEach((item, i) => validate{}(item, `[${{i}}].`))",
+                    nested.model.name
+                )
+            } else {
+                format!(", validate{}", nested.model.name)
+            }
+        })
+        .unwrap_or_default();
+    if required {
+        format!(
+            "parseBody<{}>(required({}, '{}'){})",
+            ty, raw, param.name, validator
+        )
+    } else if !validator.is_empty() {
+        format!(
+            "((value) => (value === undefined ? {} : parseBody<{}>(value{})))({})",
+            fallback, ty, validator, raw
+        )
+    } else if param.default.is_some() {
+        format!("({} ?? {}) as {}", raw, fallback, ty)
+    } else {
+        format!("{} as {} | undefined", raw, ty)
+    }
+}
+
+fn generate_index(file: &TypeSpecFile, side: Side) -> Result<String, CodegenError> {
     let mut out = String::new();
 
     writeln!(out, "/**")?;
@@ -433,6 +1429,12 @@ fn generate_index(side: Side) -> Result<String, CodegenError> {
 
     if matches!(side, Side::Client | Side::Both) {
         writeln!(out, "export * from './client';")?;
+
+        // Client classes win over models of the same name
+        let local_names = client_names(file);
+        for model in file.models().filter(|m| local_names.contains(&m.name)) {
+            writeln!(out, "export {{ {} }} from './client';", model.name)?;
+        }
     }
 
     if matches!(side, Side::Server | Side::Both) {
@@ -442,6 +1444,120 @@ fn generate_index(side: Side) -> Result<String, CodegenError> {
     Ok(out)
 }
 
+/// Names the client module declares
+fn client_names(file: &TypeSpecFile) -> Vec<String> {
+    let mut names: Vec<String> = ["ApiError", "ClientConfig", "BaseClient", "Client"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    for iface in &collect_services(file) {
+        names.push(format!("{}Client", iface.name));
+    }
+    names
+}
+
+/// Name a model is imported as in the client module
+fn model_alias(name: &str, local_names: &[String]) -> String {
+    if local_names.iter().any(|n| n == name) {
+        format!("{}Model", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Names of the `@error` models an operation responds with, fixed statuses first
+fn error_model_names(http_op: &HttpOperation<'_>) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for response in &http_op.errors {
+        if let Some(model) = response.error.filter(|m| m.type_params.is_empty()) {
+            if !names.contains(&model.name) {
+                names.push(model.name.clone());
+            }
+        }
+    }
+    names
+}
+
+/// The TypeScript type of an operation's success: its body, with its
+/// `@header`s when it has any, or the union of its responses when it has several
+fn success_type(iface_name: &str, http_op: &HttpOperation<'_>) -> String {
+    let op = http_op.op;
+    if op.return_type.is_none() {
+        return "void".to_string();
+    }
+    let responses = &http_op.responses;
+    if responses.len() > 1 {
+        return response_type_name(iface_name, op);
+    }
+    let Some(response) = responses.last() else {
+        return "void".to_string();
+    };
+    let body = response
+        .body
+        .as_ref()
+        .map(type_to_typescript)
+        .unwrap_or_else(|| "void".to_string());
+    if response.headers.is_empty() {
+        body
+    } else {
+        format!(
+            "WithHeaders<{}, {}>",
+            body,
+            http_op.headers_name(iface_name, response)
+        )
+    }
+}
+
+fn response_type_name(iface_name: &str, op: &Operation) -> String {
+    format!("{}{}Response", iface_name, op.name.to_case(Case::Pascal))
+}
+
+/// Names `models.ts` exports: models, the response unions of operations
+/// and the types of their response headers
+fn model_exports(file: &TypeSpecFile, models: &ModelMap<'_>) -> Vec<String> {
+    let mut names: Vec<String> = file.models().map(|m| m.name.clone()).collect();
+    for iface in &collect_services(file) {
+        for http_op in http_operations(iface, models) {
+            if http_op.responses.len() > 1 {
+                names.push(response_type_name(&iface.name, http_op.op));
+            }
+        }
+    }
+    let header_responses = response_headers(file, models);
+    if header_responses.iter().any(|(_, _, several, _)| !several) {
+        names.push("WithHeaders".to_string());
+    }
+    names.extend(header_responses.into_iter().map(|(name, _, _, _)| name));
+    names
+}
+
+/// TypeScript expression of a default value, e.g. `'desc'` or `Status.Active`
+fn value_to_typescript(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'")),
+        Value::Int(n) => n.to_string(),
+        Value::Float(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Ident(name) => name.clone(),
+        Value::QualifiedIdent(parts) => match parts.as_slice() {
+            [.., enum_name, member] => format!("{}.{}", enum_name, member.to_case(Case::Pascal)),
+            _ => parts.join("."),
+        },
+        Value::Array(items) => {
+            let items: Vec<_> = items.iter().map(value_to_typescript).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Object(fields) => {
+            let mut fields: Vec<_> = fields
+                .iter()
+                .map(|(k, v)| format!("{}: {}", k, value_to_typescript(v)))
+                .collect();
+            fields.sort();
+            format!("{{ {} }}", fields.join(", "))
+        }
+    }
+}
+
 pub fn type_to_typescript(type_ref: &TypeRef) -> String {
     match type_ref {
         TypeRef::Builtin(name) => match name.as_str() {
@@ -484,34 +1600,22 @@ pub fn type_to_typescript(type_ref: &TypeRef) -> String {
     }
 }
 
-fn get_description(decorators: &[Decorator]) -> Option<String> {
-    decorators
-        .iter()
-        .find(|d| d.name == "doc")
-        .and_then(|d| d.get_string_arg(0).map(|s| s.to_string()))
-}
-
-fn get_route(decorators: &[Decorator]) -> Option<String> {
-    decorators
-        .iter()
-        .find(|d| d.name == "route")
-        .and_then(|d| d.get_string_arg(0).map(|s| s.to_string()))
-}
-
-fn get_http_method(decorators: &[Decorator]) -> &'static str {
-    for d in decorators {
-        match d.name.as_str() {
-            "get" => return "GET",
-            "post" => return "POST",
-            "put" => return "PUT",
-            "patch" => return "PATCH",
-            "delete" => return "DELETE",
-            _ => {}
+/// Write the description of an item as a TSDoc comment
+fn write_tsdoc(out: &mut String, indent: &str, decorators: &[Decorator]) -> std::fmt::Result {
+    let Some(desc) = description(decorators) else {
+        return Ok(());
+    };
+    let desc = desc.replace("*/", "*\\/");
+    if !desc.contains('\n') {
+        return writeln!(out, "{}/** {} */", indent, desc);
+    }
+    writeln!(out, "{}/**", indent)?;
+    for line in desc.lines() {
+        if line.is_empty() {
+            writeln!(out, "{} *", indent)?;
+        } else {
+            writeln!(out, "{} * {}", indent, line)?;
         }
     }
-    "GET"
-}
-
-fn has_decorator(decorators: &[Decorator], name: &str) -> bool {
-    decorators.iter().any(|d| d.name == name)
+    writeln!(out, "{} */", indent)
 }
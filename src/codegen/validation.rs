@@ -0,0 +1,281 @@
+//! Validation Constraints
+//!
+//! Read `@minLength`, `@maxLength`, `@minValue`, `@maxValue`, `@minItems`,
+//! `@maxItems`, `@pattern` and `@format` from properties, parameters and the
+//! scalars they use. Backends emit them as OpenAPI keywords or as checks in
+//! generated `validate()` methods.
+
+use crate::ast::{Decorator, DecoratorArg, Model, TypeRef, TypeSpecFile, Value};
+use crate::semantic::inheritance::lookup_model;
+use crate::semantic::{resolve_properties, ModelMap};
+use std::collections::{HashMap, HashSet};
+
+/// Constraints on a value, as declared by validation decorators
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Constraints {
+    pub min_length: Option<u64>,
+    pub max_length: Option<u64>,
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
+    pub min_items: Option<u64>,
+    pub max_items: Option<u64>,
+    pub pattern: Option<String>,
+    /// Documented in OpenAPI only: formats are open-ended and not checked
+    pub format: Option<String>,
+}
+
+/// A constraint checked at runtime
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Check<'a> {
+    /// Length of a string in Unicode code points, in every backend
+    MinLength(u64),
+    MaxLength(u64),
+    MinValue(f64),
+    MaxValue(f64),
+    MinItems(u64),
+    MaxItems(u64),
+    Pattern(&'a str),
+}
+
+impl Check<'_> {
+    /// Message of the error reported when a value fails the check
+    pub fn message(&self) -> String {
+        match self {
+            Check::MinLength(n) => format!("must be at least {} characters long", n),
+            Check::MaxLength(n) => format!("must be at most {} characters long", n),
+            Check::MinValue(n) => format!("must be at least {}", number_literal(*n)),
+            Check::MaxValue(n) => format!("must be at most {}", number_literal(*n)),
+            Check::MinItems(n) => format!("must have at least {} items", n),
+            Check::MaxItems(n) => format!("must have at most {} items", n),
+            Check::Pattern(p) => format!("must match `{}`", p),
+        }
+    }
+}
+
+/// Map of scalar name -> constraints declared on the scalar
+pub type ConstraintMap = HashMap<String, Constraints>;
+
+/// Collect the constraints of scalars, e.g. `@format("email") scalar email extends string;`
+pub fn build_constraint_map(file: &TypeSpecFile) -> ConstraintMap {
+    file.scalars()
+        .map(|s| (s.name.clone(), Constraints::from_decorators(&s.decorators)))
+        .filter(|(_, c)| !c.is_empty())
+        .collect()
+}
+
+impl Constraints {
+    pub fn from_decorators(decorators: &[Decorator]) -> Self {
+        let mut constraints = Constraints::default();
+        for decorator in decorators {
+            let arg = decorator.args.first().and_then(|a| match a {
+                DecoratorArg::Value(v) => Some(v),
+                DecoratorArg::Named { .. } => None,
+            });
+            match decorator.name.as_str() {
+                "minLength" => constraints.min_length = arg.and_then(count),
+                "maxLength" => constraints.max_length = arg.and_then(count),
+                "minValue" => constraints.min_value = arg.and_then(number),
+                "maxValue" => constraints.max_value = arg.and_then(number),
+                "minItems" => constraints.min_items = arg.and_then(count),
+                "maxItems" => constraints.max_items = arg.and_then(count),
+                "pattern" => constraints.pattern = decorator.get_string_arg(0).map(String::from),
+                "format" => constraints.format = decorator.get_string_arg(0).map(String::from),
+                _ => {}
+            }
+        }
+        constraints
+    }
+
+    /// Constraints of a property or parameter: its own decorators, falling
+    /// back to those of the scalar it is typed with.
+    pub fn of(decorators: &[Decorator], type_ref: &TypeRef, scalars: &ConstraintMap) -> Self {
+        let own = Self::from_decorators(decorators);
+        let scalar = match type_ref {
+            TypeRef::Named(name) => scalars.get(name),
+            TypeRef::Optional(inner) => return Self::of(decorators, inner, scalars),
+            _ => None,
+        };
+        match scalar {
+            Some(scalar) => own.or(scalar),
+            None => own,
+        }
+    }
+
+    fn or(self, fallback: &Constraints) -> Self {
+        Constraints {
+            min_length: self.min_length.or(fallback.min_length),
+            max_length: self.max_length.or(fallback.max_length),
+            min_value: self.min_value.or(fallback.min_value),
+            max_value: self.max_value.or(fallback.max_value),
+            min_items: self.min_items.or(fallback.min_items),
+            max_items: self.max_items.or(fallback.max_items),
+            pattern: self.pattern.or_else(|| fallback.pattern.clone()),
+            format: self.format.or_else(|| fallback.format.clone()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.format.is_none() && self.checks().is_empty()
+    }
+
+    /// The constraints checked at runtime, i.e. all but `format`
+    pub fn checks(&self) -> Vec<Check<'_>> {
+        [
+            self.min_length.map(Check::MinLength),
+            self.max_length.map(Check::MaxLength),
+            self.min_value.map(Check::MinValue),
+            self.max_value.map(Check::MaxValue),
+            self.min_items.map(Check::MinItems),
+            self.max_items.map(Check::MaxItems),
+            self.pattern.as_deref().map(Check::Pattern),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+/// A property holding a model that is validated on its own
+#[derive(Debug, Clone, Copy)]
+pub struct NestedModel<'a> {
+    pub model: &'a Model,
+    /// Whether the property holds an array of the model
+    pub array: bool,
+}
+
+/// The model a property of type `type_ref` holds, if any
+pub fn nested_model<'a>(type_ref: &TypeRef, models: &ModelMap<'a>) -> Option<NestedModel<'a>> {
+    match type_ref {
+        TypeRef::Optional(inner) => nested_model(inner, models),
+        TypeRef::Array(inner) => nested_model(inner, models)
+            .filter(|nested| !nested.array)
+            .map(|nested| NestedModel {
+                array: true,
+                ..nested
+            }),
+        TypeRef::Named(_) | TypeRef::Qualified(_) => lookup_model(type_ref, models)
+            .filter(|m| m.type_params.is_empty())
+            .map(|model| NestedModel {
+                model,
+                array: false,
+            }),
+        _ => None,
+    }
+}
+
+/// Names of the models whose values can fail validation: models with
+/// checked constraints on their properties, and models holding such models.
+pub fn validated_models(
+    file: &TypeSpecFile,
+    models: &ModelMap<'_>,
+    scalars: &ConstraintMap,
+) -> HashSet<String> {
+    let mut validated = HashSet::new();
+    loop {
+        let before = validated.len();
+        for model in file.models() {
+            if validated.contains(&model.name) {
+                continue;
+            }
+            let needs_validation = resolve_properties(model, models).iter().any(|prop| {
+                let constraints = Constraints::of(&prop.decorators, &prop.type_ref, scalars);
+                !constraints.checks().is_empty()
+                    || nested_model(&prop.type_ref, models)
+                        .is_some_and(|nested| validated.contains(&nested.model.name))
+            });
+            if needs_validation {
+                validated.insert(model.name.clone());
+            }
+        }
+        if validated.len() == before {
+            return validated;
+        }
+    }
+}
+
+/// Format a number bound without a fractional part when it has none
+pub fn number_literal(n: f64) -> String {
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        format!("{}", n as i64)
+    } else {
+        n.to_string()
+    }
+}
+
+fn count(value: &Value) -> Option<u64> {
+    match value {
+        Value::Int(n) => u64::try_from(*n).ok(),
+        _ => None,
+    }
+}
+
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Int(n) => Some(*n as f64),
+        Value::Float(n) => Some(*n),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::build_model_map;
+    use crate::parse;
+
+    #[test]
+    fn test_property_constraints_over_scalar() {
+        let file = parse(
+            r#"
+            @format("email") @maxLength(254)
+            scalar email extends string;
+
+            model User {
+                @minLength(3) @maxLength(100)
+                contact?: email;
+                @minValue(0) @maxValue(1.5)
+                score: float64;
+            }
+            "#,
+        )
+        .unwrap();
+        let scalars = build_constraint_map(&file);
+        let user = file.models().next().unwrap();
+
+        let contact = &user.properties[0];
+        let constraints = Constraints::of(&contact.decorators, &contact.type_ref, &scalars);
+        assert_eq!(constraints.format.as_deref(), Some("email"));
+        assert_eq!(
+            constraints.checks(),
+            vec![Check::MinLength(3), Check::MaxLength(100)]
+        );
+
+        let score = &user.properties[1];
+        let constraints = Constraints::of(&score.decorators, &score.type_ref, &scalars);
+        assert_eq!(
+            constraints.checks(),
+            vec![Check::MinValue(0.0), Check::MaxValue(1.5)]
+        );
+        assert_eq!(Check::MaxValue(1.5).message(), "must be at most 1.5");
+        assert_eq!(Check::MinValue(0.0).message(), "must be at least 0");
+    }
+
+    #[test]
+    fn test_validated_models_include_holders() {
+        let file = parse(
+            r#"
+            model Tag { @minLength(1) name: string; }
+            model Task { tags: Tag[]; }
+            model Page { items: Task[]; }
+            model Plain { id: string; }
+            "#,
+        )
+        .unwrap();
+        let models = build_model_map(&file);
+        let validated = validated_models(&file, &models, &ConstraintMap::new());
+
+        let mut names: Vec<_> = validated.iter().map(String::as_str).collect();
+        names.sort();
+        assert_eq!(names, vec!["Page", "Tag", "Task"]);
+    }
+}
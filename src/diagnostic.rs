@@ -0,0 +1,176 @@
+//! Source Diagnostics
+//!
+//! Line/column lookup and caret-underlined snippets for error reporting.
+
+use crate::ast::Span;
+use std::fmt::Write;
+
+/// Index of line start offsets for mapping byte offsets to line/column.
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(source: &str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(source.match_indices('\n').map(|(i, _)| i + 1));
+        Self { line_starts }
+    }
+
+    /// 1-based line and column (in characters) of a byte offset.
+    pub fn line_col(&self, source: &str, offset: usize) -> (usize, usize) {
+        let offset = offset.min(source.len());
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let line_start = self.line_starts[line];
+        let column = source[line_start..offset].chars().count() + 1;
+        (line + 1, column)
+    }
+
+    /// Text of a 1-based line, without the trailing newline.
+    pub fn line_text<'s>(&self, source: &'s str, line: usize) -> &'s str {
+        let start = self.line_starts[line - 1];
+        let end = self
+            .line_starts
+            .get(line)
+            .map(|&next| next - 1)
+            .unwrap_or(source.len());
+        source[start..end].trim_end_matches('\r')
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// A message attached to a location in a source file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub span: Span,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+            span,
+        }
+    }
+
+    pub fn warning(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+            span,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// Render with file, line, column and a caret-underlined snippet.
+    pub fn render(&self, file_name: &str, source: &str) -> String {
+        render_labeled(
+            self.severity.label(),
+            file_name,
+            source,
+            self.span,
+            &self.message,
+        )
+    }
+}
+
+/// Render `message` at `span` as a rustc-style snippet:
+///
+/// ```text
+/// error: expected identifier, got LBrace
+///   --> tasks.tsp:3:7
+///    |
+///  3 | model { }
+///    |       ^
+/// ```
+pub fn render_snippet(file_name: &str, source: &str, span: Span, message: &str) -> String {
+    render_labeled("error", file_name, source, span, message)
+}
+
+fn render_labeled(label: &str, file_name: &str, source: &str, span: Span, message: &str) -> String {
+    let index = LineIndex::new(source);
+    let (line, column) = index.line_col(source, span.start);
+    let text = index.line_text(source, line);
+
+    // Underline up to the end of the span, clamped to the first line
+    let (end_line, end_column) = index.line_col(source, span.end);
+    let width = if end_line == line && end_column > column {
+        end_column - column
+    } else if end_line > line {
+        text.chars().count().saturating_sub(column - 1).max(1)
+    } else {
+        1
+    };
+
+    let gutter = line.to_string().len();
+    let pad = " ".repeat(gutter);
+    let mut out = String::new();
+    let _ = writeln!(out, "{}: {}", label, message);
+    let _ = writeln!(out, "{}--> {}:{}:{}", pad, file_name, line, column);
+    let _ = writeln!(out, "{} |", pad);
+    let _ = writeln!(out, "{} | {}", line, text);
+    let _ = write!(
+        out,
+        "{} | {}{}",
+        pad,
+        " ".repeat(column - 1),
+        "^".repeat(width)
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_col() {
+        let source = "model A {}\nmodel B {\n  x: y;\n}";
+        let index = LineIndex::new(source);
+        assert_eq!(index.line_col(source, 0), (1, 1));
+        assert_eq!(index.line_col(source, 11), (2, 1));
+        assert_eq!(index.line_col(source, 23), (3, 3));
+        assert_eq!(index.line_text(source, 3), "  x: y;");
+    }
+
+    #[test]
+    fn test_render_snippet() {
+        let source = "model User {\n  id: string\n  name string;\n}";
+        let span = Span::new(33, 39);
+        let rendered = render_snippet("user.tsp", source, span, "expected Colon");
+        assert_eq!(
+            rendered,
+            "error: expected Colon\n --> user.tsp:3:8\n  |\n3 |   name string;\n  |        ^^^^^^"
+        );
+    }
+
+    #[test]
+    fn test_render_warning() {
+        let source = "model A {}";
+        let diagnostic = Diagnostic::warning("unused model", Span::new(6, 7));
+        assert!(diagnostic
+            .render("a.tsp", source)
+            .starts_with("warning: unused model\n --> a.tsp:1:7"));
+    }
+}
@@ -2,7 +2,9 @@
 //!
 //! Tokenizes TypeSpec source files.
 
+use crate::ast::Span;
 use logos::Logos;
+use thiserror::Error;
 
 #[derive(Logos, Debug, Clone, PartialEq)]
 #[logos(skip r"[ \t\r\n\f]+")]
@@ -39,6 +41,10 @@ pub enum Token {
     #[regex(r"@[a-zA-Z_][a-zA-Z0-9_]*(\.[a-zA-Z_][a-zA-Z0-9_]*)*", |lex| lex.slice()[1..].to_string())]
     Decorator(String),
 
+    /// `/** ... */` doc comment, with the comment markers stripped
+    #[regex(r"/\*\*[^*]*\*+(?:[^/*][^*]*\*+)*/", |lex| doc_comment_text(lex.slice()), priority = 10)]
+    DocComment(String),
+
     // Literals
     #[regex(r#""([^"\\]|\\.)*""#, |lex| {
         let s = lex.slice();
@@ -96,13 +102,107 @@ pub enum Token {
     Pipe,
     #[token("&")]
     Amp,
+
+    /// Input no other rule matches (produced by `tokenize`, never by logos)
+    Error(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LexErrorKind {
+    UnexpectedCharacter,
+    UnterminatedString,
+}
+
+/// Input the lexer could not turn into a valid token.
+#[derive(Debug, Clone, PartialEq, Error)]
+#[error("{}: {text}", match kind {
+    LexErrorKind::UnexpectedCharacter => "unexpected character",
+    LexErrorKind::UnterminatedString => "unterminated string literal",
+})]
+pub struct LexError {
+    pub kind: LexErrorKind,
+    pub span: Span,
+    pub text: String,
+}
+
+impl LexError {
+    /// Build the error for a `Token::Error` produced by `tokenize`.
+    pub fn from_token(text: &str, span: Span) -> Self {
+        let kind = if text.starts_with('"') {
+            LexErrorKind::UnterminatedString
+        } else {
+            LexErrorKind::UnexpectedCharacter
+        };
+        Self {
+            kind,
+            span,
+            text: text.to_string(),
+        }
+    }
+}
+
+/// Text of a doc comment: the `/**` and `*/` markers and the leading `*` of
+/// each line are removed, as are blank lines at the start and end.
+fn doc_comment_text(comment: &str) -> String {
+    let body = comment
+        .strip_prefix("/**")
+        .and_then(|c| c.strip_suffix("*/"))
+        .unwrap_or(comment);
+    let lines: Vec<_> = body
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            let line = line.strip_prefix('*').unwrap_or(line);
+            line.strip_prefix(' ').unwrap_or(line).trim_end()
+        })
+        .collect();
+
+    let start = lines
+        .iter()
+        .position(|l| !l.is_empty())
+        .unwrap_or(lines.len());
+    let end = lines
+        .iter()
+        .rposition(|l| !l.is_empty())
+        .map_or(start, |i| i + 1);
+    lines[start..end].join("\n")
 }
 
+/// Tokenize input. Unrecognized input is kept as `Token::Error` so callers
+/// can report it at its real position.
 pub fn tokenize(input: &str) -> Vec<(Token, std::ops::Range<usize>)> {
-    Token::lexer(input)
-        .spanned()
-        .filter_map(|(tok, span)| tok.ok().map(|t| (t, span)))
-        .collect()
+    let mut tokens = Vec::new();
+    let mut base = 0;
+
+    'outer: while base < input.len() {
+        let mut lexer = Token::lexer(&input[base..]);
+        while let Some(tok) = lexer.next() {
+            let span = base + lexer.span().start..base + lexer.span().end;
+            match tok {
+                Ok(t) => tokens.push((t, span)),
+                Err(()) => {
+                    let text = &input[span.clone()];
+                    // An unterminated string swallows the rest of the input;
+                    // cut it at the end of its line and resume lexing there.
+                    if text.starts_with('"') {
+                        if let Some(newline) = text.find('\n') {
+                            let end = span.start + newline;
+                            tokens.push((
+                                Token::Error(input[span.start..end].to_string()),
+                                span.start..end,
+                            ));
+                            base = end;
+                            continue 'outer;
+                        }
+                    }
+                    tokens.push((Token::Error(text.to_string()), span));
+                }
+            }
+        }
+        break;
+    }
+
+    tokens
 }
 
 #[cfg(test)]
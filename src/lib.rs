@@ -5,13 +5,23 @@
 //! - Generates Python, TypeScript, and Rust code
 
 pub mod ast;
+pub mod checker;
 pub mod codegen;
+pub mod diagnostic;
 pub mod lexer;
 pub mod parser;
+pub mod program;
+pub mod semantic;
+pub mod source;
+pub mod symbols;
 
 pub use ast::*;
 pub use codegen::{Generator, Language, Side};
-pub use parser::parse;
+pub use diagnostic::{Diagnostic, Severity};
+pub use parser::{parse, parse_with_diagnostics};
+pub use program::Program;
+pub use source::{FileSystem, InMemory, Overlay, SourceProvider};
+pub use symbols::{SymbolKind, SymbolTable};
 
 #[cfg(test)]
 mod tests {
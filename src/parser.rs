@@ -3,196 +3,421 @@
 //! Parses tokenized TypeSpec into AST.
 
 use crate::ast::*;
-use crate::lexer::Token;
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::lexer::{LexError, Token};
 use std::collections::HashMap;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum ParseError {
-    #[error("Unexpected token at position {pos}: expected {expected}, got {got:?}")]
+    #[error("Unexpected token at {}..{}: expected {expected}, got {got:?}", span.start, span.end)]
     UnexpectedToken {
-        pos: usize,
+        span: Span,
         expected: String,
         got: Option<Token>,
     },
 
     #[error("Unexpected end of input")]
-    UnexpectedEof,
+    UnexpectedEof { span: Span },
 
-    #[error("Invalid syntax: {0}")]
-    InvalidSyntax(String),
+    #[error("Invalid syntax: {message}")]
+    InvalidSyntax { span: Span, message: String },
+
+    #[error("Lex error: {0}")]
+    Lex(#[from] LexError),
+}
+
+impl ParseError {
+    /// Source span the error points at.
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::UnexpectedToken { span, .. }
+            | ParseError::UnexpectedEof { span }
+            | ParseError::InvalidSyntax { span, .. } => *span,
+            ParseError::Lex(err) => err.span,
+        }
+    }
+
+    /// Short message without position information.
+    pub fn message(&self) -> String {
+        match self {
+            ParseError::UnexpectedToken { expected, got, .. } => match got {
+                Some(tok) => format!("expected {}, got {:?}", expected, tok),
+                None => format!("expected {}, got end of input", expected),
+            },
+            ParseError::UnexpectedEof { .. } => "unexpected end of input".to_string(),
+            ParseError::InvalidSyntax { message, .. } => message.clone(),
+            ParseError::Lex(err) => err.to_string(),
+        }
+    }
+
+    /// Render the error with file, line, column and a caret-underlined snippet.
+    pub fn render(&self, file_name: &str, source: &str) -> String {
+        Diagnostic::from(self).render(file_name, source)
+    }
+}
+
+impl From<&ParseError> for Diagnostic {
+    fn from(err: &ParseError) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            message: err.message(),
+            span: err.span(),
+        }
+    }
+}
+
+/// Where the recovering parser resumes after an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Recovery {
+    /// Skip to the next `;`, `,` or the closing `}` of the enclosing body.
+    Member,
+    /// Skip past the broken declaration to the next declaration keyword.
+    Declaration,
 }
 
 pub struct Parser {
-    tokens: Vec<Token>,
+    tokens: Vec<(Token, Span)>,
     pos: usize,
+    /// Span used for errors at end of input
+    eof: Span,
+    /// Errors collected while recovering
+    errors: Vec<ParseError>,
+    /// Doc comments, by the position of the token that follows them
+    docs: HashMap<usize, (String, Span)>,
 }
 
 impl Parser {
-    pub fn new(tokens: Vec<Token>) -> Self {
-        Self { tokens, pos: 0 }
+    pub fn new(tokens: Vec<(Token, Span)>) -> Self {
+        let end = tokens.last().map(|(_, span)| span.end).unwrap_or(0);
+
+        let mut docs = HashMap::new();
+        let mut rest = Vec::with_capacity(tokens.len());
+        for (tok, span) in tokens {
+            match tok {
+                Token::DocComment(text) => {
+                    docs.insert(rest.len(), (text, span));
+                }
+                tok => rest.push((tok, span)),
+            }
+        }
+
+        Self {
+            tokens: rest,
+            pos: 0,
+            eof: Span::new(end, end),
+            errors: Vec::new(),
+            docs,
+        }
     }
 
     fn peek(&self) -> Option<&Token> {
-        self.tokens.get(self.pos)
+        self.tokens.get(self.pos).map(|(tok, _)| tok)
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + n).map(|(tok, _)| tok)
     }
 
     fn advance(&mut self) -> Option<Token> {
-        let tok = self.tokens.get(self.pos).cloned();
+        let tok = self.tokens.get(self.pos).map(|(tok, _)| tok.clone());
         self.pos += 1;
         tok
     }
 
+    /// Span of the current token (or end of input).
+    fn current_span(&self) -> Span {
+        self.tokens
+            .get(self.pos)
+            .map(|(_, span)| *span)
+            .unwrap_or(self.eof)
+    }
+
+    /// Span of the most recently consumed token.
+    fn prev_span(&self) -> Span {
+        self.pos
+            .checked_sub(1)
+            .and_then(|i| self.tokens.get(i))
+            .map(|(_, span)| *span)
+            .unwrap_or(self.eof)
+    }
+
+    /// Span from `start` up to the end of the most recently consumed token.
+    fn span_from(&self, start: Span) -> Span {
+        start.to(self.prev_span())
+    }
+
+    fn unexpected(&self, expected: impl Into<String>) -> ParseError {
+        match self.peek() {
+            Some(tok) => ParseError::UnexpectedToken {
+                span: self.current_span(),
+                expected: expected.into(),
+                got: Some(tok.clone()),
+            },
+            None => ParseError::UnexpectedEof { span: self.eof },
+        }
+    }
+
     fn expect(&mut self, expected: &Token) -> Result<(), ParseError> {
         match self.peek() {
             Some(tok) if tok == expected => {
                 self.advance();
                 Ok(())
             }
-            other => Err(ParseError::UnexpectedToken {
-                pos: self.pos,
-                expected: format!("{:?}", expected),
-                got: other.cloned(),
-            }),
+            _ => Err(self.unexpected(format!("{:?}", expected))),
         }
     }
 
     fn expect_ident(&mut self) -> Result<String, ParseError> {
-        match self.advance() {
-            Some(Token::Ident(s)) => Ok(s),
+        let name = match self.peek() {
+            Some(Token::Ident(s)) => s.clone(),
             // Allow keywords to be used as identifiers (property names, etc.)
-            Some(Token::Model) => Ok("model".to_string()),
-            Some(Token::Enum) => Ok("enum".to_string()),
-            Some(Token::Union) => Ok("union".to_string()),
-            Some(Token::Interface) => Ok("interface".to_string()),
-            Some(Token::Scalar) => Ok("scalar".to_string()),
-            Some(Token::Alias) => Ok("alias".to_string()),
-            Some(Token::Namespace) => Ok("namespace".to_string()),
-            Some(Token::Import) => Ok("import".to_string()),
-            Some(Token::Using) => Ok("using".to_string()),
-            Some(Token::Extends) => Ok("extends".to_string()),
-            Some(Token::Is) => Ok("is".to_string()),
-            Some(Token::Op) => Ok("op".to_string()),
-            other => Err(ParseError::UnexpectedToken {
-                pos: self.pos,
-                expected: "identifier".to_string(),
-                got: other,
-            }),
-        }
+            Some(Token::Model) => "model".to_string(),
+            Some(Token::Enum) => "enum".to_string(),
+            Some(Token::Union) => "union".to_string(),
+            Some(Token::Interface) => "interface".to_string(),
+            Some(Token::Scalar) => "scalar".to_string(),
+            Some(Token::Alias) => "alias".to_string(),
+            Some(Token::Namespace) => "namespace".to_string(),
+            Some(Token::Import) => "import".to_string(),
+            Some(Token::Using) => "using".to_string(),
+            Some(Token::Extends) => "extends".to_string(),
+            Some(Token::Is) => "is".to_string(),
+            Some(Token::Op) => "op".to_string(),
+            _ => return Err(self.unexpected("identifier")),
+        };
+        self.advance();
+        Ok(name)
     }
 
     fn expect_string(&mut self) -> Result<String, ParseError> {
-        match self.advance() {
-            Some(Token::StringLit(s)) => Ok(s),
-            other => Err(ParseError::UnexpectedToken {
-                pos: self.pos,
-                expected: "string literal".to_string(),
-                got: other,
-            }),
+        match self.peek() {
+            Some(Token::StringLit(s)) => {
+                let s = s.clone();
+                self.advance();
+                Ok(s)
+            }
+            _ => Err(self.unexpected("string literal")),
         }
     }
 
+    /// Parse the whole file, stopping at the first error.
     pub fn parse_file(&mut self) -> Result<TypeSpecFile, ParseError> {
+        let file = self.parse_file_recovering();
+        match self.take_errors().into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(file),
+        }
+    }
+
+    /// Parse the whole file, recovering from errors. Broken members and
+    /// declarations are skipped; the errors are available via `take_errors`.
+    pub fn parse_file_recovering(&mut self) -> TypeSpecFile {
         let mut file = TypeSpecFile::default();
 
         while self.peek().is_some() {
-            // Collect decorators
-            let decorators = self.parse_decorators()?;
+            let start = self.pos;
+            if let Err(err) = self.parse_top_level(&mut file) {
+                self.recover(start, err, Recovery::Declaration);
+            }
+        }
 
-            match self.peek() {
-                Some(Token::Import) => {
-                    file.imports.push(self.parse_import()?);
-                }
-                Some(Token::Using) => {
-                    file.usings.push(self.parse_using()?);
-                }
-                Some(Token::Namespace) => {
+        file
+    }
+
+    /// Errors collected so far by the recovering parser.
+    pub fn take_errors(&mut self) -> Vec<ParseError> {
+        std::mem::take(&mut self.errors)
+    }
+
+    fn parse_top_level(&mut self, file: &mut TypeSpecFile) -> Result<(), ParseError> {
+        // Collect decorators
+        let decorators = self.parse_decorators()?;
+
+        match self.peek() {
+            Some(Token::Import) => {
+                file.imports.push(self.parse_import()?);
+            }
+            Some(Token::Using) => {
+                file.usings.push(self.parse_using()?);
+            }
+            Some(Token::Namespace) => {
+                let start = self.current_span();
+                self.advance();
+                let name = self.parse_qualified_name()?;
+
+                // Check if this is a simple namespace declaration (with ;)
+                // or a namespace block (with {})
+                if self.peek() == Some(&Token::Semi) {
+                    // Top-level namespace declaration: namespace Name;
                     self.advance();
-                    let name = self.parse_qualified_name()?;
+                    file.namespace = Some(name);
+                    // Note: decorators on top-level namespace are ignored for now
+                } else {
+                    let namespace = self.parse_namespace_block(name, decorators, start)?;
+                    file.declarations.push(Declaration::Namespace(namespace));
+                }
+            }
+            Some(_) => {
+                file.declarations.push(self.parse_declaration(decorators)?);
+            }
+            None => return Err(self.unexpected("declaration")),
+        }
 
-                    // Check if this is a simple namespace declaration (with ;)
-                    // or a namespace block (with {})
-                    if self.peek() == Some(&Token::Semi) {
-                        // Top-level namespace declaration: namespace Name;
-                        self.advance();
-                        file.namespace = Some(name);
-                        // Note: decorators on top-level namespace are ignored for now
-                    } else {
-                        // Nested namespace block: namespace Name { ... }
-                        self.expect(&Token::LBrace)?;
-                        let mut declarations = Vec::new();
-                        while self.peek() != Some(&Token::RBrace) {
-                            let decs = self.parse_decorators()?;
-                            match self.peek() {
-                                Some(Token::Model) => {
-                                    declarations.push(Declaration::Model(self.parse_model(decs)?));
-                                }
-                                Some(Token::Enum) => {
-                                    declarations.push(Declaration::Enum(self.parse_enum(decs)?));
-                                }
-                                Some(Token::Interface) => {
-                                    declarations
-                                        .push(Declaration::Interface(self.parse_interface(decs)?));
-                                }
-                                _ => break,
-                            }
-                        }
-                        self.expect(&Token::RBrace)?;
-                        file.declarations.push(Declaration::Namespace(Namespace {
-                            name,
-                            decorators,
-                            declarations,
-                        }));
+        Ok(())
+    }
+
+    /// Parse a declaration after its decorators. Namespaces must be blocks.
+    fn parse_declaration(&mut self, decorators: Vec<Decorator>) -> Result<Declaration, ParseError> {
+        match self.peek() {
+            Some(Token::Namespace) => {
+                let start = self.current_span();
+                self.advance();
+                let name = self.parse_qualified_name()?;
+                Ok(Declaration::Namespace(
+                    self.parse_namespace_block(name, decorators, start)?,
+                ))
+            }
+            Some(Token::Model) => Ok(Declaration::Model(self.parse_model(decorators)?)),
+            Some(Token::Enum) => Ok(Declaration::Enum(self.parse_enum(decorators)?)),
+            Some(Token::Union) => Ok(Declaration::Union(self.parse_union(decorators)?)),
+            Some(Token::Interface) => Ok(Declaration::Interface(self.parse_interface(decorators)?)),
+            Some(Token::Scalar) => Ok(Declaration::Scalar(self.parse_scalar(decorators)?)),
+            Some(Token::Op) => Ok(Declaration::Operation(
+                self.parse_op_declaration(decorators)?,
+            )),
+            Some(Token::Alias) => Ok(Declaration::Alias(self.parse_alias()?)),
+            Some(tok) => Err(ParseError::InvalidSyntax {
+                span: self.current_span(),
+                message: format!("unexpected token {:?}, expected a declaration", tok),
+            }),
+            None => Err(self.unexpected("declaration")),
+        }
+    }
+
+    /// Parse the body of `namespace Name { ... }`, starting at `{`.
+    fn parse_namespace_block(
+        &mut self,
+        name: String,
+        decorators: Vec<Decorator>,
+        start: Span,
+    ) -> Result<Namespace, ParseError> {
+        self.expect(&Token::LBrace)?;
+
+        let mut usings = Vec::new();
+        let mut declarations = Vec::new();
+        while !matches!(self.peek(), Some(Token::RBrace) | None) {
+            let member_start = self.pos;
+            let result = if self.peek() == Some(&Token::Using) {
+                self.parse_using().map(|using| usings.push(using))
+            } else {
+                self.parse_decorators()
+                    .and_then(|decorators| self.parse_declaration(decorators))
+                    .map(|decl| declarations.push(decl))
+            };
+            if let Err(err) = result {
+                self.recover(member_start, err, Recovery::Declaration);
+            }
+        }
+
+        self.expect(&Token::RBrace)?;
+
+        Ok(Namespace {
+            name,
+            decorators,
+            usings,
+            declarations,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Record `err` and skip ahead to a point where parsing can resume.
+    ///
+    /// `start` is the token index where the failed member or declaration
+    /// began; brackets opened since then are balanced before stopping.
+    fn recover(&mut self, start: usize, err: ParseError, recovery: Recovery) {
+        self.errors.push(err);
+
+        let end = self.pos.min(self.tokens.len());
+        let mut depth: usize = 0;
+        for (tok, _) in &self.tokens[start.min(end)..end] {
+            match tok {
+                Token::LBrace | Token::LParen | Token::LBracket => depth += 1,
+                Token::RBrace | Token::RParen | Token::RBracket => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+
+        while let Some(tok) = self.peek() {
+            match tok {
+                Token::LBrace | Token::LParen | Token::LBracket => depth += 1,
+                Token::RBrace | Token::RParen | Token::RBracket if depth > 0 => {
+                    depth -= 1;
+                    let closes_body = depth == 0 && *tok == Token::RBrace;
+                    self.advance();
+                    if closes_body && recovery == Recovery::Declaration {
+                        break;
                     }
+                    continue;
                 }
-                Some(Token::Model) => {
-                    file.declarations
-                        .push(Declaration::Model(self.parse_model(decorators)?));
-                }
-                Some(Token::Enum) => {
-                    file.declarations
-                        .push(Declaration::Enum(self.parse_enum(decorators)?));
-                }
-                Some(Token::Union) => {
-                    file.declarations
-                        .push(Declaration::Union(self.parse_union(decorators)?));
-                }
-                Some(Token::Interface) => {
-                    file.declarations
-                        .push(Declaration::Interface(self.parse_interface(decorators)?));
-                }
-                Some(Token::Scalar) => {
-                    file.declarations
-                        .push(Declaration::Scalar(self.parse_scalar(decorators)?));
+                // End of the enclosing body: leave it for the caller
+                Token::RBrace => break,
+                Token::Semi if depth == 0 => {
+                    self.advance();
+                    break;
                 }
-                Some(Token::Alias) => {
-                    file.declarations
-                        .push(Declaration::Alias(self.parse_alias()?));
+                Token::Comma if depth == 0 && recovery == Recovery::Member => {
+                    self.advance();
+                    break;
                 }
-                Some(_) => {
-                    return Err(ParseError::InvalidSyntax(format!(
-                        "Unexpected token: {:?}",
-                        self.peek()
-                    )));
+                // A declaration keyword that itself failed is skipped
+                tok if depth == 0
+                    && recovery == Recovery::Declaration
+                    && self.pos > start
+                    && starts_declaration(tok) =>
+                {
+                    break
                 }
-                None => break,
+                _ => {}
             }
+            self.advance();
         }
 
-        Ok(file)
+        // Always make progress
+        if self.pos <= start {
+            self.pos = start + 1;
+        }
     }
 
+    /// Parse decorators. A doc comment before them becomes a `@doc`
+    /// decorator, unless there is an explicit `@doc`.
     fn parse_decorators(&mut self) -> Result<Vec<Decorator>, ParseError> {
+        let doc = self.docs.get(&self.pos).cloned();
         let mut decorators = Vec::new();
 
         while let Some(Token::Decorator(name)) = self.peek().cloned() {
+            let start = self.current_span();
             self.advance();
             let args = if self.peek() == Some(&Token::LParen) {
                 self.parse_decorator_args()?
             } else {
                 Vec::new()
             };
-            decorators.push(Decorator { name, args });
+            decorators.push(Decorator {
+                name,
+                args,
+                span: self.span_from(start),
+            });
+        }
+
+        if let Some((text, span)) = doc {
+            if !decorators.iter().any(|d| d.name == "doc") {
+                decorators.push(Decorator {
+                    name: "doc".to_string(),
+                    args: vec![DecoratorArg::Value(Value::String(text))],
+                    span,
+                });
+            }
         }
 
         Ok(decorators)
@@ -206,7 +431,7 @@ impl Parser {
             // Check for named argument
             let is_named = {
                 let has_ident = matches!(self.peek(), Some(Token::Ident(_)));
-                has_ident && self.tokens.get(self.pos + 1) == Some(&Token::Colon)
+                has_ident && self.peek_nth(1) == Some(&Token::Colon)
             };
 
             if is_named {
@@ -291,26 +516,30 @@ impl Parser {
                 self.expect(&Token::RBrace)?;
                 Ok(Value::Object(map))
             }
-            other => Err(ParseError::UnexpectedToken {
-                pos: self.pos,
-                expected: "value".to_string(),
-                got: other,
-            }),
+            _ => Err(self.unexpected("value")),
         }
     }
 
     fn parse_import(&mut self) -> Result<Import, ParseError> {
+        let start = self.current_span();
         self.expect(&Token::Import)?;
         let path = self.expect_string()?;
         self.expect(&Token::Semi)?;
-        Ok(Import { path })
+        Ok(Import {
+            path,
+            span: self.span_from(start),
+        })
     }
 
     fn parse_using(&mut self) -> Result<Using, ParseError> {
+        let start = self.current_span();
         self.expect(&Token::Using)?;
         let namespace = self.parse_qualified_name()?;
         self.expect(&Token::Semi)?;
-        Ok(Using { namespace })
+        Ok(Using {
+            namespace,
+            span: self.span_from(start),
+        })
     }
 
     fn parse_qualified_name(&mut self) -> Result<String, ParseError> {
@@ -323,6 +552,7 @@ impl Parser {
     }
 
     fn parse_model(&mut self, decorators: Vec<Decorator>) -> Result<Model, ParseError> {
+        let start = self.current_span();
         self.expect(&Token::Model)?;
         let name = self.expect_ident()?;
 
@@ -333,6 +563,14 @@ impl Parser {
             Vec::new()
         };
 
+        // Is
+        let is = if self.peek() == Some(&Token::Is) {
+            self.advance();
+            Some(self.parse_type_ref()?)
+        } else {
+            None
+        };
+
         // Extends
         let extends = if self.peek() == Some(&Token::Extends) {
             self.advance();
@@ -341,53 +579,32 @@ impl Parser {
             None
         };
 
-        self.expect(&Token::LBrace)?;
-
         let mut properties = Vec::new();
         let mut spread_refs = Vec::new();
 
-        while self.peek() != Some(&Token::RBrace) {
-            let prop_decorators = self.parse_decorators()?;
-
-            // Check for spread operator
-            if self.peek() == Some(&Token::Spread) {
-                self.advance();
-                spread_refs.push(self.parse_type_ref()?);
-                if self.peek() == Some(&Token::Semi) {
-                    self.advance();
-                }
-                continue;
-            }
-
-            let prop_name = self.expect_ident()?;
-            let optional = if self.peek() == Some(&Token::Question) {
-                self.advance();
-                true
-            } else {
-                false
-            };
-
-            self.expect(&Token::Colon)?;
-            let type_ref = self.parse_type_ref()?;
+        // `model X is Y;` has no body
+        if is.is_some() && self.peek() == Some(&Token::Semi) {
+            let span = self.span_from(start);
+            self.advance();
+            return Ok(Model {
+                name,
+                decorators,
+                type_params,
+                extends,
+                is,
+                properties,
+                spread_refs,
+                span,
+            });
+        }
 
-            let default = if self.peek() == Some(&Token::Eq) {
-                self.advance();
-                Some(self.parse_value()?)
-            } else {
-                None
-            };
+        self.expect(&Token::LBrace)?;
 
-            if self.peek() == Some(&Token::Semi) {
-                self.advance();
+        while !matches!(self.peek(), Some(Token::RBrace) | None) {
+            let member_start = self.pos;
+            if let Err(err) = self.parse_model_member(&mut properties, &mut spread_refs) {
+                self.recover(member_start, err, Recovery::Member);
             }
-
-            properties.push(Property {
-                name: prop_name,
-                decorators: prop_decorators,
-                type_ref,
-                optional,
-                default,
-            });
         }
 
         self.expect(&Token::RBrace)?;
@@ -397,11 +614,66 @@ impl Parser {
             decorators,
             type_params,
             extends,
+            is,
             properties,
             spread_refs,
+            span: self.span_from(start),
         })
     }
 
+    fn parse_model_member(
+        &mut self,
+        properties: &mut Vec<Property>,
+        spread_refs: &mut Vec<TypeRef>,
+    ) -> Result<(), ParseError> {
+        let prop_start = self.current_span();
+        let prop_decorators = self.parse_decorators()?;
+
+        // Check for spread operator
+        if self.peek() == Some(&Token::Spread) {
+            self.advance();
+            spread_refs.push(self.parse_type_ref()?);
+            if self.peek() == Some(&Token::Semi) {
+                self.advance();
+            }
+            return Ok(());
+        }
+
+        let prop_name = self.expect_ident()?;
+        let optional = if self.peek() == Some(&Token::Question) {
+            self.advance();
+            true
+        } else {
+            false
+        };
+
+        self.expect(&Token::Colon)?;
+        let type_ref = self.parse_type_ref()?;
+
+        let default = if self.peek() == Some(&Token::Eq) {
+            self.advance();
+            Some(self.parse_value()?)
+        } else {
+            None
+        };
+
+        let span = self.span_from(prop_start);
+        if self.peek() == Some(&Token::Semi) {
+            self.advance();
+        }
+
+        properties.push(Property {
+            name: prop_name,
+            decorators: prop_decorators,
+            type_ref,
+            optional,
+            default,
+            span,
+        });
+
+        Ok(())
+    }
+
     fn parse_type_params(&mut self) -> Result<Vec<String>, ParseError> {
         self.expect(&Token::LAngle)?;
         let mut params = Vec::new();
@@ -498,6 +770,7 @@ impl Parser {
                 self.advance();
                 let mut properties = Vec::new();
                 while self.peek() != Some(&Token::RBrace) {
+                    let prop_start = self.current_span();
                     let decorators = self.parse_decorators()?;
                     let name = self.expect_ident()?;
                     let optional = if self.peek() == Some(&Token::Question) {
@@ -508,6 +781,7 @@ impl Parser {
                     };
                     self.expect(&Token::Colon)?;
                     let type_ref = self.parse_type_ref()?;
+                    let span = self.span_from(prop_start);
                     if self.peek() == Some(&Token::Semi) {
                         self.advance();
                     }
@@ -517,18 +791,13 @@ impl Parser {
                         type_ref,
                         optional,
                         default: None,
+                        span,
                     });
                 }
                 self.expect(&Token::RBrace)?;
                 TypeRef::AnonymousModel(properties)
             }
-            other => {
-                return Err(ParseError::UnexpectedToken {
-                    pos: self.pos,
-                    expected: "type".to_string(),
-                    got: other,
-                })
-            }
+            _ => return Err(self.unexpected("type")),
         };
 
         // Handle array suffix []
@@ -543,31 +812,18 @@ impl Parser {
     }
 
     fn parse_enum(&mut self, decorators: Vec<Decorator>) -> Result<Enum, ParseError> {
+        let start = self.current_span();
         self.expect(&Token::Enum)?;
         let name = self.expect_ident()?;
         self.expect(&Token::LBrace)?;
 
         let mut members = Vec::new();
-        while self.peek() != Some(&Token::RBrace) {
-            let member_decorators = self.parse_decorators()?;
-            let member_name = self.expect_ident()?;
-
-            let value = if self.peek() == Some(&Token::Colon) {
-                self.advance();
-                Some(self.parse_value()?)
-            } else {
-                None
-            };
-
-            if self.peek() == Some(&Token::Comma) {
-                self.advance();
+        while !matches!(self.peek(), Some(Token::RBrace) | None) {
+            let member_start = self.pos;
+            match self.parse_enum_member() {
+                Ok(member) => members.push(member),
+                Err(err) => self.recover(member_start, err, Recovery::Member),
             }
-
-            members.push(EnumMember {
-                name: member_name,
-                decorators: member_decorators,
-                value,
-            });
         }
 
         self.expect(&Token::RBrace)?;
@@ -576,36 +832,47 @@ impl Parser {
             name,
             decorators,
             members,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_enum_member(&mut self) -> Result<EnumMember, ParseError> {
+        let member_start = self.current_span();
+        let member_decorators = self.parse_decorators()?;
+        let member_name = self.expect_ident()?;
+
+        let value = if self.peek() == Some(&Token::Colon) {
+            self.advance();
+            Some(self.parse_value()?)
+        } else {
+            None
+        };
+
+        let span = self.span_from(member_start);
+        if self.peek() == Some(&Token::Comma) {
+            self.advance();
+        }
+
+        Ok(EnumMember {
+            name: member_name,
+            decorators: member_decorators,
+            value,
+            span,
         })
     }
 
     fn parse_union(&mut self, decorators: Vec<Decorator>) -> Result<Union, ParseError> {
+        let start = self.current_span();
         self.expect(&Token::Union)?;
         let name = self.expect_ident()?;
         self.expect(&Token::LBrace)?;
 
         let mut variants = Vec::new();
-        while self.peek() != Some(&Token::RBrace) {
-            // Named variant
-            if matches!(self.peek(), Some(Token::Ident(_))) {
-                let variant_name = self.expect_ident()?;
-                self.expect(&Token::Colon)?;
-                let type_ref = self.parse_type_ref()?;
-                variants.push(UnionVariant {
-                    name: Some(variant_name),
-                    type_ref,
-                });
-            } else {
-                // Anonymous variant (just type)
-                let type_ref = self.parse_type_ref()?;
-                variants.push(UnionVariant {
-                    name: None,
-                    type_ref,
-                });
-            }
-
-            if self.peek() == Some(&Token::Comma) {
-                self.advance();
+        while !matches!(self.peek(), Some(Token::RBrace) | None) {
+            let variant_start = self.pos;
+            match self.parse_union_variant() {
+                Ok(variant) => variants.push(variant),
+                Err(err) => self.recover(variant_start, err, Recovery::Member),
             }
         }
 
@@ -615,101 +882,207 @@ impl Parser {
             name,
             decorators,
             variants,
+            span: self.span_from(start),
         })
     }
 
+    fn parse_union_variant(&mut self) -> Result<UnionVariant, ParseError> {
+        let variant_start = self.current_span();
+        // Named variant: `name: Type`
+        let variant = if matches!(self.peek(), Some(Token::Ident(_)))
+            && self.peek_nth(1) == Some(&Token::Colon)
+        {
+            let variant_name = self.expect_ident()?;
+            self.expect(&Token::Colon)?;
+            let type_ref = self.parse_type_ref()?;
+            UnionVariant {
+                name: Some(variant_name),
+                type_ref,
+                span: self.span_from(variant_start),
+            }
+        } else {
+            // Anonymous variant (just type)
+            let type_ref = self.parse_type_ref()?;
+            UnionVariant {
+                name: None,
+                type_ref,
+                span: self.span_from(variant_start),
+            }
+        };
+
+        if self.peek() == Some(&Token::Comma) {
+            self.advance();
+        }
+
+        Ok(variant)
+    }
+
     fn parse_interface(&mut self, decorators: Vec<Decorator>) -> Result<Interface, ParseError> {
+        let start = self.current_span();
         self.expect(&Token::Interface)?;
         let name = self.expect_ident()?;
         self.expect(&Token::LBrace)?;
 
         let mut operations = Vec::new();
-        while self.peek() != Some(&Token::RBrace) {
-            let op_decorators = self.parse_decorators()?;
-            let op_name = self.expect_ident()?;
+        while !matches!(self.peek(), Some(Token::RBrace) | None) {
+            let op_start = self.pos;
+            match self.parse_interface_operation() {
+                Ok(op) => operations.push(op),
+                Err(err) => self.recover(op_start, err, Recovery::Member),
+            }
+        }
 
-            self.expect(&Token::LParen)?;
-            let mut params = Vec::new();
+        self.expect(&Token::RBrace)?;
 
-            while self.peek() != Some(&Token::RParen) {
-                let param_decorators = self.parse_decorators()?;
-                let spread = if self.peek() == Some(&Token::Spread) {
-                    self.advance();
-                    true
-                } else {
-                    false
-                };
+        Ok(Interface {
+            name,
+            decorators,
+            operations,
+            span: self.span_from(start),
+        })
+    }
 
-                // For spread types like ...PaginationParams, there's no name:type syntax
-                // Check if next token after ident is colon or not
-                let is_named_param = !spread || {
-                    // Look ahead: if we have `name:` it's a named param, otherwise anonymous spread
-                    self.tokens.get(self.pos + 1) == Some(&Token::Colon)
-                        || self.tokens.get(self.pos + 1) == Some(&Token::Question)
-                };
+    fn parse_interface_operation(&mut self) -> Result<Operation, ParseError> {
+        let op_start = self.current_span();
+        let op_decorators = self.parse_decorators()?;
+        // The `op` keyword is optional inside interfaces
+        if self.peek() == Some(&Token::Op) {
+            self.advance();
+        }
+        self.parse_operation(op_decorators, op_start)
+    }
 
-                let (param_name, optional, type_ref) = if is_named_param {
-                    let name = self.expect_ident()?;
-                    let opt = if self.peek() == Some(&Token::Question) {
-                        self.advance();
-                        true
-                    } else {
-                        false
-                    };
-                    self.expect(&Token::Colon)?;
-                    let tr = self.parse_type_ref()?;
-                    (name, opt, tr)
-                } else {
-                    // Anonymous spread: ...TypeName
-                    let tr = self.parse_type_ref()?;
-                    // Use empty string as placeholder name for anonymous spread
-                    (String::new(), false, tr)
-                };
+    /// Parse a free `op` declaration.
+    fn parse_op_declaration(
+        &mut self,
+        decorators: Vec<Decorator>,
+    ) -> Result<Operation, ParseError> {
+        let start = self.current_span();
+        self.expect(&Token::Op)?;
+        self.parse_operation(decorators, start)
+    }
 
-                params.push(OperationParam {
-                    name: param_name,
-                    decorators: param_decorators,
-                    type_ref,
-                    optional,
-                    spread,
-                });
+    /// Parse an operation signature after its decorators and `op` keyword.
+    fn parse_operation(
+        &mut self,
+        op_decorators: Vec<Decorator>,
+        op_start: Span,
+    ) -> Result<Operation, ParseError> {
+        let op_name = self.expect_ident()?;
 
-                if self.peek() == Some(&Token::Comma) {
-                    self.advance();
-                }
+        let type_params = if self.peek() == Some(&Token::LAngle) {
+            self.parse_type_params()?
+        } else {
+            Vec::new()
+        };
+
+        // `op name is Template<Args>` reuses another operation's signature
+        if self.peek() == Some(&Token::Is) {
+            self.advance();
+            let is = self.parse_type_ref()?;
+            let span = self.span_from(op_start);
+            if self.peek() == Some(&Token::Semi) {
+                self.advance();
             }
+            return Ok(Operation {
+                name: op_name,
+                decorators: op_decorators,
+                type_params,
+                is: Some(is),
+                params: Vec::new(),
+                return_type: None,
+                span,
+            });
+        }
 
-            self.expect(&Token::RParen)?;
+        self.expect(&Token::LParen)?;
+        let mut params = Vec::new();
 
-            let return_type = if self.peek() == Some(&Token::Colon) {
+        while self.peek() != Some(&Token::RParen) {
+            let param_start = self.current_span();
+            let param_decorators = self.parse_decorators()?;
+            let spread = if self.peek() == Some(&Token::Spread) {
                 self.advance();
-                Some(self.parse_type_ref()?)
+                true
             } else {
-                None
+                false
             };
 
-            if self.peek() == Some(&Token::Semi) {
+            // For spread types like ...PaginationParams, there's no name:type syntax
+            // Check if next token after ident is colon or not
+            let is_named_param = !spread || {
+                // Look ahead: if we have `name:` it's a named param, otherwise anonymous spread
+                self.peek_nth(1) == Some(&Token::Colon)
+                    || self.peek_nth(1) == Some(&Token::Question)
+            };
+
+            let (param_name, optional, type_ref) = if is_named_param {
+                let name = self.expect_ident()?;
+                let opt = if self.peek() == Some(&Token::Question) {
+                    self.advance();
+                    true
+                } else {
+                    false
+                };
+                self.expect(&Token::Colon)?;
+                let tr = self.parse_type_ref()?;
+                (name, opt, tr)
+            } else {
+                // Anonymous spread: ...TypeName
+                let tr = self.parse_type_ref()?;
+                // Use empty string as placeholder name for anonymous spread
+                (String::new(), false, tr)
+            };
+
+            let default = if self.peek() == Some(&Token::Eq) {
                 self.advance();
-            }
+                Some(self.parse_value()?)
+            } else {
+                None
+            };
 
-            operations.push(Operation {
-                name: op_name,
-                decorators: op_decorators,
-                params,
-                return_type,
+            params.push(OperationParam {
+                name: param_name,
+                decorators: param_decorators,
+                type_ref,
+                optional,
+                default,
+                spread,
+                span: self.span_from(param_start),
             });
+
+            if self.peek() == Some(&Token::Comma) {
+                self.advance();
+            }
         }
 
-        self.expect(&Token::RBrace)?;
+        self.expect(&Token::RParen)?;
 
-        Ok(Interface {
-            name,
-            decorators,
-            operations,
+        let return_type = if self.peek() == Some(&Token::Colon) {
+            self.advance();
+            Some(self.parse_type_ref()?)
+        } else {
+            None
+        };
+
+        let span = self.span_from(op_start);
+        if self.peek() == Some(&Token::Semi) {
+            self.advance();
+        }
+
+        Ok(Operation {
+            name: op_name,
+            decorators: op_decorators,
+            type_params,
+            is: None,
+            params,
+            return_type,
+            span,
         })
     }
 
     fn parse_scalar(&mut self, decorators: Vec<Decorator>) -> Result<Scalar, ParseError> {
+        let start = self.current_span();
         self.expect(&Token::Scalar)?;
         let name = self.expect_ident()?;
 
@@ -726,20 +1099,44 @@ impl Parser {
             name,
             decorators,
             extends,
+            span: self.span_from(start),
         })
     }
 
     fn parse_alias(&mut self) -> Result<Alias, ParseError> {
+        let start = self.current_span();
         self.expect(&Token::Alias)?;
         let name = self.expect_ident()?;
         self.expect(&Token::Eq)?;
         let type_ref = self.parse_type_ref()?;
         self.expect(&Token::Semi)?;
 
-        Ok(Alias { name, type_ref })
+        Ok(Alias {
+            name,
+            type_ref,
+            span: self.span_from(start),
+        })
     }
 }
 
+/// Tokens that can begin a declaration, used as recovery points.
+fn starts_declaration(tok: &Token) -> bool {
+    matches!(
+        tok,
+        Token::Import
+            | Token::Using
+            | Token::Namespace
+            | Token::Model
+            | Token::Enum
+            | Token::Union
+            | Token::Interface
+            | Token::Op
+            | Token::Scalar
+            | Token::Alias
+            | Token::Decorator(_)
+    )
+}
+
 fn is_builtin(name: &str) -> bool {
     matches!(
         name,
@@ -769,15 +1166,53 @@ fn is_builtin(name: &str) -> bool {
     )
 }
 
-/// Parse TypeSpec source code.
+/// Parse TypeSpec source code, failing on the first error.
 pub fn parse(source: &str) -> Result<TypeSpecFile, ParseError> {
-    let tokens: Vec<Token> = crate::lexer::tokenize(source)
-        .into_iter()
-        .map(|(t, _)| t)
-        .collect();
+    let (file, errors) = parse_recovering(source);
+    match errors.into_iter().next() {
+        Some(err) => Err(err),
+        None => Ok(file),
+    }
+}
+
+/// Parse TypeSpec source code, recovering from errors.
+///
+/// Returns the partial file together with every lex and parse error,
+/// ordered by position.
+pub fn parse_recovering(source: &str) -> (TypeSpecFile, Vec<ParseError>) {
+    parse_recovering_at(source, 0)
+}
+
+/// Parse TypeSpec source code, reporting every error as a `Diagnostic`.
+pub fn parse_with_diagnostics(source: &str) -> (TypeSpecFile, Vec<Diagnostic>) {
+    parse_with_offset(source, 0)
+}
+
+/// Like [`parse_with_diagnostics`], with every span shifted by `offset` so
+/// that several files can share one position space (see [`crate::program`]).
+pub fn parse_with_offset(source: &str, offset: usize) -> (TypeSpecFile, Vec<Diagnostic>) {
+    let (file, errors) = parse_recovering_at(source, offset);
+    (file, errors.iter().map(Diagnostic::from).collect())
+}
+
+fn parse_recovering_at(source: &str, offset: usize) -> (TypeSpecFile, Vec<ParseError>) {
+    let mut errors = Vec::new();
+    let mut tokens = Vec::new();
+    for (tok, range) in crate::lexer::tokenize(source) {
+        let span = Span::new(range.start + offset, range.end + offset);
+        if let Token::Error(text) = &tok {
+            errors.push(LexError::from_token(text, span).into());
+            continue;
+        }
+        tokens.push((tok, span));
+    }
 
     let mut parser = Parser::new(tokens);
-    parser.parse_file()
+    let file = parser.parse_file_recovering();
+    errors.extend(parser.take_errors());
+    errors.sort_by_key(|e: &ParseError| e.span().start);
+
+    (file, errors)
 }
 
 #[cfg(test)]
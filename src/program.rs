@@ -0,0 +1,218 @@
+//! Program Loader
+//!
+//! Load TypeSpec entry files together with everything they import into one
+//! merged [`TypeSpecFile`], keeping track of the file every declaration and
+//! diagnostic comes from.
+
+use crate::ast::{Span, TypeSpecFile};
+use crate::checker;
+use crate::diagnostic::Diagnostic;
+use crate::parser::parse_with_offset;
+use crate::source::{FileSystem, SourceProvider};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// A source file loaded into a [`Program`].
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    pub path: PathBuf,
+    pub source: String,
+    /// Position of the file's first byte in the program's span space
+    pub offset: usize,
+}
+
+impl SourceFile {
+    /// Whether `span` points into this file.
+    pub fn contains(&self, span: Span) -> bool {
+        span.start >= self.offset && span.start <= self.offset + self.source.len()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum LoadError {
+    #[error("Failed to read {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// Entry files and their imports, merged into one file.
+///
+/// Spans of all files share one position space: each file is parsed at its
+/// own [`SourceFile::offset`], so any span in the merged file or in a
+/// diagnostic identifies the file it belongs to. Declarations of a
+/// `namespace X;` file are moved into a `namespace X { }` block before
+/// merging (see [`TypeSpecFile::into_scoped`]).
+#[derive(Debug, Default)]
+pub struct Program {
+    files: Vec<SourceFile>,
+    file: TypeSpecFile,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Program {
+    /// Load entry files from disk and follow their relative imports.
+    ///
+    /// Imports of `@typespec/` libraries are skipped, and each file is loaded
+    /// once however often it is imported. Import cycles are reported as
+    /// warnings. Parse errors and missing imports are collected as
+    /// diagnostics; once every file parses, the merged file is checked with
+    /// [`checker::check`].
+    pub fn load(entries: &[impl AsRef<Path>]) -> Result<Self, LoadError> {
+        Self::load_with(entries, &FileSystem)
+    }
+
+    /// Like [`Program::load`], reading every file through `sources`.
+    pub fn load_with(
+        entries: &[impl AsRef<Path>],
+        sources: &impl SourceProvider,
+    ) -> Result<Self, LoadError> {
+        let mut program = Program::default();
+        let mut loaded = HashSet::new();
+
+        for entry in entries {
+            let entry = entry.as_ref();
+            let path = sources.resolve(entry).map_err(|source| LoadError::Io {
+                path: entry.to_path_buf(),
+                source,
+            })?;
+            if loaded.insert(path.clone()) {
+                program.load_file(sources, path, &mut loaded, &mut Vec::new())?;
+            }
+        }
+
+        if !program.has_errors() {
+            program.diagnostics.extend(checker::check(&program.file));
+        }
+        Ok(program)
+    }
+
+    fn load_file(
+        &mut self,
+        sources: &dyn SourceProvider,
+        path: PathBuf,
+        loaded: &mut HashSet<PathBuf>,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<(), LoadError> {
+        let source = sources.read(&path).map_err(|source| LoadError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        let offset = self
+            .files
+            .last()
+            .map(|f| f.offset + f.source.len() + 1)
+            .unwrap_or(0);
+
+        let (file, diagnostics) = parse_with_offset(&source, offset);
+        self.diagnostics.extend(diagnostics);
+        self.files.push(SourceFile {
+            path: path.clone(),
+            source,
+            offset,
+        });
+
+        // Declarations of a file come before those of its imports
+        let file = file.into_scoped();
+        self.file.usings.extend(file.usings);
+        self.file.declarations.extend(file.declarations);
+
+        let dir = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+        stack.push(path);
+        for import in file.imports {
+            if import.path.starts_with("@typespec/") {
+                continue;
+            }
+
+            let import_path = dir.join(&import.path);
+            let import_path = if import_path.extension().is_none() {
+                import_path.with_extension("tsp")
+            } else {
+                import_path
+            };
+            let Ok(import_path) = sources.resolve(&import_path) else {
+                self.diagnostics.push(Diagnostic::error(
+                    format!("cannot find import \"{}\"", import.path),
+                    import.span,
+                ));
+                continue;
+            };
+
+            if let Some(start) = stack.iter().position(|p| *p == import_path) {
+                let cycle: Vec<_> = stack[start..]
+                    .iter()
+                    .chain([&import_path])
+                    .map(|p| file_name(p))
+                    .collect();
+                self.diagnostics.push(Diagnostic::warning(
+                    format!("import cycle: {}", cycle.join(" -> ")),
+                    import.span,
+                ));
+                continue;
+            }
+            if loaded.insert(import_path.clone()) {
+                self.load_file(sources, import_path, loaded, stack)?;
+            }
+        }
+        stack.pop();
+
+        Ok(())
+    }
+
+    /// The merged file.
+    pub fn file(&self) -> &TypeSpecFile {
+        &self.file
+    }
+
+    /// Loaded files, in load order: each file is followed by its imports.
+    pub fn files(&self) -> &[SourceFile] {
+        &self.files
+    }
+
+    /// The file a span (of a declaration, property, diagnostic...) is in.
+    pub fn source_of(&self, span: Span) -> Option<&SourceFile> {
+        self.files.iter().find(|f| f.contains(span))
+    }
+
+    /// Parse errors, missing imports, import cycles and checker findings.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(Diagnostic::is_error)
+    }
+
+    /// Render a diagnostic against the file it points into.
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let Some(file) = self.source_of(diagnostic.span) else {
+            return format!("{}: {}", diagnostic.severity.label(), diagnostic.message);
+        };
+        let local = Diagnostic {
+            span: Span::new(
+                diagnostic.span.start - file.offset,
+                diagnostic.span.end.saturating_sub(file.offset),
+            ),
+            ..diagnostic.clone()
+        };
+        local.render(&file.path.display().to_string(), &file.source)
+    }
+
+    /// Render every diagnostic, separated by blank lines.
+    pub fn render_diagnostics(&self) -> String {
+        self.diagnostics
+            .iter()
+            .map(|d| self.render(d))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
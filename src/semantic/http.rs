@@ -0,0 +1,736 @@
+//! HTTP Operations
+//!
+//! Resolve an operation into what every generator serves or calls: its verb,
+//! full path, where each parameter is sent, applying TypeSpec's rules for
+//! parameters without `@path`, `@query`, `@header` or `@body`, and the
+//! responses it declares in its return type, e.g.
+//! `{ @statusCode _: 201; @body body: Task } | NotFound | ApiError`: their
+//! status codes, bodies, headers and whether they are `@error` models.
+
+use super::inheritance::lookup_model;
+use super::{has_decorator, resolve_params, resolve_properties, ModelMap};
+use crate::ast::{Decorator, Interface, Model, Operation, OperationParam, Property, TypeRef};
+use crate::diagnostic::Diagnostic;
+use convert_case::{Case, Casing};
+use std::borrow::Cow;
+
+/// An operation as it is sent over HTTP
+#[derive(Debug, Clone)]
+pub struct HttpOperation<'a> {
+    /// The operation, for its name, docs and return type
+    pub op: &'a Operation,
+    /// HTTP method, e.g. `GET`
+    pub method: &'static str,
+    /// Path template with the interface's route, e.g. `/tasks/{id}`
+    pub path: String,
+    pub params: Vec<HttpParam<'a>>,
+    /// Success responses, one per status
+    pub responses: Vec<Response<'a>>,
+    /// `@error` responses, those with a fixed status first
+    pub errors: Vec<Response<'a>>,
+}
+
+impl<'a> HttpOperation<'a> {
+    /// Resolve an operation of `iface`
+    pub fn new(iface: &Interface, op: &'a Operation, models: &ModelMap<'a>) -> Self {
+        let mut path = format!(
+            "{}{}",
+            route(&iface.decorators).unwrap_or_default(),
+            route(&op.decorators).unwrap_or_default()
+        );
+        // Operations without a route are served at the root
+        if !path.starts_with('/') {
+            path.insert(0, '/');
+        }
+        Self {
+            op,
+            method: http_method(&op.decorators),
+            params: http_params(op, &path, models),
+            path,
+            responses: success_responses(op.return_type.as_ref(), models),
+            errors: error_responses(op.return_type.as_ref(), models),
+        }
+    }
+
+    /// The parameters sent at `location`
+    pub fn params_at(&self, location: ParamLocation) -> impl Iterator<Item = &HttpParam<'a>> {
+        self.params.iter().filter(move |p| p.location == location)
+    }
+
+    /// The `@body` parameter
+    pub fn body(&self) -> Option<&HttpParam<'a>> {
+        self.params_at(ParamLocation::Body).next()
+    }
+
+    /// Whether the request has a body, explicit or implicit
+    pub fn has_body(&self) -> bool {
+        self.params.iter().any(|p| {
+            matches!(
+                p.location,
+                ParamLocation::Body | ParamLocation::BodyProperty
+            )
+        })
+    }
+
+    /// Name of the type of a response's `@header`s in the service
+    /// `iface_name`: `TasksCreateResponseHeaders` when it is the only
+    /// response, `TasksCreateCreatedHeaders` for the 201 of several
+    pub fn headers_name(&self, iface_name: &str, response: &Response<'_>) -> String {
+        let op_name = self.op.name.to_case(Case::Pascal);
+        if self.responses.len() > 1 {
+            format!(
+                "{}{}{}Headers",
+                iface_name,
+                op_name,
+                status_name(response.status)
+            )
+        } else {
+            format!("{}{}ResponseHeaders", iface_name, op_name)
+        }
+    }
+
+    /// The responses sent with `@header`s
+    pub fn header_responses(&self) -> impl Iterator<Item = &Response<'a>> {
+        self.responses.iter().filter(|r| !r.headers.is_empty())
+    }
+}
+
+/// The operations of a service as sent over HTTP
+pub fn http_operations<'a>(iface: &'a Interface, models: &ModelMap<'a>) -> Vec<HttpOperation<'a>> {
+    iface
+        .operations
+        .iter()
+        .map(|op| HttpOperation::new(iface, op, models))
+        .collect()
+}
+
+/// Where a parameter is sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamLocation {
+    Path,
+    Query,
+    Header,
+    /// The whole request body, marked `@body`
+    Body,
+    /// A property of the implicit JSON body, for a parameter without a
+    /// location that is not in the route
+    BodyProperty,
+}
+
+/// An operation parameter with where it is sent
+#[derive(Debug, Clone)]
+pub struct HttpParam<'a> {
+    pub param: Cow<'a, OperationParam>,
+    pub location: ParamLocation,
+}
+
+impl std::ops::Deref for HttpParam<'_> {
+    type Target = OperationParam;
+
+    fn deref(&self) -> &OperationParam {
+        &self.param
+    }
+}
+
+/// The parameters of an operation served at `path`, with spreads expanded.
+///
+/// A parameter without a location is a path parameter when its name appears
+/// in the route, e.g. `{id}`, and otherwise a property of the implicit body.
+/// Spreads of unknown models are left out.
+fn http_params<'a>(op: &'a Operation, path: &str, models: &ModelMap<'a>) -> Vec<HttpParam<'a>> {
+    let route_params = path_params(path);
+    resolve_params(op, models)
+        .into_iter()
+        .filter(|param| !(param.spread && param.name.is_empty()))
+        .map(|param| {
+            let location = if has_decorator(&param.decorators, "path") {
+                ParamLocation::Path
+            } else if has_decorator(&param.decorators, "query") {
+                ParamLocation::Query
+            } else if is_header(&param.decorators) {
+                ParamLocation::Header
+            } else if has_decorator(&param.decorators, "body") {
+                ParamLocation::Body
+            } else if route_params.contains(&param.name.as_str()) {
+                ParamLocation::Path
+            } else {
+                ParamLocation::BodyProperty
+            };
+            HttpParam { param, location }
+        })
+        .collect()
+}
+
+/// Check the parameters of an operation against its route and method:
+/// `@path` parameters must be in the route and every name in the route must
+/// be a path parameter. Parameters left for the implicit body are an error when
+/// the operation cannot have a body, a GET or DELETE, or already has a
+/// `@body`.
+pub fn check_params(operation: &HttpOperation<'_>) -> Vec<Diagnostic> {
+    let op = operation.op;
+    let route_params = path_params(&operation.path);
+    let mut diagnostics: Vec<_> = operation
+        .params_at(ParamLocation::Path)
+        .filter(|param| !route_params.contains(&param.name.as_str()))
+        .map(|param| {
+            Diagnostic::error(
+                format!(
+                    "@path parameter `{}` of `{}` is not in its route `{}`",
+                    param.name, op.name, operation.path
+                ),
+                param.span,
+            )
+        })
+        .collect();
+    for name in route_params {
+        if !operation
+            .params_at(ParamLocation::Path)
+            .any(|param| param.name == name)
+        {
+            diagnostics.push(Diagnostic::error(
+                format!(
+                    "route `{}` of `{}` has no path parameter `{}`",
+                    operation.path, op.name, name
+                ),
+                op.span,
+            ));
+        }
+    }
+
+    let reason = match operation.method {
+        "GET" | "DELETE" => format!(
+            "`{}` is a {} operation without a body",
+            op.name, operation.method
+        ),
+        _ if operation.body().is_some() => format!("`{}` already has a @body", op.name),
+        _ => return diagnostics,
+    };
+    diagnostics.extend(
+        operation
+            .params_at(ParamLocation::BodyProperty)
+            .map(|param| {
+                Diagnostic::error(
+                    format!(
+                        "parameter `{}` needs @path, @query or @header: {}",
+                        param.name, reason
+                    ),
+                    param.span,
+                )
+            }),
+    );
+    diagnostics
+}
+
+/// Names of the parameters in a route, e.g. `id` for `/tasks/{id}`
+pub fn path_params(path: &str) -> Vec<&str> {
+    path.split('{')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}'))
+        .map(|(name, _)| name)
+        .collect()
+}
+
+/// The route of an operation or interface, from `@route`
+pub fn route(decorators: &[Decorator]) -> Option<&str> {
+    decorators
+        .iter()
+        .find(|d| d.name == "route")
+        .and_then(|d| d.get_string_arg(0))
+}
+
+/// The HTTP method of an operation, from its verb decorator; GET without one
+pub fn http_method(decorators: &[Decorator]) -> &'static str {
+    for d in decorators {
+        match d.name.as_str() {
+            "get" => return "GET",
+            "post" => return "POST",
+            "put" => return "PUT",
+            "patch" => return "PATCH",
+            "delete" => return "DELETE",
+            _ => {}
+        }
+    }
+    "GET"
+}
+
+/// A response an operation can send
+#[derive(Debug, Clone)]
+pub struct Response<'a> {
+    /// Status code; `None` when a `@statusCode` property picks it at runtime
+    pub status: Option<u16>,
+    /// Body type; `None` when the response has no content
+    pub body: Option<TypeRef>,
+    /// The `@error` model this response sends
+    pub error: Option<&'a Model>,
+    /// `@header` properties sent along with the body, e.g. `ETag`
+    pub headers: Vec<Property>,
+}
+
+impl Response<'_> {
+    pub fn is_error(&self) -> bool {
+        self.error.is_some()
+    }
+}
+
+/// The responses of an operation returning `return_type`, in declaration order
+pub fn responses<'a>(return_type: Option<&TypeRef>, models: &ModelMap<'a>) -> Vec<Response<'a>> {
+    let Some(return_type) = return_type else {
+        return vec![Response {
+            status: Some(204),
+            body: None,
+            error: None,
+            headers: Vec::new(),
+        }];
+    };
+    match return_type {
+        TypeRef::Union(variants) => variants
+            .iter()
+            .map(|variant| response(variant, models))
+            .collect(),
+        _ => vec![response(return_type, models)],
+    }
+}
+
+/// The `@error` responses of an operation. Responses with a fixed status
+/// come first, so decoders try them before the ones that accept any status.
+pub fn error_responses<'a>(
+    return_type: Option<&TypeRef>,
+    models: &ModelMap<'a>,
+) -> Vec<Response<'a>> {
+    let mut errors: Vec<_> = responses(return_type, models)
+        .into_iter()
+        .filter(Response::is_error)
+        .collect();
+    errors.sort_by_key(|r| r.status.is_none());
+    errors
+}
+
+/// The success responses of an operation, one per status. Bodies sent with
+/// the same status are merged into a union, e.g. `Task | Job`.
+pub fn success_responses<'a>(
+    return_type: Option<&TypeRef>,
+    models: &ModelMap<'a>,
+) -> Vec<Response<'a>> {
+    let mut successes: Vec<Response<'a>> = Vec::new();
+    for response in responses(return_type, models) {
+        if response.is_error() {
+            continue;
+        }
+        let Some(existing) = successes.iter_mut().find(|r| r.status == response.status) else {
+            successes.push(response);
+            continue;
+        };
+        existing.body = match (existing.body.take(), response.body) {
+            (Some(TypeRef::Union(mut variants)), Some(body)) => {
+                if !variants.contains(&body) {
+                    variants.push(body);
+                }
+                Some(TypeRef::Union(variants))
+            }
+            (Some(first), Some(body)) if first != body => Some(TypeRef::Union(vec![first, body])),
+            (first, body) => first.or(body),
+        };
+        for header in response.headers {
+            if !existing.headers.iter().any(|h| h.name == header.name) {
+                existing.headers.push(header);
+            }
+        }
+    }
+    successes
+}
+
+/// The first success response of an operation
+pub fn success_response<'a>(
+    return_type: Option<&TypeRef>,
+    models: &ModelMap<'a>,
+) -> Option<Response<'a>> {
+    success_responses(return_type, models).into_iter().next()
+}
+
+/// Name of a response by its status, e.g. `Created` for 201. Responses whose
+/// status is picked at runtime are named `Default`.
+pub fn status_name(status: Option<u16>) -> String {
+    let Some(status) = status else {
+        return "Default".to_string();
+    };
+    let name = match status {
+        200 => "Ok",
+        201 => "Created",
+        202 => "Accepted",
+        203 => "NonAuthoritativeInformation",
+        204 => "NoContent",
+        205 => "ResetContent",
+        206 => "PartialContent",
+        301 => "MovedPermanently",
+        302 => "Found",
+        303 => "SeeOther",
+        304 => "NotModified",
+        307 => "TemporaryRedirect",
+        308 => "PermanentRedirect",
+        _ => return format!("Status{}", status),
+    };
+    name.to_string()
+}
+
+/// Whether a model is tagged `@error`
+pub fn is_error_model(model: &Model) -> bool {
+    has_decorator(&model.decorators, "error")
+}
+
+/// Whether a property is HTTP metadata rather than part of a body
+pub fn is_metadata(prop: &Property) -> bool {
+    is_status_code(prop) || is_header(&prop.decorators)
+}
+
+/// Whether a property is the `@statusCode` of a response
+pub fn is_status_code(prop: &Property) -> bool {
+    has_decorator(&prop.decorators, "statusCode")
+}
+
+/// Whether a parameter or property is sent as an HTTP header
+pub fn is_header(decorators: &[Decorator]) -> bool {
+    has_decorator(decorators, "header")
+}
+
+/// Name of the HTTP header a `@header` parameter or property is sent as: the
+/// decorator's argument, e.g. `@header("X-Request-ID")`, or its name in
+/// kebab-case, e.g. `if-match` for `ifMatch`
+pub fn header_name(decorators: &[Decorator], name: &str) -> String {
+    decorators
+        .iter()
+        .find(|d| d.name == "header")
+        .and_then(|d| d.get_string_arg(0))
+        .map(str::to_string)
+        .unwrap_or_else(|| name.to_case(Case::Kebab))
+}
+
+/// The `@statusCode` property of a model
+pub fn status_property(model: &Model, models: &ModelMap<'_>) -> Option<Property> {
+    resolve_properties(model, models)
+        .into_iter()
+        .find(|p| is_status_code(p))
+        .map(|p| p.into_owned())
+}
+
+fn response<'a>(type_ref: &TypeRef, models: &ModelMap<'a>) -> Response<'a> {
+    match type_ref {
+        TypeRef::AnonymousModel(props) => from_properties(props.iter()),
+        TypeRef::Builtin(name) if name == "void" => Response {
+            status: Some(204),
+            body: None,
+            error: None,
+            headers: Vec::new(),
+        },
+        _ => {
+            let Some(model) = lookup_model(type_ref, models) else {
+                return Response {
+                    status: Some(200),
+                    body: Some(type_ref.clone()),
+                    error: None,
+                    headers: Vec::new(),
+                };
+            };
+            let properties = resolve_properties(model, models);
+            if is_error_model(model) {
+                return Response {
+                    status: properties.iter().find_map(|p| status_literal(p)),
+                    body: Some(type_ref.clone()),
+                    error: Some(model),
+                    headers: properties
+                        .iter()
+                        .filter(|p| is_header(&p.decorators))
+                        .map(|p| p.as_ref().clone())
+                        .collect(),
+                };
+            }
+            // A named response envelope, e.g. `model Created { @statusCode _: 201; @body task: Task }`.
+            // Without a `@body`, the model itself is the body, less its metadata.
+            if properties
+                .iter()
+                .any(|p| has_decorator(&p.decorators, "body"))
+            {
+                return from_properties(properties.iter().map(|p| p.as_ref()));
+            }
+            if properties.iter().any(|p| is_metadata(p)) {
+                let response = from_properties(properties.iter().map(|p| p.as_ref()));
+                return Response {
+                    body: response.body.map(|_| type_ref.clone()),
+                    ..response
+                };
+            }
+            Response {
+                status: Some(200),
+                body: Some(type_ref.clone()),
+                error: None,
+                headers: Vec::new(),
+            }
+        }
+    }
+}
+
+/// A response described by envelope properties: `@statusCode`, `@header`,
+/// `@body` and, without a `@body`, the remaining properties as an anonymous body
+fn from_properties<'p, 'a>(props: impl Iterator<Item = &'p Property>) -> Response<'a> {
+    let mut status = None;
+    let mut dynamic_status = false;
+    let mut body = None;
+    let mut headers = Vec::new();
+    let mut rest = Vec::new();
+    for prop in props {
+        if is_status_code(prop) {
+            status = status_literal(prop);
+            dynamic_status = status.is_none();
+        } else if is_header(&prop.decorators) {
+            headers.push(prop.clone());
+        } else if has_decorator(&prop.decorators, "body") {
+            body = Some(prop.type_ref.clone());
+        } else {
+            rest.push(prop.clone());
+        }
+    }
+    if body.is_none() && !rest.is_empty() {
+        body = Some(TypeRef::AnonymousModel(rest));
+    }
+    if status.is_none() && !dynamic_status {
+        status = Some(if body.is_some() { 200 } else { 204 });
+    }
+    Response {
+        status,
+        body,
+        error: None,
+        headers,
+    }
+}
+
+/// The status of a `@statusCode` property typed with a literal, e.g. `_: 404`
+pub fn status_literal(prop: &Property) -> Option<u16> {
+    if !is_status_code(prop) {
+        return None;
+    }
+    match prop.type_ref {
+        TypeRef::IntLiteral(code) => u16::try_from(code).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+    use crate::semantic::build_model_map;
+
+    #[test]
+    fn test_http_params_infer_locations() {
+        let file = parse(
+            r#"
+            model Filters { @query search?: string; }
+
+            op update(
+                id: string,
+                @header("X-Request-ID") requestId: string,
+                ...Filters,
+                title: string,
+                done?: boolean,
+            ): void;
+            "#,
+        )
+        .unwrap();
+        let models = build_model_map(&file);
+        let op = file.operations().next().unwrap();
+
+        let params = http_params(op, "/tasks/{id}", &models);
+        let locations: Vec<_> = params
+            .iter()
+            .map(|p| (p.name.as_str(), p.location))
+            .collect();
+        assert_eq!(
+            locations,
+            vec![
+                ("id", ParamLocation::Path),
+                ("requestId", ParamLocation::Header),
+                ("search", ParamLocation::Query),
+                ("title", ParamLocation::BodyProperty),
+                ("done", ParamLocation::BodyProperty),
+            ]
+        );
+        assert_eq!(path_params("/tasks/{id}/runs/{runId}"), vec!["id", "runId"]);
+    }
+
+    #[test]
+    fn test_http_operations() {
+        let file = parse(
+            r#"
+            model Task { id: string; }
+            @error model NotFound { @statusCode _: 404; message: string; }
+
+            @route("/tasks")
+            interface Tasks {
+                @get @route("/{id}") get(id: string, @query verbose?: boolean): Task | NotFound;
+                @post create(@body body: Task): { @statusCode _: 201; @body body: Task };
+                @delete @route("/{id}") remove(id: string): void;
+                list(): Task[];
+            }
+            "#,
+        )
+        .unwrap();
+        let models = build_model_map(&file);
+        let iface = file.interfaces().next().unwrap();
+
+        let operations = http_operations(iface, &models);
+        let routes: Vec<_> = operations
+            .iter()
+            .map(|o| (o.op.name.as_str(), o.method, o.path.as_str()))
+            .collect();
+        assert_eq!(
+            routes,
+            vec![
+                ("get", "GET", "/tasks/{id}"),
+                ("create", "POST", "/tasks"),
+                ("remove", "DELETE", "/tasks/{id}"),
+                ("list", "GET", "/tasks"),
+            ]
+        );
+
+        let get = &operations[0];
+        assert_eq!(get.params_at(ParamLocation::Query).count(), 1);
+        assert!(!get.has_body());
+        assert_eq!(get.responses.len(), 1);
+        assert_eq!(get.errors[0].status, Some(404));
+
+        let create = &operations[1];
+        assert_eq!(create.body().map(|p| p.name.as_str()), Some("body"));
+        assert_eq!(create.responses[0].status, Some(201));
+    }
+
+    #[test]
+    fn test_responses_split_errors() {
+        let file = parse(
+            r#"
+            model Task { id: string; }
+            @error model NotFound { @statusCode _: 404; message: string; }
+            @error model ApiError { @statusCode statusCode: int32; code: string; }
+
+            op create(): { @statusCode _: 201; @body body: Task } | NotFound | ApiError;
+            "#,
+        )
+        .unwrap();
+        let models = build_model_map(&file);
+        let op = file.operations().next().unwrap();
+
+        let responses = responses(op.return_type.as_ref(), &models);
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0].status, Some(201));
+        assert_eq!(responses[0].body, Some(TypeRef::Named("Task".into())));
+        assert!(!responses[0].is_error());
+
+        let errors = error_responses(op.return_type.as_ref(), &models);
+        let names: Vec<_> = errors
+            .iter()
+            .map(|r| r.error.unwrap().name.as_str())
+            .collect();
+        assert_eq!(names, vec!["NotFound", "ApiError"]);
+        assert_eq!(errors[0].status, Some(404));
+        assert_eq!(errors[1].status, None);
+    }
+
+    #[test]
+    fn test_success_responses_by_status() {
+        let file = parse(
+            r#"
+            model Task { id: string; }
+            model Job { id: string; }
+
+            op create(): { @statusCode _: 201; @body task: Task }
+                | { @statusCode _: 202; @body job: Job }
+                | { @statusCode _: 202; @body other: Job };
+
+            op find(): Task | Job;
+            "#,
+        )
+        .unwrap();
+        let models = build_model_map(&file);
+        let op = file.operations().next().unwrap();
+
+        let successes = success_responses(op.return_type.as_ref(), &models);
+        let names: Vec<_> = successes.iter().map(|r| status_name(r.status)).collect();
+        assert_eq!(names, vec!["Created", "Accepted"]);
+        assert_eq!(successes[1].body, Some(TypeRef::Named("Job".into())));
+        assert_eq!(status_name(Some(299)), "Status299");
+
+        // Plain types are all sent with 200
+        let find = file.operations().nth(1).unwrap();
+        let successes = success_responses(find.return_type.as_ref(), &models);
+        assert_eq!(successes.len(), 1);
+        assert_eq!(
+            successes[0].body,
+            Some(TypeRef::Union(vec![
+                TypeRef::Named("Task".into()),
+                TypeRef::Named("Job".into())
+            ]))
+        );
+        assert_eq!(status_name(None), "Default");
+    }
+
+    #[test]
+    fn test_response_without_body_is_no_content() {
+        let file = parse("op remove(): { @statusCode _: 204 };\nop ping(): void;").unwrap();
+        let models = build_model_map(&file);
+        for op in file.operations() {
+            let success = success_response(op.return_type.as_ref(), &models).unwrap();
+            assert_eq!(success.status, Some(204));
+            assert!(success.body.is_none());
+        }
+    }
+
+    #[test]
+    fn test_response_headers() {
+        let file = parse(
+            r#"
+            model Task { id: string; }
+
+            op create(@header("X-Request-ID") requestId: string): {
+                @statusCode _: 201;
+                @header location: string;
+                @header contentLanguage?: string;
+                @body task: Task;
+            };
+            "#,
+        )
+        .unwrap();
+        let models = build_model_map(&file);
+        let op = file.operations().next().unwrap();
+
+        let success = success_response(op.return_type.as_ref(), &models).unwrap();
+        assert_eq!(success.body, Some(TypeRef::Named("Task".into())));
+        let names: Vec<_> = success
+            .headers
+            .iter()
+            .map(|h| header_name(&h.decorators, &h.name))
+            .collect();
+        assert_eq!(names, vec!["location", "content-language"]);
+
+        let param = &op.params[0];
+        assert!(is_header(&param.decorators));
+        assert_eq!(header_name(&param.decorators, &param.name), "X-Request-ID");
+    }
+
+    #[test]
+    fn test_named_response_with_headers_is_its_body() {
+        let file = parse(
+            r#"
+            model TaskWithEtag { @header etag: string; id: string; title: string; }
+
+            op get(): TaskWithEtag;
+            "#,
+        )
+        .unwrap();
+        let models = build_model_map(&file);
+        let op = file.operations().next().unwrap();
+
+        let success = success_response(op.return_type.as_ref(), &models).unwrap();
+        assert_eq!(success.status, Some(200));
+        assert_eq!(success.body, Some(TypeRef::Named("TaskWithEtag".into())));
+        assert_eq!(success.headers.len(), 1);
+        assert_eq!(success.headers[0].name, "etag");
+    }
+}
@@ -0,0 +1,322 @@
+//! Model Inheritance
+//!
+//! Resolve the properties a model gets from `extends`, `is` and spreads, and
+//! the parameters an operation gets from spreads.
+
+use super::templates::{substitute_type, TypeBindings};
+use super::ModelMap;
+use crate::ast::{Model, Operation, OperationParam, Property, TypeRef, TypeSpecFile};
+use crate::diagnostic::Diagnostic;
+use std::borrow::Cow;
+
+/// Resolve all properties of a model, in order: inherited from `extends`,
+/// copied with `is`, spread in, then declared. A property with the same name
+/// as an earlier one replaces it in place.
+pub fn resolve_properties<'a>(model: &'a Model, models: &ModelMap<'a>) -> Vec<Cow<'a, Property>> {
+    collect_properties(model, models, true, &mut vec![model.name.as_str()])
+}
+
+/// Resolve the parameters of an operation: a spread like `...PaginationParams`
+/// becomes a parameter per property of the model, keeping its decorators and
+/// default. Spreads of unknown models are kept as they are.
+pub fn resolve_params<'a>(
+    op: &'a Operation,
+    models: &ModelMap<'a>,
+) -> Vec<Cow<'a, OperationParam>> {
+    let mut params = Vec::new();
+    for param in &op.params {
+        if !param.spread || lookup_model(&param.type_ref, models).is_none() {
+            params.push(Cow::Borrowed(param));
+            continue;
+        }
+        for prop in referenced_properties(&param.type_ref, models, &mut Vec::new()) {
+            params.push(Cow::Owned(OperationParam {
+                name: prop.name.clone(),
+                decorators: prop.decorators.clone(),
+                type_ref: prop.type_ref.clone(),
+                optional: prop.optional,
+                default: prop.default.clone(),
+                spread: false,
+                span: prop.span,
+            }));
+        }
+    }
+    params
+}
+
+/// Properties a model adds on top of its `extends` base, including the
+/// base properties it overrides.
+pub fn own_properties<'a>(model: &'a Model, models: &ModelMap<'a>) -> Vec<Cow<'a, Property>> {
+    collect_properties(model, models, false, &mut vec![model.name.as_str()])
+}
+
+/// Order models so that every `extends` base comes before its subclasses
+pub fn models_in_inheritance_order<'a>(
+    file: &'a TypeSpecFile,
+    models: &ModelMap<'a>,
+) -> Vec<&'a Model> {
+    fn visit<'a>(
+        model: &'a Model,
+        models: &ModelMap<'a>,
+        visiting: &mut Vec<&'a str>,
+        ordered: &mut Vec<&'a Model>,
+    ) {
+        if visiting.contains(&model.name.as_str())
+            || ordered.iter().any(|m| std::ptr::eq(*m, model))
+        {
+            return;
+        }
+        visiting.push(&model.name);
+        if let Some(base) = model.extends.as_ref().and_then(|b| lookup_model(b, models)) {
+            visit(base, models, visiting, ordered);
+        }
+        visiting.pop();
+        ordered.push(model);
+    }
+
+    let mut ordered = Vec::new();
+    for model in file.models() {
+        visit(model, models, &mut Vec::new(), &mut ordered);
+    }
+    ordered
+}
+
+/// Check that properties a model redeclares are compatible with the ones
+/// it inherits from its `extends` base.
+pub fn check_overrides<'a>(model: &'a Model, models: &ModelMap<'a>) -> Vec<Diagnostic> {
+    let Some(base) = &model.extends else {
+        return Vec::new();
+    };
+    let inherited = referenced_properties(base, models, &mut vec![model.name.as_str()]);
+
+    let mut diagnostics = Vec::new();
+    for prop in &model.properties {
+        let Some(base_prop) = inherited.iter().find(|p| p.name == prop.name) else {
+            continue;
+        };
+        if prop.optional && !base_prop.optional {
+            diagnostics.push(Diagnostic::error(
+                format!(
+                    "property `{}` of `{}` cannot be optional: it is required in `{}`",
+                    prop.name, model.name, base
+                ),
+                prop.span,
+            ));
+        } else if !is_assignable(&prop.type_ref, &base_prop.type_ref) {
+            diagnostics.push(Diagnostic::error(
+                format!(
+                    "property `{}` of `{}` has type `{}`, which is not assignable to `{}` inherited from `{}`",
+                    prop.name, model.name, prop.type_ref, base_prop.type_ref, base
+                ),
+                prop.span,
+            ));
+        }
+    }
+    diagnostics
+}
+
+fn collect_properties<'a>(
+    model: &'a Model,
+    models: &ModelMap<'a>,
+    include_base: bool,
+    stack: &mut Vec<&'a str>,
+) -> Vec<Cow<'a, Property>> {
+    let mut properties = Vec::new();
+
+    if include_base {
+        if let Some(base) = &model.extends {
+            merge(&mut properties, referenced_properties(base, models, stack));
+        }
+    }
+    if let Some(source) = &model.is {
+        merge(
+            &mut properties,
+            referenced_properties(source, models, stack),
+        );
+    }
+    for spread_ref in &model.spread_refs {
+        merge(
+            &mut properties,
+            referenced_properties(spread_ref, models, stack),
+        );
+    }
+    merge(&mut properties, model.properties.iter().map(Cow::Borrowed));
+
+    properties
+}
+
+/// All properties of the model `type_ref` refers to, with its template
+/// arguments substituted. Unknown and cyclic references yield nothing.
+fn referenced_properties<'a>(
+    type_ref: &TypeRef,
+    models: &ModelMap<'a>,
+    stack: &mut Vec<&'a str>,
+) -> Vec<Cow<'a, Property>> {
+    let Some(model) = lookup_model(type_ref, models) else {
+        return Vec::new();
+    };
+    if stack.contains(&model.name.as_str()) {
+        return Vec::new();
+    }
+
+    stack.push(&model.name);
+    let properties = collect_properties(model, models, true, stack);
+    stack.pop();
+
+    if model.type_params.is_empty() {
+        return properties;
+    }
+
+    let args = match type_ref {
+        TypeRef::Generic { args, .. } => args.as_slice(),
+        _ => &[],
+    };
+    let bindings: TypeBindings<'_> = model
+        .type_params
+        .iter()
+        .map(String::as_str)
+        .zip(args)
+        .collect();
+    properties
+        .into_iter()
+        .map(|p| {
+            let type_ref = substitute_type(&p.type_ref, &bindings);
+            Cow::Owned(Property {
+                type_ref,
+                ..p.into_owned()
+            })
+        })
+        .collect()
+}
+
+fn merge<'a>(
+    properties: &mut Vec<Cow<'a, Property>>,
+    incoming: impl IntoIterator<Item = Cow<'a, Property>>,
+) {
+    for prop in incoming {
+        match properties.iter_mut().find(|p| p.name == prop.name) {
+            Some(existing) => *existing = prop,
+            None => properties.push(prop),
+        }
+    }
+}
+
+/// Model a type refers to, ignoring template arguments
+pub(crate) fn lookup_model<'a>(type_ref: &TypeRef, models: &ModelMap<'a>) -> Option<&'a Model> {
+    match type_ref {
+        TypeRef::Named(name) => models.get(name).copied(),
+        TypeRef::Qualified(parts) => models
+            .get(&parts.join("."))
+            .or_else(|| models.get(parts.last()?))
+            .copied(),
+        TypeRef::Generic { base, .. } => lookup_model(base, models),
+        _ => None,
+    }
+}
+
+/// Whether a property of type `derived` may override one of type `base`
+fn is_assignable(derived: &TypeRef, base: &TypeRef) -> bool {
+    if derived == base {
+        return true;
+    }
+    match (derived, base) {
+        (TypeRef::Union(narrowed), TypeRef::Union(variants)) => {
+            narrowed.iter().all(|t| variants.contains(t))
+        }
+        (_, TypeRef::Union(variants)) => variants.contains(derived),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+    use crate::semantic::build_model_map;
+
+    #[test]
+    fn test_override_must_be_assignable() {
+        let source = r#"
+            model Base {
+                status: "active" | "inactive";
+                id: string;
+                note: string;
+            }
+
+            model Narrowed extends Base {
+                status: "active";
+            }
+
+            model Broken extends Base {
+                id: int32;
+                note?: string;
+            }
+        "#;
+
+        let file = parse(source).unwrap();
+        let models = build_model_map(&file);
+
+        assert!(check_overrides(models["Narrowed"], &models).is_empty());
+        let diagnostics = check_overrides(models["Broken"], &models);
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics[0].message.contains("`int32`"));
+        assert!(diagnostics[1].message.contains("cannot be optional"));
+    }
+
+    #[test]
+    fn test_inheritance_order_puts_bases_first() {
+        let source = r#"
+            model Admin extends User {}
+            model User extends Entity {}
+            model Entity {}
+        "#;
+
+        let file = parse(source).unwrap();
+        let models = build_model_map(&file);
+        let names: Vec<_> = models_in_inheritance_order(&file, &models)
+            .iter()
+            .map(|m| m.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Entity", "User", "Admin"]);
+    }
+
+    #[test]
+    fn test_resolve_params_expands_spreads() {
+        let source = r#"
+            model Base { @query page?: int32 = 1; }
+            model Filters extends Base { @query search?: string; }
+
+            op list(@path id: string, ...Filters, ...Unknown): void;
+        "#;
+
+        let file = parse(source).unwrap();
+        let models = build_model_map(&file);
+        let op = file.operations().next().unwrap();
+        let params = resolve_params(op, &models);
+
+        let names: Vec<_> = params.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "page", "search", ""]);
+        assert!(!params[1].spread);
+        assert_eq!(params[1].default, Some(crate::ast::Value::Int(1)));
+        assert!(params[1].decorators.iter().any(|d| d.name == "query"));
+        assert!(params[3].spread);
+    }
+
+    #[test]
+    fn test_lookup_never_picks_a_same_named_model() {
+        let source = r#"
+            namespace Tasks { model Status { done: boolean; } }
+            namespace Agents { model Status { busy: boolean; } }
+        "#;
+        let file = parse(source).unwrap();
+        let models = build_model_map(&file);
+        let lookup = |parts: &[&str]| {
+            let type_ref = TypeRef::Qualified(parts.iter().map(|p| p.to_string()).collect());
+            lookup_model(&type_ref, &models).map(|m| m.properties[0].name.as_str())
+        };
+
+        assert_eq!(lookup(&["Tasks", "Status"]), Some("done"));
+        assert_eq!(lookup(&["Agents", "Status"]), Some("busy"));
+        assert!(lookup_model(&TypeRef::Named("Status".to_string()), &models).is_none());
+    }
+}
@@ -0,0 +1,215 @@
+//! Semantic Model
+//!
+//! Resolution shared by the checker and the code generators: model and
+//! scalar lookup, inheritance, template instantiation, the services to
+//! serve and the HTTP shape of their operations.
+
+pub mod http;
+pub mod inheritance;
+pub mod names;
+pub mod templates;
+
+use crate::ast::{Declaration, Decorator, Interface, Model, Operation, TypeSpecFile};
+use crate::symbols::SymbolTable;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use templates::resolve_operation;
+
+pub use inheritance::{own_properties, resolve_params, resolve_properties};
+
+/// Map of scalar name -> base type it extends
+pub type ScalarMap = HashMap<String, String>;
+
+/// Map of model name -> Model. Models are keyed by their fully qualified
+/// name and by each shorter qualified form, e.g. `Tasks.Status` and
+/// `Status`. A shorter form shared by models of different namespaces is
+/// left out, so a lookup never finds the wrong model.
+pub type ModelMap<'a> = HashMap<String, &'a Model>;
+
+/// Build a map of custom scalars from parsed TypeSpec file
+pub fn build_scalar_map(file: &TypeSpecFile) -> ScalarMap {
+    file.scalars()
+        .filter_map(|s| {
+            s.extends
+                .as_ref()
+                .map(|base| (s.name.clone(), base.clone()))
+        })
+        .collect()
+}
+
+/// Build a map of model names to models for spread resolution
+pub fn build_model_map(file: &TypeSpecFile) -> ModelMap<'_> {
+    let table = SymbolTable::build(file);
+    let declared: Vec<_> = table
+        .symbols()
+        .filter_map(|symbol| match symbol.declaration {
+            Declaration::Model(model) => Some((symbol.qualified_name.as_str(), model)),
+            _ => None,
+        })
+        .collect();
+
+    let mut models: ModelMap<'_> = declared
+        .iter()
+        .map(|&(name, model)| (name.to_string(), model))
+        .collect();
+    let mut shortened: HashMap<&str, Option<&Model>> = HashMap::new();
+    for &(name, model) in &declared {
+        for (i, _) in name.match_indices('.') {
+            let entry = shortened.entry(&name[i + 1..]).or_insert(Some(model));
+            if entry.is_some_and(|m| !std::ptr::eq(m, model)) {
+                *entry = None;
+            }
+        }
+    }
+    for (name, model) in shortened {
+        if let Some(model) = model {
+            models.entry(name.to_string()).or_insert(model);
+        }
+    }
+    models
+}
+
+/// Whether `decorators` include `@name`
+pub fn has_decorator(decorators: &[Decorator], name: &str) -> bool {
+    decorators.iter().any(|d| d.name == name)
+}
+
+/// Name of the service holding operations declared outside any interface
+/// and namespace
+pub const DEFAULT_SERVICE_NAME: &str = "Default";
+
+/// Collect the services to generate: every interface, plus free operations.
+///
+/// Top-level `op` declarations are grouped into a service named after the
+/// file's `namespace X;`, as if declared in a `namespace X { }` block, or
+/// [`DEFAULT_SERVICE_NAME`] without one; operations in a `namespace { }`
+/// block are grouped into a service named after the namespace, carrying its
+/// decorators. Services sharing a name are prefixed with the namespaces
+/// telling them apart, like types (see [`names::prefixed`]).
+/// `op ... is Template<Args>` references are resolved and operation
+/// templates themselves are left out.
+pub fn collect_services(file: &TypeSpecFile) -> Vec<Cow<'_, Interface>> {
+    qualified_services(file)
+        .into_iter()
+        .map(|(_, service)| service)
+        .collect()
+}
+
+/// The services of [`collect_services`], each with the fully qualified name
+/// of the interface or namespace declaring it, the file's namespace for
+/// free operations.
+pub fn qualified_services(file: &TypeSpecFile) -> Vec<(String, Cow<'_, Interface>)> {
+    let table = SymbolTable::build(file);
+
+    let mut services: Vec<(String, Cow<'_, Interface>)> = table
+        .symbols()
+        .filter_map(|symbol| match symbol.declaration {
+            Declaration::Interface(iface) => Some((symbol, iface)),
+            _ => None,
+        })
+        .map(|(symbol, iface)| {
+            let service = if iface.operations.iter().any(|op| op.is.is_some()) {
+                Cow::Owned(Interface {
+                    operations: resolve_operations(&iface.operations, &symbol.namespace, &table),
+                    ..iface.clone()
+                })
+            } else {
+                Cow::Borrowed(iface)
+            };
+            (symbol.qualified_name.clone(), service)
+        })
+        .collect();
+
+    let root = file.namespace.as_deref().unwrap_or_default();
+    let free_ops = resolve_operations(&direct_operations(&file.declarations), root, &table);
+    if let Some(first) = free_ops.first() {
+        let span = first.span;
+        let name = match root.rsplit('.').next() {
+            Some(name) if !name.is_empty() => name,
+            _ => DEFAULT_SERVICE_NAME,
+        };
+        services.push((
+            root.to_string(),
+            Cow::Owned(Interface {
+                name: name.to_string(),
+                decorators: Vec::new(),
+                operations: free_ops,
+                span,
+            }),
+        ));
+    }
+
+    collect_namespace_services(&file.declarations, root, &table, &mut services);
+
+    let renamed = names::prefixed(
+        services
+            .iter()
+            .enumerate()
+            .map(|(i, (qualified, service))| {
+                let namespace = qualified
+                    .rsplit_once('.')
+                    .map_or("", |(namespace, _)| namespace);
+                (i, namespace, service.name.as_str())
+            }),
+    );
+    for (i, name) in renamed {
+        services[i].1.to_mut().name = name;
+    }
+    services
+}
+
+fn collect_namespace_services<'a>(
+    declarations: &'a [Declaration],
+    namespace: &str,
+    table: &SymbolTable<'_>,
+    services: &mut Vec<(String, Cow<'a, Interface>)>,
+) {
+    for decl in declarations {
+        if let Declaration::Namespace(ns) = decl {
+            let scope = if namespace.is_empty() {
+                ns.name.clone()
+            } else {
+                format!("{}.{}", namespace, ns.name)
+            };
+            let operations =
+                resolve_operations(&direct_operations(&ns.declarations), &scope, table);
+            if !operations.is_empty() {
+                let name = ns.name.rsplit('.').next().unwrap_or(&ns.name);
+                services.push((
+                    scope.clone(),
+                    Cow::Owned(Interface {
+                        name: name.to_string(),
+                        decorators: ns.decorators.clone(),
+                        operations,
+                        span: ns.span,
+                    }),
+                ));
+            }
+            collect_namespace_services(&ns.declarations, &scope, table, services);
+        }
+    }
+}
+
+/// Free operations declared directly in `declarations`, not in nested namespaces
+fn direct_operations(declarations: &[Declaration]) -> Vec<Operation> {
+    declarations
+        .iter()
+        .filter_map(|d| match d {
+            Declaration::Operation(op) => Some(op.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Resolve `is` references, dropping operation templates
+fn resolve_operations(
+    operations: &[Operation],
+    scope: &str,
+    table: &SymbolTable<'_>,
+) -> Vec<Operation> {
+    operations
+        .iter()
+        .filter(|op| !op.is_template())
+        .map(|op| resolve_operation(op, scope, table))
+        .collect()
+}
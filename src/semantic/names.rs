@@ -0,0 +1,333 @@
+//! Generated Names
+//!
+//! Types and interfaces are generated without their namespace. Declarations
+//! that share a name in different namespaces are told apart by prefixing the
+//! namespaces they do not have in common: `Tasks.Status` and `Agents.Status`
+//! are generated as `TasksStatus` and `AgentsStatus`. Services, from
+//! interfaces and namespaces alike, are told apart the same way.
+
+use crate::ast::{Declaration, Operation, OperationParam, Property, TypeRef, TypeSpecFile, Value};
+use crate::symbols::{SymbolKind, SymbolTable};
+use convert_case::{Case, Casing};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Whether a declaration of this kind is generated as a named type or service
+pub fn is_generated(kind: SymbolKind) -> bool {
+    matches!(
+        kind,
+        SymbolKind::Model | SymbolKind::Enum | SymbolKind::Union | SymbolKind::Interface
+    )
+}
+
+/// Generated names of the declarations that need their namespace to be told
+/// apart, by fully qualified name. Every other declaration keeps its name.
+pub fn renamed(table: &SymbolTable<'_>) -> HashMap<String, String> {
+    let mut declared = HashSet::new();
+    prefixed(
+        table
+            .symbols()
+            .filter(|s| is_generated(s.kind))
+            .filter(|s| declared.insert(s.qualified_name.as_str()))
+            .map(|s| (s.qualified_name.clone(), s.namespace.as_str(), s.name())),
+    )
+}
+
+/// New names of the `(key, namespace, name)` items sharing a name with
+/// another: their name prefixed with the namespaces they do not have in
+/// common. Items keeping their name are left out.
+pub fn prefixed<'n, K: Eq + Hash>(
+    items: impl IntoIterator<Item = (K, &'n str, &'n str)>,
+) -> HashMap<K, String> {
+    let mut by_name: HashMap<&str, Vec<(K, &str)>> = HashMap::new();
+    for (key, namespace, name) in items {
+        by_name.entry(name).or_default().push((key, namespace));
+    }
+
+    let mut renamed = HashMap::new();
+    for (name, same) in by_name.into_iter().filter(|(_, same)| same.len() > 1) {
+        let common = common_namespace(same.iter().map(|(_, namespace)| *namespace));
+        for (key, namespace) in same {
+            let new: String = segments(namespace)
+                .skip(common)
+                .map(|part| part.to_case(Case::Pascal))
+                .chain([name.to_string()])
+                .collect();
+            if new != name {
+                renamed.insert(key, new);
+            }
+        }
+    }
+    renamed
+}
+
+/// Number of leading namespace segments shared by all `namespaces`
+fn common_namespace<'n>(mut namespaces: impl Iterator<Item = &'n str>) -> usize {
+    let Some(first) = namespaces.next() else {
+        return 0;
+    };
+    let mut common: Vec<&str> = segments(first).collect();
+    for namespace in namespaces {
+        let shared = common
+            .iter()
+            .zip(segments(namespace))
+            .take_while(|(a, b)| **a == *b)
+            .count();
+        common.truncate(shared);
+    }
+    common.len()
+}
+
+fn segments(namespace: &str) -> impl Iterator<Item = &str> {
+    namespace.split('.').filter(|part| !part.is_empty())
+}
+
+/// The file with declarations renamed to their generated names, see
+/// [`renamed`], and every reference to them rewritten to the new, unqualified name.
+pub fn disambiguate(file: &TypeSpecFile) -> Cow<'_, TypeSpecFile> {
+    let table = SymbolTable::build(file);
+    let renamed = renamed(&table);
+    if renamed.is_empty() {
+        return Cow::Borrowed(file);
+    }
+
+    let rewriter = Rewriter {
+        table: &table,
+        renamed: &renamed,
+    };
+    let mut declarations = file.declarations.clone();
+    let root = file.namespace.clone().unwrap_or_default();
+    rewriter.declarations(&mut declarations, &root);
+    Cow::Owned(TypeSpecFile {
+        declarations,
+        ..file.clone()
+    })
+}
+
+struct Rewriter<'t, 'a> {
+    table: &'t SymbolTable<'a>,
+    renamed: &'t HashMap<String, String>,
+}
+
+impl Rewriter<'_, '_> {
+    fn declarations(&self, declarations: &mut [Declaration], scope: &str) {
+        for decl in declarations {
+            match decl {
+                Declaration::Model(model) => {
+                    self.rename(&mut model.name, scope);
+                    let params = &model.type_params;
+                    for base in model.extends.iter_mut().chain(&mut model.is) {
+                        self.type_ref(base, scope, params);
+                    }
+                    for spread in &mut model.spread_refs {
+                        self.type_ref(spread, scope, params);
+                    }
+                    for prop in &mut model.properties {
+                        self.property(prop, scope, params);
+                    }
+                }
+                Declaration::Enum(e) => self.rename(&mut e.name, scope),
+                Declaration::Union(union) => {
+                    self.rename(&mut union.name, scope);
+                    for variant in &mut union.variants {
+                        self.type_ref(&mut variant.type_ref, scope, &[]);
+                    }
+                }
+                Declaration::Interface(iface) => {
+                    self.rename(&mut iface.name, scope);
+                    for op in &mut iface.operations {
+                        self.operation(op, scope);
+                    }
+                }
+                Declaration::Operation(op) => self.operation(op, scope),
+                Declaration::Alias(alias) => self.type_ref(&mut alias.type_ref, scope, &[]),
+                Declaration::Scalar(_) => {}
+                Declaration::Namespace(ns) => {
+                    let inner = if scope.is_empty() {
+                        ns.name.clone()
+                    } else {
+                        format!("{}.{}", scope, ns.name)
+                    };
+                    self.declarations(&mut ns.declarations, &inner);
+                }
+            }
+        }
+    }
+
+    fn rename(&self, name: &mut String, scope: &str) {
+        let qualified = if scope.is_empty() {
+            name.clone()
+        } else {
+            format!("{}.{}", scope, name)
+        };
+        if let Some(new) = self.renamed.get(&qualified) {
+            *name = new.clone();
+        }
+    }
+
+    fn operation(&self, op: &mut Operation, scope: &str) {
+        let params = &op.type_params;
+        if let Some(is) = &mut op.is {
+            self.type_ref(is, scope, params);
+        }
+        for param in &mut op.params {
+            self.param(param, scope, params);
+        }
+        if let Some(ret) = &mut op.return_type {
+            self.type_ref(ret, scope, params);
+        }
+    }
+
+    fn param(&self, param: &mut OperationParam, scope: &str, params: &[String]) {
+        self.type_ref(&mut param.type_ref, scope, params);
+        if let Some(default) = &mut param.default {
+            self.value(default, scope);
+        }
+    }
+
+    fn property(&self, prop: &mut Property, scope: &str, params: &[String]) {
+        self.type_ref(&mut prop.type_ref, scope, params);
+        if let Some(default) = &mut prop.default {
+            self.value(default, scope);
+        }
+    }
+
+    fn type_ref(&self, type_ref: &mut TypeRef, scope: &str, params: &[String]) {
+        match type_ref {
+            TypeRef::Named(name) if params.contains(name) => {}
+            TypeRef::Named(_) | TypeRef::Qualified(_) => {
+                let Some(Ok(symbol)) = self.table.resolve(type_ref, scope) else {
+                    return;
+                };
+                let Some(new) = self.renamed.get(&symbol.qualified_name) else {
+                    return;
+                };
+                // Generated names are unique, so the new name needs no namespace
+                *type_ref = match type_ref {
+                    // A reference to a member, like `Status.active`
+                    TypeRef::Qualified(written)
+                        if written.last().map(String::as_str) != Some(symbol.name()) =>
+                    {
+                        TypeRef::Qualified(vec![new.clone(), written.last().cloned().unwrap()])
+                    }
+                    _ => TypeRef::Named(new.clone()),
+                };
+            }
+            TypeRef::Generic { base, args } => {
+                self.type_ref(base, scope, params);
+                for arg in args {
+                    self.type_ref(arg, scope, params);
+                }
+            }
+            TypeRef::Array(inner) | TypeRef::Optional(inner) => self.type_ref(inner, scope, params),
+            TypeRef::Union(types) | TypeRef::Intersection(types) => {
+                for t in types {
+                    self.type_ref(t, scope, params);
+                }
+            }
+            TypeRef::AnonymousModel(props) => {
+                for prop in props {
+                    self.property(prop, scope, params);
+                }
+            }
+            TypeRef::Builtin(_) | TypeRef::StringLiteral(_) | TypeRef::IntLiteral(_) => {}
+        }
+    }
+
+    /// Rewrite an enum member value like `Status.active` to `TasksStatus.active`
+    fn value(&self, value: &mut Value, scope: &str) {
+        let Value::QualifiedIdent(parts) = value else {
+            return;
+        };
+        let Some((member, parent)) = parts.split_last() else {
+            return;
+        };
+        let Ok(symbol) = self.table.resolve_name(&parent.join("."), scope) else {
+            return;
+        };
+        if let Some(new) = self.renamed.get(&symbol.qualified_name) {
+            *parts = vec![new.clone(), member.clone()];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    #[test]
+    fn test_renamed_by_distinguishing_namespaces() {
+        let source = r#"
+            namespace AdiFamily;
+
+            model Status {}
+            namespace Tasks { model Status {} model Task {} }
+            namespace Agents.Runs { enum Status { busy } }
+        "#;
+        let file = parse(source).unwrap();
+        let renamed = renamed(&SymbolTable::build(&file));
+
+        let mut names: Vec<_> = renamed.iter().collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                (
+                    &"AdiFamily.Agents.Runs.Status".to_string(),
+                    &"AgentsRunsStatus".to_string()
+                ),
+                (
+                    &"AdiFamily.Tasks.Status".to_string(),
+                    &"TasksStatus".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_disambiguate_rewrites_references() {
+        let source = r#"
+            namespace Tasks {
+                enum Status { open, done }
+                model Task { status: Status = Status.open; list: Page<Status>[]; }
+            }
+            namespace Agents {
+                model Status {}
+                model Agent { status: Status; task: Tasks.Status; }
+            }
+            model Page<T> { items: T[]; }
+        "#;
+        let file = parse(source).unwrap();
+        let file = disambiguate(&file);
+
+        let names: Vec<_> = file.models().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["Task", "AgentsStatus", "Agent", "Page"]);
+        assert_eq!(file.enums().next().unwrap().name, "TasksStatus");
+
+        let types: Vec<_> = file
+            .models()
+            .flat_map(|m| &m.properties)
+            .map(|p| p.type_ref.to_string())
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                "TasksStatus",
+                "Page<TasksStatus>[]",
+                "AgentsStatus",
+                "TasksStatus",
+                "T[]",
+            ]
+        );
+        let task = file.models().next().unwrap();
+        assert_eq!(
+            task.properties[0].default,
+            Some(Value::QualifiedIdent(vec![
+                "TasksStatus".to_string(),
+                "open".to_string()
+            ]))
+        );
+    }
+}
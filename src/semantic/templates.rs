@@ -0,0 +1,553 @@
+//! Template Resolution
+//!
+//! Expand `op name is Template<Args>` references into concrete operations,
+//! and instantiate generic models for targets without generics.
+
+use super::inheritance::{lookup_model, resolve_properties};
+use super::{collect_services, ModelMap};
+use crate::ast::{Declaration, Model, Operation, OperationParam, Property, TypeRef, TypeSpecFile};
+use crate::diagnostic::Diagnostic;
+use crate::symbols::{Symbol, SymbolTable};
+use convert_case::{Case, Casing};
+use std::collections::{HashMap, HashSet};
+
+/// Type parameter name -> bound type argument
+pub type TypeBindings<'a> = HashMap<&'a str, &'a TypeRef>;
+
+/// Resolve an operation's `is` reference, written in namespace `scope`, into
+/// a concrete signature.
+///
+/// The template's parameters and return type are copied with its type
+/// parameters substituted; decorators on `op` take precedence over those
+/// on the template. Unknown or cyclic references are left unresolved, the
+/// checker reports both.
+pub fn resolve_operation(op: &Operation, scope: &str, table: &SymbolTable<'_>) -> Operation {
+    resolve_with_stack(op, scope, table, &mut Vec::new())
+}
+
+fn resolve_with_stack(
+    op: &Operation,
+    scope: &str,
+    table: &SymbolTable<'_>,
+    stack: &mut Vec<String>,
+) -> Operation {
+    let Some(is) = &op.is else {
+        return op.clone();
+    };
+
+    let args = match is {
+        TypeRef::Generic { args, .. } => args.as_slice(),
+        _ => &[][..],
+    };
+    let Some((symbol, template)) = referenced_operation(is, scope, table) else {
+        return op.clone();
+    };
+    if stack.contains(&symbol.qualified_name) {
+        return op.clone();
+    }
+
+    stack.push(symbol.qualified_name.clone());
+    let base = resolve_with_stack(template, &symbol.namespace, table, stack);
+    stack.pop();
+    if base.is.is_some() {
+        return op.clone();
+    }
+
+    let bindings: TypeBindings<'_> = base
+        .type_params
+        .iter()
+        .map(String::as_str)
+        .zip(args)
+        .collect();
+
+    let mut decorators = op.decorators.clone();
+    for decorator in &base.decorators {
+        if !decorators.iter().any(|d| d.name == decorator.name) {
+            decorators.push(decorator.clone());
+        }
+    }
+
+    Operation {
+        name: op.name.clone(),
+        decorators,
+        type_params: op.type_params.clone(),
+        is: None,
+        params: base
+            .params
+            .iter()
+            .map(|p| OperationParam {
+                type_ref: substitute_type(&p.type_ref, &bindings),
+                ..p.clone()
+            })
+            .collect(),
+        return_type: base
+            .return_type
+            .as_ref()
+            .map(|t| substitute_type(t, &bindings)),
+        span: op.span,
+    }
+}
+
+/// Check that the `is` references of free operations end in an operation
+/// without one: `op a is b; op b is a;` has no signature to copy.
+pub fn check_operation_cycles(table: &SymbolTable<'_>) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for symbol in table.symbols() {
+        let Declaration::Operation(op) = symbol.declaration else {
+            continue;
+        };
+        let mut visited: Vec<&str> = Vec::new();
+        let mut current = (symbol, op);
+        while let Some(next) = current
+            .1
+            .is
+            .as_ref()
+            .and_then(|is| referenced_operation(is, &current.0.namespace, table))
+        {
+            if next.0.qualified_name == symbol.qualified_name {
+                diagnostics.push(Diagnostic::error(
+                    format!("operation `{}` reuses itself through `is`", op.name),
+                    op.span,
+                ));
+                break;
+            }
+            if visited.contains(&next.0.qualified_name.as_str()) {
+                break;
+            }
+            visited.push(&next.0.qualified_name);
+            current = next;
+        }
+    }
+    diagnostics
+}
+
+/// The free operation an `is` reference written in `scope` refers to
+fn referenced_operation<'t, 'a>(
+    is: &TypeRef,
+    scope: &str,
+    table: &'t SymbolTable<'a>,
+) -> Option<(&'t Symbol<'a>, &'a Operation)> {
+    let symbol = table.resolve(is, scope)?.ok()?;
+    match symbol.declaration {
+        Declaration::Operation(op) => Some((symbol, op)),
+        _ => None,
+    }
+}
+
+/// Replace template parameters in `type_ref` with their bound arguments
+pub fn substitute_type(type_ref: &TypeRef, bindings: &TypeBindings<'_>) -> TypeRef {
+    match type_ref {
+        TypeRef::Named(name) => bindings
+            .get(name.as_str())
+            .map(|t| (*t).clone())
+            .unwrap_or_else(|| type_ref.clone()),
+        TypeRef::Array(inner) => TypeRef::Array(Box::new(substitute_type(inner, bindings))),
+        TypeRef::Generic { base, args } => TypeRef::Generic {
+            base: Box::new(substitute_type(base, bindings)),
+            args: args.iter().map(|a| substitute_type(a, bindings)).collect(),
+        },
+        TypeRef::Union(variants) => TypeRef::Union(
+            variants
+                .iter()
+                .map(|v| substitute_type(v, bindings))
+                .collect(),
+        ),
+        TypeRef::Intersection(parts) => {
+            TypeRef::Intersection(parts.iter().map(|p| substitute_type(p, bindings)).collect())
+        }
+        TypeRef::Optional(inner) => TypeRef::Optional(Box::new(substitute_type(inner, bindings))),
+        TypeRef::AnonymousModel(props) => TypeRef::AnonymousModel(
+            props
+                .iter()
+                .map(|p| Property {
+                    type_ref: substitute_type(&p.type_ref, bindings),
+                    ..p.clone()
+                })
+                .collect(),
+        ),
+        TypeRef::Builtin(_)
+        | TypeRef::Qualified(_)
+        | TypeRef::StringLiteral(_)
+        | TypeRef::IntLiteral(_) => type_ref.clone(),
+    }
+}
+
+/// Concrete models for every generic model instantiation used in the file.
+///
+/// `PaginatedResponse<Task>` becomes a model named `PaginatedResponseTask`
+/// (see [`instance_name`]) with `T` replaced by `Task` in all of its resolved
+/// properties. Instantiations used by other instances are included too.
+pub fn instantiate_models(file: &TypeSpecFile, models: &ModelMap<'_>) -> Vec<Model> {
+    instantiate(file, models).models
+}
+
+/// Check that generic models instantiate finitely many models: a template
+/// using a deeper instance of itself, e.g. `model Node<T> { child:
+/// Node<Node<T>>; }`, would need a new model for every depth.
+pub fn check_instantiations(file: &TypeSpecFile, models: &ModelMap<'_>) -> Vec<Diagnostic> {
+    instantiate(file, models).unbounded
+}
+
+/// Check that instances get distinct schema names, see [`instance_name`]:
+/// `Pair<A, BC>` and `Pair<AB, C>` are both `PairABC`, and `Page<TaskList>`
+/// is named like a declared `PageTaskList`.
+pub fn check_instance_names(file: &TypeSpecFile, models: &ModelMap<'_>) -> Vec<Diagnostic> {
+    let Instances {
+        models: instances,
+        refs,
+        mut clashes,
+        ..
+    } = instantiate(file, models);
+    for instance in &instances {
+        let mut declared = file
+            .models()
+            .filter(|m| m.type_params.is_empty())
+            .map(|m| m.name.as_str())
+            .chain(file.enums().map(|e| e.name.as_str()));
+        if let Some(name) = declared.find(|&n| n == instance.name) {
+            clashes.push(Diagnostic::error(
+                format!(
+                    "`{}` and `{}` would both be generated as `{}`",
+                    refs[&instance.name], name, instance.name
+                ),
+                instance.span,
+            ));
+        }
+    }
+    clashes
+}
+
+/// Generic arguments nest at most this deep in an instance
+const MAX_INSTANCE_DEPTH: usize = 16;
+
+/// Generic model instances of a file, see [`instantiate_models`]
+struct Instances {
+    models: Vec<Model>,
+    /// The type each instance was first instantiated from, by instance name
+    refs: HashMap<String, TypeRef>,
+    /// Templates instantiating ever deeper versions of themselves
+    unbounded: Vec<Diagnostic>,
+    /// Different instantiations getting the same name
+    clashes: Vec<Diagnostic>,
+}
+
+fn instantiate(file: &TypeSpecFile, models: &ModelMap<'_>) -> Instances {
+    let mut pending = Vec::new();
+    for model in file.models().filter(|m| m.type_params.is_empty()) {
+        for prop in resolve_properties(model, models) {
+            collect_generic_refs(&prop.type_ref, &mut pending);
+        }
+        if let Some(base) = &model.extends {
+            collect_generic_refs(base, &mut pending);
+        }
+    }
+    for iface in &collect_services(file) {
+        for op in &iface.operations {
+            for param in &op.params {
+                collect_generic_refs(&param.type_ref, &mut pending);
+            }
+            if let Some(ret) = &op.return_type {
+                collect_generic_refs(ret, &mut pending);
+            }
+        }
+    }
+
+    let mut refs: HashMap<String, TypeRef> = HashMap::new();
+    let mut instances = Vec::new();
+    let mut unbounded = HashSet::new();
+    let mut diagnostics = Vec::new();
+    let mut clashes = Vec::new();
+    while let Some(type_ref) = pending.pop() {
+        let TypeRef::Generic { args, .. } = &type_ref else {
+            continue;
+        };
+        let Some(template) = lookup_model(&type_ref, models).filter(|m| !m.type_params.is_empty())
+        else {
+            continue;
+        };
+        if generic_depth(&type_ref) > MAX_INSTANCE_DEPTH {
+            if unbounded.insert(template.name.as_str()) {
+                diagnostics.push(Diagnostic::error(
+                    format!(
+                        "generic model `{}` instantiates ever deeper versions of itself",
+                        template.name
+                    ),
+                    template.span,
+                ));
+            }
+            continue;
+        }
+
+        let name = instance_name(&type_ref);
+        if let Some(first) = refs.get(&name) {
+            if unqualified(first) != unqualified(&type_ref) {
+                clashes.push(Diagnostic::error(
+                    format!(
+                        "`{}` and `{}` would both be generated as `{}`",
+                        first, type_ref, name
+                    ),
+                    template.span,
+                ));
+            }
+            continue;
+        }
+        refs.insert(name.clone(), type_ref.clone());
+
+        let bindings: TypeBindings<'_> = template
+            .type_params
+            .iter()
+            .map(String::as_str)
+            .zip(args)
+            .collect();
+        let properties: Vec<_> = resolve_properties(template, models)
+            .into_iter()
+            .map(|p| Property {
+                type_ref: substitute_type(&p.type_ref, &bindings),
+                ..p.into_owned()
+            })
+            .collect();
+        for prop in &properties {
+            collect_generic_refs(&prop.type_ref, &mut pending);
+        }
+
+        instances.push(Model {
+            name,
+            decorators: template.decorators.clone(),
+            type_params: Vec::new(),
+            extends: None,
+            is: None,
+            properties,
+            spread_refs: Vec::new(),
+            span: template.span,
+        });
+    }
+
+    instances.sort_by(|a, b| a.name.cmp(&b.name));
+    clashes.dedup_by(|a, b| a.message == b.message);
+    Instances {
+        models: instances,
+        refs,
+        unbounded: diagnostics,
+        clashes,
+    }
+}
+
+/// A type with qualified references replaced by their declared name, which
+/// is all that [`instance_name`] looks at
+fn unqualified(type_ref: &TypeRef) -> TypeRef {
+    match type_ref {
+        TypeRef::Qualified(parts) => TypeRef::Named(parts.last().cloned().unwrap_or_default()),
+        TypeRef::Array(inner) => TypeRef::Array(Box::new(unqualified(inner))),
+        TypeRef::Optional(inner) => TypeRef::Optional(Box::new(unqualified(inner))),
+        TypeRef::Generic { base, args } => TypeRef::Generic {
+            base: Box::new(unqualified(base)),
+            args: args.iter().map(unqualified).collect(),
+        },
+        TypeRef::Union(types) => TypeRef::Union(types.iter().map(unqualified).collect()),
+        TypeRef::Intersection(types) => {
+            TypeRef::Intersection(types.iter().map(unqualified).collect())
+        }
+        other => other.clone(),
+    }
+}
+
+/// How deep generic arguments nest in a type, e.g. 2 for `Page<Page<Task>>`
+fn generic_depth(type_ref: &TypeRef) -> usize {
+    match type_ref {
+        TypeRef::Generic { args, .. } => 1 + args.iter().map(generic_depth).max().unwrap_or(0),
+        TypeRef::Array(inner) | TypeRef::Optional(inner) => generic_depth(inner),
+        TypeRef::Union(types) | TypeRef::Intersection(types) => {
+            types.iter().map(generic_depth).max().unwrap_or(0)
+        }
+        _ => 0,
+    }
+}
+
+/// Name of the concrete model for a type: `PaginatedResponse<Task>` is
+/// `PaginatedResponseTask`, `Page<string[]>` is `PageStringArray`.
+pub fn instance_name(type_ref: &TypeRef) -> String {
+    match type_ref {
+        TypeRef::Builtin(name) | TypeRef::Named(name) => name.to_case(Case::Pascal),
+        TypeRef::Qualified(parts) => parts
+            .last()
+            .map(|p| p.to_case(Case::Pascal))
+            .unwrap_or_default(),
+        TypeRef::Array(inner) => format!("{}Array", instance_name(inner)),
+        TypeRef::Generic { base, args } => {
+            let mut name = instance_name(base);
+            for arg in args {
+                name.push_str(&instance_name(arg));
+            }
+            name
+        }
+        TypeRef::Union(variants) => variants
+            .iter()
+            .map(instance_name)
+            .collect::<Vec<_>>()
+            .join("Or"),
+        TypeRef::Intersection(parts) => parts
+            .iter()
+            .map(instance_name)
+            .collect::<Vec<_>>()
+            .join("And"),
+        TypeRef::Optional(inner) => instance_name(inner),
+        TypeRef::StringLiteral(s) => s.to_case(Case::Pascal),
+        TypeRef::IntLiteral(n) => n.to_string(),
+        TypeRef::AnonymousModel(_) => "Object".to_string(),
+    }
+}
+
+/// Collect generic instantiations (other than `Record`) in a type
+fn collect_generic_refs(type_ref: &TypeRef, out: &mut Vec<TypeRef>) {
+    match type_ref {
+        TypeRef::Generic { base, args } => {
+            if base.base_name() != Some("Record") {
+                out.push(type_ref.clone());
+            }
+            for arg in args {
+                collect_generic_refs(arg, out);
+            }
+        }
+        TypeRef::Array(inner) | TypeRef::Optional(inner) => collect_generic_refs(inner, out),
+        TypeRef::Union(types) | TypeRef::Intersection(types) => {
+            for t in types {
+                collect_generic_refs(t, out);
+            }
+        }
+        TypeRef::AnonymousModel(props) => {
+            for prop in props {
+                collect_generic_refs(&prop.type_ref, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    #[test]
+    fn test_substitute_nested_type_params() {
+        let file = parse("model M { items: Page<T>[] | T; }").unwrap();
+        let prop = &file.models().next().unwrap().properties[0];
+        let user = TypeRef::Named("User".to_string());
+        let bindings: TypeBindings<'_> = [("T", &user)].into_iter().collect();
+
+        let TypeRef::Union(variants) = substitute_type(&prop.type_ref, &bindings) else {
+            panic!("Expected union");
+        };
+        let TypeRef::Array(inner) = &variants[0] else {
+            panic!("Expected array");
+        };
+        assert!(
+            matches!(inner.as_ref(), TypeRef::Generic { args, .. } if matches!(&args[0], TypeRef::Named(n) if n == "User"))
+        );
+        assert!(matches!(&variants[1], TypeRef::Named(n) if n == "User"));
+    }
+
+    #[test]
+    fn test_instance_name() {
+        let file =
+            parse("model M { a: Page<Task>; b: Page<string[]>; c: Pair<int32, Tasks.Task>; }")
+                .unwrap();
+        let names: Vec<_> = file
+            .models()
+            .next()
+            .unwrap()
+            .properties
+            .iter()
+            .map(|p| instance_name(&p.type_ref))
+            .collect();
+        assert_eq!(names, vec!["PageTask", "PageStringArray", "PairInt32Task"]);
+    }
+
+    #[test]
+    fn test_cyclic_reference_left_unresolved() {
+        let file = parse("op a is b; op b is a;").unwrap();
+        let table = SymbolTable::build(&file);
+        let a = file.operations().next().unwrap();
+        let resolved = resolve_operation(a, "", &table);
+        assert!(resolved.is.is_some());
+
+        let messages: Vec<_> = check_operation_cycles(&table)
+            .into_iter()
+            .map(|d| d.message)
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "operation `a` reuses itself through `is`",
+                "operation `b` reuses itself through `is`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_operation_from_its_namespace() {
+        let source = r#"
+            namespace Tasks { op Read<T>(@path id: string): T; op get is Read<string>; }
+            namespace Agents { op Read<T>(@query name: string): T; op get is Read<int32>; }
+        "#;
+        let file = parse(source).unwrap();
+        let table = SymbolTable::build(&file);
+
+        let params: Vec<_> = file
+            .operations()
+            .filter(|op| op.name == "get")
+            .zip(["Tasks", "Agents"])
+            .map(|(op, scope)| resolve_operation(op, scope, &table).params[0].name.clone())
+            .collect();
+        assert_eq!(params, vec!["id", "name"]);
+    }
+
+    #[test]
+    fn test_instance_name_clashes_are_reported() {
+        let source = r#"
+            model Page<T> { items: T[]; }
+            model Pair<A, B> { a: A; b: B; }
+            model TaskList {}
+            model PageTaskList {}
+            model Task {} model ListItem {} model Item {}
+            model Lists {
+                tasks: Page<TaskList>;
+                same: Page<TaskList>;
+                first: Pair<Task, ListItem>;
+                second: Pair<TaskList, Item>;
+            }
+        "#;
+        let file = parse(source).unwrap();
+        let models = crate::semantic::build_model_map(&file);
+
+        let mut messages: Vec<_> = check_instance_names(&file, &models)
+            .into_iter()
+            .map(|d| d.message)
+            .collect();
+        messages.sort();
+        assert_eq!(
+            messages,
+            vec![
+                "`Page<TaskList>` and `PageTaskList` would both be generated as `PageTaskList`",
+                "`Pair<TaskList, Item>` and `Pair<Task, ListItem>` would both be generated as `PairTaskListItem`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_self_growing_generic_is_reported() {
+        let file = parse(
+            "model Node<T> { value: T; child?: Node<Node<T>>; } model Tree { root: Node<string>; }",
+        )
+        .unwrap();
+        let models = crate::semantic::build_model_map(&file);
+
+        let diagnostics = check_instantiations(&file, &models);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "generic model `Node` instantiates ever deeper versions of itself"
+        );
+        assert_eq!(instantiate_models(&file, &models).len(), MAX_INSTANCE_DEPTH);
+    }
+}
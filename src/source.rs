@@ -0,0 +1,199 @@
+//! Source Providers
+//!
+//! Where [`Program`](crate::program::Program) reads TypeSpec files from: the
+//! file system, an in-memory map, or an in-memory overlay on top of another
+//! provider (for unsaved editor buffers).
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// Reads TypeSpec sources by path.
+pub trait SourceProvider {
+    /// The path that identifies `path`'s file, so that the same file reached
+    /// through different relative paths is loaded once. Fails with
+    /// [`io::ErrorKind::NotFound`] if there is no such file.
+    fn resolve(&self, path: &Path) -> io::Result<PathBuf>;
+
+    /// Read the file at a path returned by [`SourceProvider::resolve`].
+    fn read(&self, path: &Path) -> io::Result<String>;
+}
+
+/// Sources on disk.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FileSystem;
+
+impl SourceProvider for FileSystem {
+    fn resolve(&self, path: &Path) -> io::Result<PathBuf> {
+        path.canonicalize()
+    }
+
+    fn read(&self, path: &Path) -> io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+}
+
+/// Sources held in memory, keyed by path.
+///
+/// Paths are normalized lexically: `./a/../b.tsp` and `b.tsp` are the same
+/// file. Relative and absolute paths are never the same file.
+#[derive(Debug, Clone, Default)]
+pub struct InMemory {
+    files: HashMap<PathBuf, String>,
+}
+
+impl InMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a file, replacing any previous source at the same path.
+    pub fn insert(&mut self, path: impl AsRef<Path>, source: impl Into<String>) {
+        self.files.insert(normalize(path.as_ref()), source.into());
+    }
+
+    /// Add a file, builder style.
+    pub fn with_file(mut self, path: impl AsRef<Path>, source: impl Into<String>) -> Self {
+        self.insert(path, source);
+        self
+    }
+
+    /// Remove a file, returning its source.
+    pub fn remove(&mut self, path: impl AsRef<Path>) -> Option<String> {
+        self.files.remove(&normalize(path.as_ref()))
+    }
+
+    fn get(&self, path: &Path) -> Option<&String> {
+        self.files.get(&normalize(path))
+    }
+}
+
+impl SourceProvider for InMemory {
+    fn resolve(&self, path: &Path) -> io::Result<PathBuf> {
+        let path = normalize(path);
+        if self.files.contains_key(&path) {
+            Ok(path)
+        } else {
+            Err(not_found(&path))
+        }
+    }
+
+    fn read(&self, path: &Path) -> io::Result<String> {
+        self.get(path).cloned().ok_or_else(|| not_found(path))
+    }
+}
+
+/// In-memory files shadowing those of another provider.
+///
+/// Files in the overlay take precedence over files at the same path in the
+/// base, and may also be files the base does not have at all. Give overlay
+/// paths in the form the base resolves them to: absolute, canonical paths
+/// for [`FileSystem`].
+#[derive(Debug, Clone, Default)]
+pub struct Overlay<P> {
+    base: P,
+    overlay: InMemory,
+}
+
+impl<P: SourceProvider> Overlay<P> {
+    pub fn new(base: P) -> Self {
+        Self {
+            base,
+            overlay: InMemory::new(),
+        }
+    }
+
+    /// Shadow the file at `path`.
+    pub fn insert(&mut self, path: impl AsRef<Path>, source: impl Into<String>) {
+        self.overlay.insert(path, source);
+    }
+
+    /// Shadow the file at `path`, builder style.
+    pub fn with_file(mut self, path: impl AsRef<Path>, source: impl Into<String>) -> Self {
+        self.insert(path, source);
+        self
+    }
+
+    /// Stop shadowing the file at `path`, returning its overlay source.
+    pub fn remove(&mut self, path: impl AsRef<Path>) -> Option<String> {
+        self.overlay.remove(path)
+    }
+}
+
+impl<P: SourceProvider> SourceProvider for Overlay<P> {
+    fn resolve(&self, path: &Path) -> io::Result<PathBuf> {
+        match self.base.resolve(path) {
+            Ok(resolved) => Ok(resolved),
+            Err(err) => self.overlay.resolve(path).map_err(|_| err),
+        }
+    }
+
+    fn read(&self, path: &Path) -> io::Result<String> {
+        match self.overlay.get(path) {
+            Some(source) => Ok(source.clone()),
+            None => self.base.read(path),
+        }
+    }
+}
+
+/// Remove `.` components and resolve `..` against the preceding component
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                Some(Component::RootDir) => {}
+                _ => normalized.push(".."),
+            },
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{} not found", path.display()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            normalize(Path::new("./a/../b/./c.tsp")),
+            Path::new("b/c.tsp")
+        );
+        assert_eq!(normalize(Path::new("/x/y/../z.tsp")), Path::new("/x/z.tsp"));
+        assert_eq!(
+            normalize(Path::new("../../z.tsp")),
+            Path::new("../../z.tsp")
+        );
+    }
+
+    #[test]
+    fn test_overlay_shadows_base() {
+        let base = InMemory::new()
+            .with_file("a.tsp", "model A {}")
+            .with_file("b.tsp", "model B {}");
+        let overlay = Overlay::new(base)
+            .with_file("a.tsp", "model Edited {}")
+            .with_file("new.tsp", "model New {}");
+
+        assert_eq!(overlay.read(Path::new("a.tsp")).unwrap(), "model Edited {}");
+        assert_eq!(overlay.read(Path::new("b.tsp")).unwrap(), "model B {}");
+        assert_eq!(
+            overlay.resolve(Path::new("./new.tsp")).unwrap(),
+            Path::new("new.tsp")
+        );
+        assert!(overlay.resolve(Path::new("missing.tsp")).is_err());
+    }
+}
@@ -0,0 +1,434 @@
+//! Symbol Table
+//!
+//! Fully qualified names for every declaration, and namespace-aware
+//! resolution of type references.
+
+use crate::ast::{Declaration, Operation, Span, TypeRef, TypeSpecFile, Using};
+use crate::diagnostic::Diagnostic;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Types provided by the TypeSpec standard library
+const LIBRARY_TYPES: &[&str] = &["Record"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Model,
+    Enum,
+    Union,
+    Interface,
+    Operation,
+    Scalar,
+    Alias,
+}
+
+/// A named declaration and the namespace it is declared in.
+#[derive(Debug, Clone)]
+pub struct Symbol<'a> {
+    /// Fully qualified name, e.g. `AdiFamily.Tasks.Task`
+    pub qualified_name: String,
+    /// Fully qualified name of the enclosing namespace, empty at the root
+    pub namespace: String,
+    pub kind: SymbolKind,
+    pub declaration: &'a Declaration,
+}
+
+impl Symbol<'_> {
+    /// Declared (unqualified) name.
+    pub fn name(&self) -> &str {
+        self.qualified_name
+            .rsplit('.')
+            .next()
+            .unwrap_or(&self.qualified_name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ResolveError {
+    #[error("unknown type `{0}`")]
+    Unknown(String),
+
+    #[error("ambiguous reference `{name}`: could be `{}`", .candidates.join("` or `"))]
+    Ambiguous {
+        name: String,
+        candidates: Vec<String>,
+    },
+}
+
+/// Declarations of a file by fully qualified name, with the `using`
+/// statements in effect in each namespace.
+#[derive(Debug, Default)]
+pub struct SymbolTable<'a> {
+    symbols: Vec<Symbol<'a>>,
+    by_name: HashMap<String, Vec<usize>>,
+    usings: HashMap<String, Vec<String>>,
+}
+
+impl<'a> SymbolTable<'a> {
+    /// Collect the declarations of `file`, including those in namespace blocks.
+    pub fn build(file: &'a TypeSpecFile) -> Self {
+        let mut table = Self::default();
+        let root = file.namespace.clone().unwrap_or_default();
+        table.add_usings(&root, &file.usings);
+        table.collect(&file.declarations, &root);
+        table
+    }
+
+    fn collect(&mut self, declarations: &'a [Declaration], namespace: &str) {
+        for decl in declarations {
+            let (name, kind) = match decl {
+                Declaration::Model(m) => (&m.name, SymbolKind::Model),
+                Declaration::Enum(e) => (&e.name, SymbolKind::Enum),
+                Declaration::Union(u) => (&u.name, SymbolKind::Union),
+                Declaration::Interface(i) => (&i.name, SymbolKind::Interface),
+                Declaration::Operation(o) => (&o.name, SymbolKind::Operation),
+                Declaration::Scalar(s) => (&s.name, SymbolKind::Scalar),
+                Declaration::Alias(a) => (&a.name, SymbolKind::Alias),
+                Declaration::Namespace(ns) => {
+                    let inner = qualify(namespace, &ns.name);
+                    self.add_usings(&inner, &ns.usings);
+                    self.collect(&ns.declarations, &inner);
+                    continue;
+                }
+            };
+            let qualified_name = qualify(namespace, name);
+            self.by_name
+                .entry(qualified_name.clone())
+                .or_default()
+                .push(self.symbols.len());
+            self.symbols.push(Symbol {
+                qualified_name,
+                namespace: namespace.to_string(),
+                kind,
+                declaration: decl,
+            });
+        }
+    }
+
+    fn add_usings(&mut self, namespace: &str, usings: &[Using]) {
+        self.usings
+            .entry(namespace.to_string())
+            .or_default()
+            .extend(usings.iter().map(|u| u.namespace.clone()));
+    }
+
+    /// All symbols, in declaration order.
+    pub fn symbols(&self) -> impl Iterator<Item = &Symbol<'a>> {
+        self.symbols.iter()
+    }
+
+    /// Symbols declared with the given fully qualified name. More than one
+    /// means the name is declared twice.
+    pub fn get(&self, qualified_name: &str) -> impl Iterator<Item = &Symbol<'a>> {
+        self.by_name
+            .get(qualified_name)
+            .into_iter()
+            .flatten()
+            .map(|&i| &self.symbols[i])
+    }
+
+    /// Resolve a dotted name as written in namespace `scope`.
+    ///
+    /// The scope and each enclosing namespace are searched first, innermost
+    /// wins. Otherwise the name is looked up in the namespaces brought in
+    /// with `using`, where more than one match is ambiguous. A reference to
+    /// an enum or union member (`TaskStatus.pending`) resolves to its parent.
+    pub fn resolve_name(&self, name: &str, scope: &str) -> Result<&Symbol<'a>, ResolveError> {
+        if let Some(symbol) = self.lookup(name, scope)? {
+            return Ok(symbol);
+        }
+        if let Some((parent, _member)) = name.rsplit_once('.') {
+            if let Some(symbol) = self.lookup(parent, scope)? {
+                if matches!(symbol.kind, SymbolKind::Enum | SymbolKind::Union) {
+                    return Ok(symbol);
+                }
+            }
+        }
+        Err(ResolveError::Unknown(name.to_string()))
+    }
+
+    fn lookup(&self, name: &str, scope: &str) -> Result<Option<&Symbol<'a>>, ResolveError> {
+        for namespace in scope_chain(scope) {
+            if let Some(symbol) = self.get(&qualify(namespace, name)).next() {
+                return Ok(Some(symbol));
+            }
+        }
+
+        let mut candidates: Vec<&Symbol<'a>> = Vec::new();
+        for namespace in scope_chain(scope) {
+            for using in self.usings.get(namespace).into_iter().flatten() {
+                if let Some(symbol) = self.get(&qualify(using, name)).next() {
+                    if !candidates
+                        .iter()
+                        .any(|c| c.qualified_name == symbol.qualified_name)
+                    {
+                        candidates.push(symbol);
+                    }
+                }
+            }
+        }
+        match candidates.len() {
+            0 => Ok(None),
+            1 => Ok(Some(candidates[0])),
+            _ => {
+                let mut candidates: Vec<_> = candidates
+                    .into_iter()
+                    .map(|c| c.qualified_name.clone())
+                    .collect();
+                candidates.sort();
+                Err(ResolveError::Ambiguous {
+                    name: name.to_string(),
+                    candidates,
+                })
+            }
+        }
+    }
+
+    /// Resolve the declaration a type refers to, ignoring template arguments.
+    ///
+    /// Returns `None` for types that are not references to a declaration:
+    /// builtins, literals, arrays, unions and standard library types.
+    pub fn resolve(
+        &self,
+        type_ref: &TypeRef,
+        scope: &str,
+    ) -> Option<Result<&Symbol<'a>, ResolveError>> {
+        let name = match type_ref {
+            TypeRef::Named(name) => name.clone(),
+            TypeRef::Qualified(parts) => parts.join("."),
+            TypeRef::Generic { base, .. } => return self.resolve(base, scope),
+            _ => return None,
+        };
+        let resolved = self.resolve_name(&name, scope);
+        if resolved.is_err() && LIBRARY_TYPES.contains(&name.as_str()) {
+            return None;
+        }
+        Some(resolved)
+    }
+
+    /// Resolve every type reference in every declaration, reporting the ones
+    /// that are unknown or ambiguous.
+    pub fn check_references(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        self.visit_references(|reference| {
+            if let Some(Err(err)) = self.resolve(reference.type_ref, reference.scope) {
+                diagnostics.push(Diagnostic::error(err.to_string(), reference.span));
+            }
+        });
+        diagnostics
+    }
+
+    /// Call `visit` for every reference to a declaration: each named,
+    /// qualified or generic type in every declaration, in order. The base of
+    /// a generic type is not visited on its own, and references to template
+    /// parameters are skipped.
+    pub fn visit_references(&self, mut visit: impl FnMut(Reference<'_>)) {
+        for symbol in &self.symbols {
+            let scope = symbol.namespace.as_str();
+            let mut walk = |type_ref: &TypeRef, params: &[String], span: Span| {
+                walk_type(type_ref, params, span, &mut |type_ref, span| {
+                    visit(Reference {
+                        type_ref,
+                        scope,
+                        span,
+                    })
+                })
+            };
+            match symbol.declaration {
+                Declaration::Model(model) => {
+                    let params = &model.type_params;
+                    for base in model.extends.iter().chain(&model.is) {
+                        walk(base, params, model.span);
+                    }
+                    for spread in &model.spread_refs {
+                        walk(spread, params, model.span);
+                    }
+                    for prop in &model.properties {
+                        walk(&prop.type_ref, params, prop.span);
+                    }
+                }
+                Declaration::Union(union) => {
+                    for variant in &union.variants {
+                        walk(&variant.type_ref, &[], variant.span);
+                    }
+                }
+                Declaration::Interface(iface) => {
+                    for op in &iface.operations {
+                        walk_operation(op, &mut walk);
+                    }
+                }
+                Declaration::Operation(op) => walk_operation(op, &mut walk),
+                Declaration::Alias(alias) => walk(&alias.type_ref, &[], alias.span),
+                Declaration::Enum(_) | Declaration::Scalar(_) | Declaration::Namespace(_) => {}
+            }
+        }
+    }
+}
+
+/// A reference to a declaration, see [`SymbolTable::visit_references`].
+#[derive(Debug, Clone, Copy)]
+pub struct Reference<'r> {
+    pub type_ref: &'r TypeRef,
+    /// Namespace the reference is written in
+    pub scope: &'r str,
+    /// Span of the property, parameter or declaration holding the reference
+    pub span: Span,
+}
+
+fn walk_operation(op: &Operation, walk: &mut impl FnMut(&TypeRef, &[String], Span)) {
+    let params = &op.type_params;
+    if let Some(is) = &op.is {
+        walk(is, params, op.span);
+    }
+    for param in &op.params {
+        walk(&param.type_ref, params, param.span);
+    }
+    if let Some(ret) = &op.return_type {
+        walk(ret, params, op.span);
+    }
+}
+
+fn walk_type(
+    type_ref: &TypeRef,
+    params: &[String],
+    span: Span,
+    visit: &mut impl FnMut(&TypeRef, Span),
+) {
+    match type_ref {
+        TypeRef::Named(name) if params.contains(name) => {}
+        TypeRef::Named(_) | TypeRef::Qualified(_) => visit(type_ref, span),
+        TypeRef::Generic { args, .. } => {
+            visit(type_ref, span);
+            for arg in args {
+                walk_type(arg, params, span, visit);
+            }
+        }
+        TypeRef::Array(inner) | TypeRef::Optional(inner) => walk_type(inner, params, span, visit),
+        TypeRef::Union(types) | TypeRef::Intersection(types) => {
+            for t in types {
+                walk_type(t, params, span, visit);
+            }
+        }
+        TypeRef::AnonymousModel(props) => {
+            for prop in props {
+                walk_type(&prop.type_ref, params, prop.span, visit);
+            }
+        }
+        TypeRef::Builtin(_) | TypeRef::StringLiteral(_) | TypeRef::IntLiteral(_) => {}
+    }
+}
+
+/// `namespace.name`, or just `name` at the root
+fn qualify(namespace: &str, name: &str) -> String {
+    if namespace.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", namespace, name)
+    }
+}
+
+/// `scope` followed by each enclosing namespace, ending with the root
+fn scope_chain(scope: &str) -> impl Iterator<Item = &str> {
+    let mut next = Some(scope);
+    std::iter::from_fn(move || {
+        let current = next?;
+        next = match current.rsplit_once('.') {
+            Some((parent, _)) => Some(parent),
+            None if current.is_empty() => None,
+            None => Some(""),
+        };
+        Some(current)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    const SOURCE: &str = r#"
+        namespace AdiFamily;
+
+        model Shared {}
+
+        namespace Tasks {
+            model Status {}
+            model Task { status: Status; shared: Shared; }
+        }
+
+        namespace Agents {
+            model Status {}
+            model Agent { status: Agents.Status; task: Tasks.Task; }
+        }
+    "#;
+
+    #[test]
+    fn test_qualified_names() {
+        let file = parse(SOURCE).unwrap();
+        let table = SymbolTable::build(&file);
+        let names: Vec<_> = table.symbols().map(|s| s.qualified_name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "AdiFamily.Shared",
+                "AdiFamily.Tasks.Status",
+                "AdiFamily.Tasks.Task",
+                "AdiFamily.Agents.Status",
+                "AdiFamily.Agents.Agent",
+            ]
+        );
+        assert!(table.check_references().is_empty());
+    }
+
+    #[test]
+    fn test_resolve_from_scope() {
+        let file = parse(SOURCE).unwrap();
+        let table = SymbolTable::build(&file);
+
+        let status = table.resolve_name("Status", "AdiFamily.Agents").unwrap();
+        assert_eq!(status.qualified_name, "AdiFamily.Agents.Status");
+        let status = table
+            .resolve_name("Tasks.Status", "AdiFamily.Agents")
+            .unwrap();
+        assert_eq!(status.qualified_name, "AdiFamily.Tasks.Status");
+        assert_eq!(
+            table.resolve_name("Status", "AdiFamily").unwrap_err(),
+            ResolveError::Unknown("Status".to_string())
+        );
+    }
+
+    #[test]
+    fn test_ambiguous_and_unknown_references() {
+        let source = r#"
+            namespace Tasks { model Status {} }
+            namespace Agents { model Status {} }
+            namespace Api {
+                using Tasks;
+                using Agents;
+                model Run { status: Status; owner: Ownr; }
+            }
+        "#;
+        let file = parse(source).unwrap();
+        let table = SymbolTable::build(&file);
+
+        let messages: Vec<_> = table
+            .check_references()
+            .into_iter()
+            .map(|d| d.message)
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "ambiguous reference `Status`: could be `Agents.Status` or `Tasks.Status`",
+                "unknown type `Ownr`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scope_chain() {
+        let chain: Vec<_> = scope_chain("A.B").collect();
+        assert_eq!(chain, vec!["A.B", "A", ""]);
+        assert_eq!(scope_chain("").collect::<Vec<_>>(), vec![""]);
+    }
+}
@@ -0,0 +1,637 @@
+//! OpenAPI spec generation tests
+
+use serde_json::Value;
+use tempfile::TempDir;
+use typespec_api::{
+    codegen::{Generator, Language, Side},
+    parse,
+};
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn generate_spec(source: &str) -> Value {
+    let file = parse(source).expect("Failed to parse TypeSpec");
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let generator = Generator::new(&file, temp_dir.path(), "Test API");
+    generator
+        .generate(Language::OpenApi, Side::Both)
+        .expect("Failed to generate");
+    let json = std::fs::read_to_string(temp_dir.path().join("openapi.json"))
+        .expect("Failed to read openapi.json");
+    serde_json::from_str(&json).expect("Invalid JSON")
+}
+
+// ============================================================================
+// Schema Generation Tests
+// ============================================================================
+
+#[test]
+fn test_generate_derived_model_with_all_of() {
+    let source = r#"
+        model User {
+            id: string;
+            name?: string;
+        }
+
+        model Admin extends User {
+            permissions: string[];
+        }
+    "#;
+
+    let spec = generate_spec(source);
+    let admin = &spec["components"]["schemas"]["Admin"];
+
+    assert_eq!(admin["allOf"][0]["$ref"], "#/components/schemas/User");
+    let own = &admin["allOf"][1];
+    assert!(own["properties"]["permissions"].is_object());
+    assert!(own["properties"]["id"].is_null());
+    assert_eq!(own["required"][0], "permissions");
+}
+
+#[test]
+fn test_generate_instantiated_generic_schemas() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        model Envelope<T> {
+            data: T;
+        }
+
+        model PaginatedResponse<T> {
+            items: T[];
+            total: int32;
+        }
+
+        model TaskList {
+            page: PaginatedResponse<Envelope<Task>>;
+        }
+
+        @route("/tasks")
+        interface TaskService {
+            @get list(): PaginatedResponse<Task>;
+        }
+    "#;
+
+    let spec = generate_spec(source);
+    let schemas = &spec["components"]["schemas"];
+
+    // Templates themselves have no schema
+    assert!(schemas["PaginatedResponse"].is_null());
+    assert!(schemas["Envelope"].is_null());
+
+    assert_eq!(
+        schemas["PaginatedResponseTask"]["properties"]["items"]["items"]["$ref"],
+        "#/components/schemas/Task"
+    );
+    assert_eq!(
+        schemas["TaskList"]["properties"]["page"]["$ref"],
+        "#/components/schemas/PaginatedResponseEnvelopeTask"
+    );
+    // Instantiations nested in other instances are generated too
+    assert_eq!(
+        schemas["PaginatedResponseEnvelopeTask"]["properties"]["items"]["items"]["$ref"],
+        "#/components/schemas/EnvelopeTask"
+    );
+    assert_eq!(
+        schemas["EnvelopeTask"]["properties"]["data"]["$ref"],
+        "#/components/schemas/Task"
+    );
+
+    let response = &spec["paths"]["/tasks"]["get"]["responses"]["200"];
+    assert_eq!(
+        response["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/PaginatedResponseTask"
+    );
+}
+
+#[test]
+fn test_instance_named_like_a_declared_model_is_rejected() {
+    let source = r#"
+        model TaskList { id: string; }
+        model PageTaskList { cursor: string; }
+        model Page<T> { items: T[]; }
+
+        @route("/tasks")
+        interface TaskService {
+            @get list(): Page<TaskList>;
+        }
+    "#;
+
+    let file = parse(source).expect("Failed to parse TypeSpec");
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let result =
+        Generator::new(&file, temp_dir.path(), "Test API").generate(Language::OpenApi, Side::Both);
+
+    let err = result.expect_err("Expected a name collision").to_string();
+    assert_eq!(
+        err,
+        "`Page<TaskList>` and `PageTaskList` would both be generated as `PageTaskList`"
+    );
+}
+
+#[test]
+fn test_interface_named_like_the_free_operations_service_is_rejected() {
+    let source = r#"
+        @route("/health") op health(): void;
+
+        interface Default {
+            @route("/ping") op ping(): void;
+        }
+    "#;
+
+    let file = parse(source).expect("Failed to parse TypeSpec");
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let result =
+        Generator::new(&file, temp_dir.path(), "Test API").generate(Language::OpenApi, Side::Both);
+
+    let err = result.expect_err("Expected a name collision").to_string();
+    assert_eq!(
+        err,
+        "`Default` and the free operations would both be generated as the `Default` service"
+    );
+}
+
+#[test]
+fn test_generate_descriptions_from_doc_comments() {
+    let source = r#"
+        /**
+         * A unit of work.
+         *
+         * Tasks are assigned to agents.
+         */
+        model Task {
+            /** Unique identifier */
+            id: string;
+        }
+
+        /** Lifecycle of a task */
+        enum TaskStatus {
+            /** Not started yet */
+            pending,
+            done,
+        }
+
+        interface Tasks {
+            /** Fetch a task by id */
+            @get
+            @route("/tasks/{id}")
+            get(@path id: string): Task;
+        }
+    "#;
+
+    let spec = generate_spec(source);
+    let schemas = &spec["components"]["schemas"];
+
+    assert_eq!(
+        schemas["Task"]["description"],
+        "A unit of work.\n\nTasks are assigned to agents."
+    );
+    assert_eq!(
+        schemas["Task"]["properties"]["id"]["description"],
+        "Unique identifier"
+    );
+    assert_eq!(schemas["TaskStatus"]["description"], "Lifecycle of a task");
+    let get = &spec["paths"]["/tasks/{id}"]["get"];
+    assert_eq!(get["summary"], "Fetch a task by id");
+}
+
+#[test]
+fn test_yaml_escapes_multiline_descriptions() {
+    let source = r#"
+        /**
+         * A unit of work.
+         *
+         * Tasks are "assigned" to agents.
+         */
+        model Task { id: string; }
+    "#;
+
+    let file = parse(source).expect("Failed to parse TypeSpec");
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    Generator::new(&file, temp_dir.path(), "Test API")
+        .generate(Language::OpenApi, Side::Both)
+        .expect("Failed to generate");
+    let yaml = std::fs::read_to_string(temp_dir.path().join("openapi.yaml"))
+        .expect("Failed to read openapi.yaml");
+
+    assert!(yaml.contains(r#"description: "A unit of work.\n\nTasks are \"assigned\" to agents.""#));
+}
+
+#[test]
+fn test_generate_validation_keywords() {
+    let source = r#"
+        @format("email")
+        scalar email extends string;
+
+        model CreateUserRequest {
+            @minLength(1)
+            @maxLength(100)
+            @pattern("^[A-Za-z ]+$")
+            name: string;
+            contact: email;
+            @minValue(0)
+            @maxValue(1.5)
+            score?: float64;
+            @minItems(1)
+            roles: string[];
+        }
+
+        @route("/users")
+        interface Users {
+            @get list(@query @minValue(1) @maxValue(100) limit?: int32): CreateUserRequest[];
+        }
+    "#;
+
+    let spec = generate_spec(source);
+    let props = &spec["components"]["schemas"]["CreateUserRequest"]["properties"];
+
+    assert_eq!(props["name"]["minLength"], 1);
+    assert_eq!(props["name"]["maxLength"], 100);
+    assert_eq!(props["name"]["pattern"], "^[A-Za-z ]+$");
+    assert_eq!(props["contact"]["format"], "email");
+    assert_eq!(props["score"]["minimum"], 0);
+    assert_eq!(props["score"]["maximum"], 1.5);
+    assert_eq!(props["roles"]["minItems"], 1);
+
+    let limit = &spec["paths"]["/users"]["get"]["parameters"][0]["schema"];
+    assert_eq!(limit["minimum"], 1);
+    assert_eq!(limit["maximum"], 100);
+}
+
+// ============================================================================
+// Path Generation Tests
+// ============================================================================
+
+#[test]
+fn test_generate_paths_from_interface() {
+    let source = r#"
+        @route("/users")
+        interface UserService {
+            @get list(): string[];
+            @get @route("/{id}") get(@path id: string): string;
+        }
+    "#;
+
+    let spec = generate_spec(source);
+
+    assert_eq!(
+        spec["paths"]["/users"]["get"]["operationId"],
+        "userServiceList"
+    );
+    assert!(spec["paths"]["/users/{id}"]["get"].is_object());
+}
+
+#[test]
+fn test_generate_paths_for_free_operations() {
+    let source = r#"
+        model HealthResponse {
+            status: string;
+        }
+
+        @get
+        @route("/health")
+        op getHealth(): HealthResponse;
+
+        @route("/admin")
+        namespace Admin {
+            @post
+            @route("/reindex")
+            op reindex(): void;
+        }
+    "#;
+
+    let spec = generate_spec(source);
+
+    let health = &spec["paths"]["/health"]["get"];
+    assert_eq!(health["operationId"], "defaultGetHealth");
+    assert_eq!(health["tags"][0], "Default");
+
+    let reindex = &spec["paths"]["/admin/reindex"]["post"];
+    assert_eq!(reindex["operationId"], "adminReindex");
+}
+
+#[test]
+fn test_generate_paths_for_templated_operations() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        model CreateTaskRequest {
+            title: string;
+        }
+
+        @post
+        op CreateResource<TRequest, TResource>(@body body: TRequest): TResource;
+
+        op CreateTask is CreateResource<CreateTaskRequest, Task>;
+
+        @route("/tasks")
+        interface TaskService {
+            @route("/bulk")
+            create is CreateTask;
+        }
+    "#;
+
+    let spec = generate_spec(source);
+
+    let create = &spec["paths"]["/tasks/bulk"]["post"];
+    assert_eq!(create["operationId"], "taskServiceCreate");
+    assert_eq!(
+        create["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/CreateTaskRequest"
+    );
+    // `CreateTask` is a concrete operation and is generated on its own
+    assert_eq!(
+        spec["paths"]["/"]["post"]["operationId"],
+        "defaultCreateTask"
+    );
+}
+
+// ============================================================================
+// Response Generation Tests
+// ============================================================================
+
+#[test]
+fn test_generate_error_responses() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        /** The task does not exist */
+        @error
+        model NotFound {
+            @statusCode _: 404;
+            message: string;
+        }
+
+        @error
+        model ApiError {
+            @statusCode statusCode: int32;
+            code: string;
+            message: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @get
+            @route("/{id}")
+            get(@path id: string): {
+                @statusCode _: 200;
+                @body body: Task;
+            } | NotFound | ApiError;
+
+            @get list(): Task[];
+        }
+    "#;
+
+    let spec = generate_spec(source);
+
+    let responses = &spec["paths"]["/tasks/{id}"]["get"]["responses"];
+    assert_eq!(
+        responses["200"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/Task"
+    );
+    assert_eq!(responses["404"]["description"], "The task does not exist");
+    assert_eq!(
+        responses["404"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/NotFound"
+    );
+    assert_eq!(
+        responses["default"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/ApiError"
+    );
+
+    // The status is sent as the HTTP status, not in the body
+    let api_error = &spec["components"]["schemas"]["ApiError"];
+    assert!(api_error["properties"].get("statusCode").is_none());
+    assert_eq!(
+        api_error["required"],
+        serde_json::json!(["code", "message"])
+    );
+
+    // Operations without `@error` models keep the generic error response
+    let list = &spec["paths"]["/tasks"]["get"]["responses"];
+    assert_eq!(list["default"]["description"], "Error response");
+}
+
+#[test]
+fn test_generate_errors_sharing_a_status() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        @error
+        model Conflict {
+            @statusCode _: 409;
+            @header etag: string;
+            message: string;
+        }
+
+        @error
+        model Locked {
+            @statusCode _: 409;
+            @header("Retry-After") retryAfter: int32;
+            lockedBy: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @put
+            @route("/{id}")
+            update(@path id: string, @body body: Task): Task | Conflict | Locked;
+        }
+    "#;
+
+    let spec = generate_spec(source);
+
+    let conflict = &spec["paths"]["/tasks/{id}"]["put"]["responses"]["409"];
+    assert_eq!(
+        conflict["content"]["application/json"]["schema"]["oneOf"],
+        serde_json::json!([
+            { "$ref": "#/components/schemas/Conflict" },
+            { "$ref": "#/components/schemas/Locked" }
+        ])
+    );
+    // Each header is sent by one of the two errors only
+    assert_eq!(conflict["headers"]["etag"]["required"], false);
+    assert_eq!(conflict["headers"]["Retry-After"]["required"], false);
+    assert_eq!(
+        conflict["headers"]["Retry-After"]["schema"]["type"],
+        "integer"
+    );
+}
+
+#[test]
+fn test_generate_multiple_success_responses() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        model Job {
+            id: string;
+            progress: int32;
+        }
+
+        @error
+        model ApiError {
+            @statusCode statusCode: int32;
+            message: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @post
+            create(@body body: Task): {
+                @statusCode _: 201;
+                @body task: Task;
+            } | {
+                @statusCode _: 202;
+                @body job: Job;
+            } | ApiError;
+
+            @delete
+            @route("/{id}")
+            delete(@path id: string): {
+                @statusCode _: 204;
+            } | {
+                @statusCode _: 202;
+                @body job: Job;
+            } | ApiError;
+        }
+    "#;
+
+    let spec = generate_spec(source);
+
+    let create = &spec["paths"]["/tasks"]["post"]["responses"];
+    assert_eq!(
+        create["201"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/Task"
+    );
+    assert_eq!(
+        create["202"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/Job"
+    );
+    assert!(create.get("200").is_none());
+
+    let delete = &spec["paths"]["/tasks/{id}"]["delete"]["responses"];
+    assert!(delete["204"].get("content").is_none());
+    assert!(delete.get("202").is_some());
+}
+
+#[test]
+fn test_generate_headers() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @post
+            create(@header("X-Request-ID") requestId: string, @body body: Task, @header ifMatch?: string): {
+                @statusCode _: 201;
+                /** Where the task lives */
+                @header location: string;
+                @header("ETag") etag?: string;
+                @body task: Task;
+            };
+
+            @get
+            @route("/{id}")
+            get(@path id: string, @header("X-Trace") trace?: int32): Task;
+        }
+    "#;
+
+    let spec = generate_spec(source);
+
+    let create = &spec["paths"]["/tasks"]["post"];
+    assert_eq!(
+        create["parameters"],
+        serde_json::json!([
+            { "name": "X-Request-ID", "in": "header", "required": true, "schema": { "type": "string" } },
+            { "name": "if-match", "in": "header", "required": false, "schema": { "type": "string" } }
+        ])
+    );
+
+    let headers = &create["responses"]["201"]["headers"];
+    assert_eq!(headers["location"]["required"], true);
+    assert_eq!(headers["location"]["description"], "Where the task lives");
+    assert_eq!(headers["ETag"]["required"], false);
+    assert_eq!(
+        spec["paths"]["/tasks/{id}"]["get"]["parameters"][1]["name"],
+        "X-Trace"
+    );
+}
+
+#[test]
+fn test_generate_spread_params() {
+    let source = r#"
+        enum SortOrder {
+            asc,
+            desc,
+        }
+
+        model PaginationParams {
+            @query page?: int32 = 1;
+            @query order?: SortOrder = SortOrder.desc;
+        }
+
+        @route("/items")
+        interface Items {
+            @get
+            list(@query search?: string, ...PaginationParams): string[];
+        }
+    "#;
+
+    let spec = generate_spec(source);
+
+    let params = &spec["paths"]["/items"]["get"]["parameters"];
+    let names: Vec<_> = params
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["search", "page", "order"]);
+    assert_eq!(params[1]["schema"]["default"], 1);
+    assert_eq!(params[2]["schema"]["default"], "desc");
+}
+
+#[test]
+fn test_generate_implicit_body() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @patch
+            @route("/{id}")
+            update(id: string, @minLength(1) title: string, done?: boolean): Task;
+        }
+    "#;
+
+    let spec = generate_spec(source);
+    let operation = &spec["paths"]["/tasks/{id}"]["patch"];
+
+    // A param named in the route is a path param
+    assert_eq!(operation["parameters"][0]["name"], "id");
+    assert_eq!(operation["parameters"][0]["in"], "path");
+    assert_eq!(operation["parameters"].as_array().unwrap().len(), 1);
+
+    // The rest are the properties of a JSON body
+    let schema = &operation["requestBody"]["content"]["application/json"]["schema"];
+    assert_eq!(schema["type"], "object");
+    assert_eq!(schema["properties"]["title"]["minLength"], 1);
+    assert_eq!(schema["properties"]["done"]["type"], "boolean");
+    assert_eq!(schema["required"], Value::from(vec!["title"]));
+}
@@ -2,7 +2,7 @@
 
 use tempfile::TempDir;
 use typespec_api::{
-    codegen::{Generator, Language, Side},
+    codegen::{Generator, Language, PythonModels, Side},
     parse,
 };
 
@@ -227,6 +227,350 @@ fn test_generate_model_from_dict_method() {
     assert!(models.contains("def from_dict(cls, data: Dict[str, Any])"));
 }
 
+#[test]
+fn test_generate_model_subclass() {
+    let source = r#"
+        model Admin extends User {
+            permissions: string[];
+        }
+
+        model User {
+            id: string;
+            name?: string;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_py(source, Side::Client);
+    let models = read_generated(&temp_dir, "models.py");
+
+    // Base class is emitted first even when declared later
+    let user_pos = models.find("class User:").unwrap();
+    let admin_pos = models
+        .find("@dataclass(kw_only=True)\nclass Admin(User):")
+        .unwrap();
+    assert!(user_pos < admin_pos);
+
+    let admin = &models[admin_pos..];
+    assert!(admin.contains("    permissions: List[str]"));
+    assert!(!admin.contains("    id: str"));
+    // Serialization still covers inherited fields
+    assert!(admin.contains(r#"result["id"] = self.id"#));
+}
+
+#[test]
+fn test_generate_doc_comments() {
+    let source = r#"
+        /**
+         * A unit of work.
+         *
+         * Tasks are assigned to agents.
+         */
+        model Task {
+            /** Unique identifier */
+            id: string;
+        }
+
+        /** Lifecycle of a task */
+        enum TaskStatus {
+            /** Not started yet */
+            pending,
+            done,
+        }
+
+        interface Tasks {
+            /** Fetch a task by id */
+            @get
+            @route("/tasks/{id}")
+            get(@path id: string): Task;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_py(source, Side::Both);
+    let models = read_generated(&temp_dir, "models.py");
+    let enums = read_generated(&temp_dir, "enums.py");
+    let client = read_generated(&temp_dir, "client/__init__.py");
+
+    assert!(models.contains(
+        "class Task:\n    \"\"\"A unit of work.\n\n    Tasks are assigned to agents.\n    \"\"\"\n"
+    ));
+    assert!(models.contains("    id: str\n    \"\"\"Unique identifier\"\"\"\n"));
+    assert!(enums.contains("    \"\"\"Lifecycle of a task\"\"\"\n"));
+    assert!(enums.contains("    PENDING = \"pending\"\n    \"\"\"Not started yet\"\"\"\n"));
+    assert!(client.contains("        \"\"\"Fetch a task by id\"\"\"\n"));
+}
+
+// ============================================================================
+// Validation Tests
+// ============================================================================
+
+#[test]
+fn test_generate_validate_methods() {
+    let source = r#"
+        model Tag {
+            @minLength(1)
+            @pattern("^[a-z-]+$")
+            name: string;
+        }
+
+        model CreateTaskRequest {
+            @minLength(1)
+            @maxLength(200)
+            title: string;
+            @minValue(1)
+            @maxValue(5)
+            priority?: int32;
+            @maxItems(10)
+            tags: Tag[];
+        }
+
+        model User {
+            id: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @post create(@body body: CreateTaskRequest): void;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_py(source, Side::Both);
+    let models = read_generated(&temp_dir, "models.py");
+    let server = read_generated(&temp_dir, "server/__init__.py");
+
+    assert!(models.contains("class ValidationError(ValueError):"));
+    let request = models.split("class CreateTaskRequest:").nth(1).unwrap();
+    assert!(request.contains(r#"def validate(self, path: str = "") -> None:"#));
+    assert!(request.contains("        if len(self.title) > 200:\n"));
+    assert!(request.contains(
+        r#"raise ValidationError(f"{path}title", "must be at most 200 characters long")"#
+    ));
+    assert!(request.contains("if self.priority is not None and self.priority < 1:"));
+    assert!(request.contains(r#"item.validate(f"{path}tags[{i}].")"#));
+    assert!(models.contains(r#"if re.search("^[a-z-]+$", self.name) is None:"#));
+    let user = models.split("class User:").nth(1).unwrap();
+    assert!(!user.contains("def validate"));
+    assert!(server.contains("def parse_body(model: Type[M], data: Dict[str, Any]) -> M:"));
+}
+
+// ============================================================================
+// Error Response Tests
+// ============================================================================
+
+#[test]
+fn test_generate_error_responses() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        @error
+        model NotFound {
+            @statusCode _: 404;
+            message: string;
+        }
+
+        @error
+        model ApiError {
+            @statusCode statusCode: int32;
+            code: string;
+            message: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @get
+            @route("/{id}")
+            get(@path id: string): {
+                @statusCode _: 200;
+                @body body: Task;
+            } | ApiError | NotFound;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_py(source, Side::Both);
+    let models = read_generated(&temp_dir, "models.py");
+    let client = read_generated(&temp_dir, "client/__init__.py");
+    let server = read_generated(&temp_dir, "server/__init__.py");
+
+    let not_found = models.split("class NotFound:").nth(1).unwrap();
+    assert!(!not_found.contains("    _:"));
+    assert!(not_found.contains("        if status != 404:\n            return None"));
+
+    let api_error = models.split("class ApiError:").nth(1).unwrap();
+    assert!(api_error.contains("    status_code: int\n"));
+    assert!(!api_error.contains(r#"result["statusCode"]"#));
+    // The status is not in the body, `from_response` sets it
+    assert!(api_error.contains(r#"status_code=_read(data, "statusCode"),"#));
+    assert!(api_error.contains("        return self.status_code or 500"));
+    assert!(api_error.contains("        error.status_code = status\n"));
+
+    // Fixed statuses are tried first
+    assert!(client.contains("from .. import models"));
+    assert!(client.contains("errors=(models.NotFound, models.ApiError,)"));
+    assert!(client.contains("return ApiError(resp.status_code, resp.text, error)"));
+
+    assert!(server.contains("class ErrorResponse(Exception):"));
+    assert!(server.contains("async def get(self, id: str) -> Task:"));
+}
+
+#[test]
+fn test_generate_multiple_success_responses() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        model Job {
+            id: string;
+            progress: int32;
+        }
+
+        @error
+        model ApiError {
+            @statusCode statusCode: int32;
+            message: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @post
+            create(@body body: Task): {
+                @statusCode _: 201;
+                @body task: Task;
+            } | {
+                @statusCode _: 202;
+                @body job: Job;
+            } | ApiError;
+
+            @delete
+            @route("/{id}")
+            delete(@path id: string): {
+                @statusCode _: 204;
+            } | {
+                @statusCode _: 202;
+                @body job: Job;
+            } | ApiError;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_py(source, Side::Both);
+    let client = read_generated(&temp_dir, "client/__init__.py");
+    let server = read_generated(&temp_dir, "server/__init__.py");
+
+    assert!(client.contains("async def _request_response("));
+    assert!(client.contains("async def create(self, body: Task) -> Task | Job:"));
+    assert!(client.contains(
+        "        status, result = await self._client._request_response(\"POST\", path, json=body.to_dict(), errors=(models.ApiError,))\n        if status == 201:\n            return Task.from_dict(result)\n        if status == 202:\n            return Job.from_dict(result)\n"
+    ));
+    assert!(client.contains("        if status == 204:\n            return None\n"));
+    assert!(server.contains("async def delete(self, id: str) -> None | Job:"));
+}
+
+#[test]
+fn test_generate_headers() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @post
+            create(@header("X-Request-ID") requestId: string, @body body: Task, @header ifMatch?: string): {
+                @statusCode _: 201;
+                /** Where the task lives */
+                @header location: string;
+                @header("ETag") etag?: string;
+                @body task: Task;
+            };
+
+            @get
+            @route("/{id}")
+            get(@path id: string, @header("X-Trace") trace?: int32): Task;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_py(source, Side::Both);
+    let models = read_generated(&temp_dir, "models.py");
+    let client = read_generated(&temp_dir, "client/__init__.py");
+    let server = read_generated(&temp_dir, "server/__init__.py");
+
+    assert!(client.contains(
+        "async def create(self, request_id: str, body: Task, if_match: Optional[str] = None) -> WithHeaders[Task, TasksCreateResponseHeaders]:"
+    ));
+    assert!(client.contains(r#"        headers["X-Request-ID"] = str(request_id)"#));
+    assert!(client.contains(
+        "        if if_match is not None:\n            headers[\"if-match\"] = str(if_match)"
+    ));
+    assert!(client.contains(
+        r#"resp = await self._client._send("POST", path, json=body.to_dict(), headers=headers)"#
+    ));
+    assert!(client.contains(
+        "return WithHeaders(Task.from_dict(result), TasksCreateResponseHeaders.from_headers(resp.headers))"
+    ));
+    assert!(client.contains(r#"headers = {**self._headers(), **kwargs.pop("headers", {})}"#));
+
+    assert!(models.contains("class WithHeaders(Generic[T, H]):"));
+    assert!(models.contains("class TasksCreateResponseHeaders:"));
+    assert!(models.contains(r#"            location=headers["location"],"#));
+    assert!(models.contains(r#"            etag=headers.get("ETag"),"#));
+    assert!(models.contains(r#"            headers["ETag"] = str(self.etag)"#));
+
+    assert!(server.contains("async def get(self, id: str, trace: Optional[int] = None) -> Task:"));
+}
+
+#[test]
+fn test_generate_headers_of_several_responses() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        model Job {
+            id: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @post
+            create(@body body: Task): {
+                @statusCode _: 201;
+                @header location: string;
+                @body task: Task;
+            } | {
+                @statusCode _: 202;
+                @body job: Job;
+            } | {
+                @statusCode _: 204;
+                @header("ETag") etag: string;
+            };
+        }
+    "#;
+
+    let (temp_dir, _) = generate_py(source, Side::Both);
+    let models = read_generated(&temp_dir, "models.py");
+    let client = read_generated(&temp_dir, "client/__init__.py");
+    let app = read_generated(&temp_dir, "server/app.py");
+
+    // Each response keeps its own headers
+    assert!(models.contains("class TasksCreateCreatedHeaders:"));
+    assert!(models.contains("class TasksCreateNoContentHeaders:"));
+    assert!(client.contains(
+        "-> WithHeaders[Task, TasksCreateCreatedHeaders] | Job | WithHeaders[None, TasksCreateNoContentHeaders]:"
+    ));
+    assert!(client.contains(
+        "        if status == 201:\n            return WithHeaders(Task.from_dict(result), TasksCreateCreatedHeaders.from_headers(resp.headers))"
+    ));
+    assert!(client.contains("        if status == 202:\n            return Job.from_dict(result)"));
+
+    // Servers tell responses apart by their headers
+    assert!(app.contains(
+        "        if isinstance(result, WithHeaders) and isinstance(result.headers, TasksCreateNoContentHeaders):\n            return _respond(204, result.body, result.headers.to_headers())"
+    ));
+    assert!(app
+        .contains("        if isinstance(result, Job):\n            return _respond(202, result)"));
+}
+
 // ============================================================================
 // Enum Generation Tests
 // ============================================================================
@@ -304,6 +648,26 @@ fn test_generate_service_client() {
     assert!(client.contains("async def list(self)"));
 }
 
+#[test]
+fn test_generate_client_for_free_operations() {
+    let source = r#"
+        model HealthResponse {
+            status: string;
+        }
+
+        @get
+        @route("/health")
+        op getHealth(): HealthResponse;
+    "#;
+
+    let (temp_dir, _) = generate_py(source, Side::Client);
+    let client = read_generated(&temp_dir, "client/__init__.py");
+
+    assert!(client.contains("class DefaultClient:"));
+    assert!(client.contains("async def get_health(self) -> HealthResponse:"));
+    assert!(client.contains("self.default = DefaultClient(self)"));
+}
+
 #[test]
 fn test_generate_client_with_path_params() {
     let source = r#"
@@ -344,6 +708,35 @@ fn test_generate_client_with_body() {
     assert!(client.contains("json=body.to_dict()"));
 }
 
+#[test]
+fn test_generate_client_with_array_body() {
+    let source = r#"
+        model SubTask {
+            title: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @post
+            @route("/{id}/subtasks")
+            add(@path id: string, @body items: SubTask[]): SubTask[];
+
+            @put
+            replace(@body task?: SubTask): SubTask;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_py(source, Side::Client);
+    let client = read_generated(&temp_dir, "client/__init__.py");
+
+    // Bodies keep their name and are encoded like their type
+    assert!(client.contains("async def add(self, id: str, items: List[SubTask]) -> List[SubTask]:"));
+    assert!(client.contains(r#"json=[item.to_dict() for item in items])"#));
+    assert!(client.contains("return [SubTask.from_dict(item) for item in result]"));
+    assert!(client.contains("async def replace(self, task: Optional[SubTask] = None) -> SubTask:"));
+    assert!(client.contains("json=None if task is None else task.to_dict())"));
+}
+
 #[test]
 fn test_generate_client_with_query_params() {
     let source = r#"
@@ -528,3 +921,307 @@ fn test_generates_typevar_for_generics() {
     assert!(models.contains("Generic"));
     assert!(models.contains("T = TypeVar('T')"));
 }
+
+#[test]
+fn test_generate_spread_params() {
+    let source = r#"
+        enum SortOrder {
+            asc,
+            desc,
+        }
+
+        model PaginationParams {
+            @query page?: int32 = 1;
+            @query order?: SortOrder = SortOrder.desc;
+        }
+
+        @route("/items")
+        interface Items {
+            @get
+            list(@query search?: string, ...PaginationParams): string[];
+        }
+    "#;
+
+    let (temp_dir, _) = generate_py(source, Side::Both);
+    let client = read_generated(&temp_dir, "client/__init__.py");
+    let server = read_generated(&temp_dir, "server/__init__.py");
+
+    let signature = "async def list(self, search: Optional[str] = None, page: int = 1, order: SortOrder = SortOrder.DESC) -> List[str]:";
+    assert!(client.contains(signature));
+    assert!(client.contains(r#"params["order"] = order"#));
+    assert!(server.contains(signature));
+}
+
+#[test]
+fn test_generate_implicit_body() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @patch
+            @route("/{id}")
+            update(id: string, @minLength(1) title: string, done?: boolean): Task;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_py(source, Side::Client);
+    let client = read_generated(&temp_dir, "client/__init__.py");
+
+    assert!(client.contains(
+        "async def update(self, id: str, title: str, done: Optional[bool] = None) -> Task:"
+    ));
+    assert!(client.contains(r#"path = path.replace("{id}", str(id))"#));
+    assert!(client.contains(r#"        payload["title"] = title"#));
+    assert!(client.contains(r#"            payload["done"] = done"#));
+    assert!(client.contains(r#"await self._client._request("PATCH", path, json=payload)"#));
+}
+
+#[test]
+fn test_generate_fastapi_implicit_body_decodes_properties() {
+    let source = r#"
+        enum Status { active, archived }
+
+        model Note {
+            @maxLength(100)
+            text: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @patch
+            @route("/{id}")
+            update(id: string, status: Status, due?: utcDateTime, note?: Note, notes?: Note[], tags?: Status[], priority?: int32 = 1): Note;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_py(source, Side::Server);
+    let app = read_generated(&temp_dir, "server/app.py");
+    let models = read_generated(&temp_dir, "models.py");
+
+    // Properties are converted like the fields of a model, raising
+    // `ValidationError` when missing or invalid
+    assert!(app.contains(r#"status = _read(data, "status", Status, required=True)"#));
+    assert!(app.contains(r#"due = _read(data, "due", _datetime)"#));
+    assert!(app.contains(r#"note = _read(data, "note", lambda value: _decode(Note, value))"#));
+    assert!(
+        app.contains(r#"notes = _read(data, "notes", lambda value: _decode_list(Note, value))"#)
+    );
+    assert!(app.contains(
+        r#"tags = _read(data, "tags", lambda value: [Status(item) for item in _list(value)])"#
+    ));
+    assert!(app.contains(r#"priority = _read(data, "priority", _int, default=1)"#));
+    assert!(app.contains("from ..models import _datetime, _read"));
+
+    assert!(models.contains(r#"raise ValidationError(field, "is required")"#));
+    assert!(models.contains(r#"raise ValidationError(field, "is invalid") from None"#));
+}
+
+#[test]
+fn test_generate_fastapi_app() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        @error
+        model NotFound {
+            @statusCode _: 404;
+            message: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @get list(@query pageSize?: int32 = 20, @header("X-Request-ID") requestId?: string): Task[];
+            @get @route("/{taskId}") get(@path taskId: string): Task | NotFound;
+            @post create(@body body: Task): { @statusCode _: 201; @body task: Task };
+            @post @route("/bulk") bulk(@body tasks: Task[]): Task[];
+            @patch @route("/{taskId}") update(taskId: string, title: string, done?: boolean): Task;
+            @delete @route("/{taskId}") remove(@path taskId: string): void;
+        }
+    "#;
+
+    let (temp_dir, files) = generate_py(source, Side::Server);
+    let app = read_generated(&temp_dir, "server/app.py");
+    let init = read_generated(&temp_dir, "__init__.py");
+
+    assert!(files.iter().any(|f| f.ends_with("openapi.json")));
+    assert!(!init.contains("from .client"));
+
+    assert!(app.contains("def tasks_router(handler: TasksHandler) -> fastapi.APIRouter:"));
+    assert!(app.contains("def create_app(*, tasks: TasksHandler) -> fastapi.FastAPI:"));
+    assert!(app.contains("app.openapi_schema = OPENAPI"));
+    assert!(app.contains("app.add_exception_handler(ErrorResponse, _error_response)"));
+    assert!(app.contains("app.include_router(tasks_router(tasks))"));
+
+    // Params are bound by FastAPI, path params by their snake case name
+    assert!(app.contains(r#"@router.get("/tasks/{task_id}")"#));
+    assert!(app.contains("task_id: Annotated[str, fastapi.Path()]"));
+    assert!(app.contains(r#"page_size: Annotated[int, fastapi.Query(alias="pageSize")] = 20"#));
+    assert!(app.contains(
+        r#"request_id: Annotated[Optional[str], fastapi.Header(alias="X-Request-ID")] = None"#
+    ));
+    assert!(app.contains("await handler.list(page_size=page_size, request_id=request_id)"));
+
+    // Bodies are decoded into the dataclasses
+    assert!(app.contains(r#"body = _decode(Task, _required(await _json(request), "body"))"#));
+    assert!(app.contains(r#"tasks = _decode_list(Task, _required(await _json(request), "tasks"))"#));
+    assert!(app.contains("return _respond(201, result)"));
+    assert!(app.contains(r#"title = _read(data, "title", _str, required=True)"#));
+    assert!(app.contains(r#"done = _read(data, "done", _bool)"#));
+    assert!(app.contains("return fastapi.Response(status_code=204)"));
+}
+
+#[test]
+fn test_generate_pydantic_models() {
+    let source = r#"
+        enum Status { active, archived }
+
+        model Task {
+            id: string;
+            @minLength(1) @maxLength(100) title: string;
+            status: Status;
+            createdAt: utcDateTime;
+            @minItems(1) tags: string[];
+            @minValue(0) priority?: int32 = 1;
+            dueAt?: utcDateTime;
+        }
+
+        model Page<T> { items: T[]; }
+
+        @error model Conflict { @statusCode code: int32; message: string; }
+
+        @route("/tasks")
+        interface Tasks {
+            @get list(): Page<Task>;
+        }
+    "#;
+
+    let file = parse(source).expect("Failed to parse TypeSpec");
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    Generator::new(&file, temp_dir.path(), "test_api")
+        .with_python_models(PythonModels::Pydantic)
+        .generate(Language::Python, Side::Both)
+        .expect("Failed to generate");
+    let models = read_generated(&temp_dir, "models.py");
+
+    assert!(models.contains("from pydantic import BaseModel, ConfigDict, Field"));
+    assert!(models.contains("from .enums import *"));
+    assert!(models.contains("class ApiModel(BaseModel):"));
+    assert!(models.contains("return cls.model_validate(data)"));
+    assert!(!models.contains("@dataclass\nclass Task"));
+
+    assert!(models.contains("class Task(ApiModel):"));
+    assert!(models.contains("    id: str\n"));
+    assert!(models.contains("    title: str = Field(min_length=1, max_length=100)"));
+    assert!(models.contains("    status: Status\n"));
+    assert!(models.contains("    created_at: datetime = Field(alias=\"createdAt\")"));
+    assert!(models.contains("    tags: List[str] = Field(min_length=1)"));
+    assert!(models.contains("    priority: Optional[int] = Field(default=1, ge=0)"));
+    assert!(
+        models.contains("    due_at: Optional[datetime] = Field(default=None, alias=\"dueAt\")")
+    );
+    assert!(models.contains("class Page(ApiModel, Generic[T]):"));
+
+    // Pydantic decodes instances of templates itself
+    let client = read_generated(&temp_dir, "client/__init__.py");
+    assert!(client.contains("return Page[Task].from_dict(result)"));
+
+    // `@statusCode` fields are set from the response, not the body
+    assert!(models.contains("    code: Optional[int] = Field(default=None, exclude=True)"));
+    assert!(models.contains("error.code = status"));
+}
+
+#[test]
+fn test_generate_nested_serialization() {
+    let source = r#"
+        @format("uuid")
+        scalar uuid extends string;
+
+        enum Status { active, archived }
+
+        model Audit { createdAt: utcDateTime; }
+
+        model Task {
+            id: uuid;
+            status: Status;
+            audit: Audit;
+            history?: Audit[];
+            labels: Record<Audit>;
+            closedAt: utcDateTime | null;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_py(source, Side::Both);
+    let models = read_generated(&temp_dir, "models.py");
+
+    assert!(models.contains("from .enums import *"));
+    assert!(models.contains("    id: UUID\n"));
+
+    // to_dict converts values to their JSON form
+    assert!(models.contains(r#"result["id"] = str(self.id)"#));
+    assert!(models.contains(r#"result["status"] = Status(self.status).value"#));
+    assert!(models.contains(r#"result["audit"] = self.audit.to_dict()"#));
+    assert!(models.contains(r#"result["history"] = [item.to_dict() for item in self.history]"#));
+    assert!(models.contains(
+        r#"result["labels"] = {key: item.to_dict() for key, item in self.labels.items()}"#
+    ));
+    assert!(models.contains(
+        r#"result["closedAt"] = (None if self.closed_at is None else self.closed_at.isoformat())"#
+    ));
+
+    // from_dict rebuilds them, rejecting missing required values and values
+    // that do not convert with `ValidationError`
+    assert!(models.contains(r#"created_at=_read(data, "createdAt", _datetime, required=True),"#));
+    assert!(models.contains(r#"id=_read(data, "id", UUID, required=True),"#));
+    assert!(models.contains(r#"status=_read(data, "status", Status, required=True),"#));
+    assert!(models.contains(
+        r#"history=_read(data, "history", lambda value: [Audit.from_dict(item) for item in value]),"#
+    ));
+    assert!(models.contains(
+        r#"labels=_read(data, "labels", lambda value: {key: Audit.from_dict(item) for key, item in value.items()}, required=True),"#
+    ));
+    assert!(models.contains(r#"closed_at=_read(data, "closedAt", _datetime),"#));
+}
+
+#[test]
+fn test_generate_template_instance_serialization() {
+    let source = r#"
+        enum Status { active, archived }
+
+        model Task { id: string; }
+
+        model Page<T> { items: T[]; }
+
+        @route("/tasks")
+        interface Tasks {
+            @get list(): Page<Task>;
+            @patch tag(tags: Page<Status>): void;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_py(source, Side::Client);
+    let models = read_generated(&temp_dir, "models.py");
+    let client = read_generated(&temp_dir, "client/__init__.py");
+
+    // Templates convert the values of their type params with the functions
+    // they are given
+    assert!(models.contains(
+        "def to_dict(self, t: Callable[[Any], Any] = lambda value: value) -> Dict[str, Any]:"
+    ));
+    assert!(models.contains(r#"result["items"] = [t(item) for item in self.items]"#));
+    assert!(models.contains(
+        r#"def from_dict(cls, data: Dict[str, Any], t: Callable[[Any], Any] = lambda value: value) -> "Page":"#
+    ));
+    assert!(models.contains(
+        r#"items=_read(data, "items", lambda value: [t(item) for item in value], required=True),"#
+    ));
+
+    // Instances pass the conversions of their type args
+    assert!(client.contains("return Page.from_dict(result, t=Task.from_dict)"));
+    assert!(
+        client.contains(r#"payload["tags"] = tags.to_dict(t=lambda value: Status(value).value)"#)
+    );
+}
@@ -200,6 +200,660 @@ fn test_generate_model_with_rust_keyword_field() {
     assert!(models.contains("r#type") || models.contains("pub type_:"));
 }
 
+#[test]
+fn test_generate_operation_with_rust_keyword_params() {
+    let source = r#"
+        model Item { id: string; }
+
+        @route("/items")
+        interface Items {
+            @get list(@query type?: string): Item[];
+            @get @route("/{loop}") get(@path loop: string): Item;
+            @post create(match: boolean): Item;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Both);
+    let client = read_generated(&temp_dir, "client.rs");
+    let server = read_generated(&temp_dir, "server.rs");
+
+    assert!(client.contains("pub async fn list(&self, r#type: Option<String>)"));
+    assert!(client.contains("if let Some(value) = &r#type {"));
+    assert!(client.contains(r#"let path = format!("/items/{}", r#loop);"#));
+    assert!(client.contains("serde_json::json!(r#match)"));
+    assert!(server.contains("async fn list(&self, r#type: Option<String>)"));
+    assert!(server.contains("async fn get(&self, r#loop: String)"));
+    assert!(server.contains("handler.create(payload.r#match)"));
+}
+
+#[test]
+fn test_generate_model_flattens_inherited_properties() {
+    let source = r#"
+        model User {
+            id: string;
+            name?: string;
+        }
+
+        model Admin extends User {
+            permissions: string[];
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Client);
+    let models = read_generated(&temp_dir, "models.rs");
+
+    let admin = models.split("pub struct Admin {").nth(1).unwrap();
+    let admin = &admin[..admin.find('}').unwrap()];
+    assert!(admin.contains("pub id: String"));
+    assert!(admin.contains("pub name: Option<String>"));
+    assert!(admin.contains("pub permissions: Vec<String>"));
+}
+
+#[test]
+fn test_generate_model_is_substitutes_template_args() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        model Page<T> {
+            items: T[];
+            total: int32;
+        }
+
+        model TaskPage is Page<Task>;
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Client);
+    let models = read_generated(&temp_dir, "models.rs");
+
+    let page = models.split("pub struct TaskPage {").nth(1).unwrap();
+    assert!(page.contains("pub items: Vec<Task>"));
+    assert!(page.contains("pub total: i32"));
+}
+
+#[test]
+fn test_generate_rejects_incompatible_override() {
+    let source = r#"
+        model User {
+            id: string;
+        }
+
+        model Admin extends User {
+            id: int32;
+        }
+    "#;
+
+    let file = parse(source).unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    let generator = Generator::new(&file, temp_dir.path(), "test_api");
+    let err = generator
+        .generate(Language::Rust, Side::Client)
+        .unwrap_err();
+    assert!(err.to_string().contains("property `id` of `Admin`"));
+}
+
+#[test]
+fn test_generate_rejects_unknown_type() {
+    let source = r#"
+        enum TaskStatus { pending, done }
+
+        model Task {
+            status: TaskStatsu;
+        }
+    "#;
+
+    let file = parse(source).unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    let generator = Generator::new(&file, temp_dir.path(), "test_api");
+    let err = generator
+        .generate(Language::Rust, Side::Client)
+        .unwrap_err();
+    assert!(err.to_string().contains("unknown type `TaskStatsu`"));
+    assert!(!temp_dir.path().join("src").exists());
+}
+
+#[test]
+fn test_generate_models_in_namespace_blocks() {
+    let source = r#"
+        namespace Api {
+            model User {
+                id: string;
+            }
+
+            namespace Admin {
+                enum Role { owner, member }
+            }
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Client);
+    let models = read_generated(&temp_dir, "models.rs");
+    let enums = read_generated(&temp_dir, "enums.rs");
+
+    assert!(models.contains("pub struct User"));
+    assert!(enums.contains("pub enum Role"));
+}
+
+#[test]
+fn test_generate_qualified_base_model() {
+    let source = r#"
+        namespace Tasks {
+            model TaskBase {
+                task_id: string;
+            }
+        }
+
+        namespace Agents {
+            model Base {
+                agent_id: string;
+            }
+        }
+
+        model Run extends Agents.Base {
+            name: string;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Client);
+    let models = read_generated(&temp_dir, "models.rs");
+    let run = &models[models.find("pub struct Run").unwrap()..];
+
+    assert!(run.contains("pub agent_id: String"));
+    assert!(!run.contains("pub task_id: String"));
+}
+
+#[test]
+fn test_same_named_models_in_sibling_namespaces_are_prefixed() {
+    let source = r#"
+        namespace Tasks {
+            model Status { done: boolean; }
+            model Task { status: Status; }
+        }
+
+        namespace Agents {
+            model Status { busy: boolean; }
+            model Agent { status: Status; task: Tasks.Status; }
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Client);
+    let models = read_generated(&temp_dir, "models.rs");
+
+    assert!(models.contains("pub struct TasksStatus {\n    pub done: bool,"));
+    assert!(models.contains("pub struct AgentsStatus {\n    pub busy: bool,"));
+    assert!(!models.contains("pub struct Status "));
+    let task = &models[models.find("pub struct Task ").unwrap()..];
+    assert!(task.contains("pub status: TasksStatus,"));
+    let agent = &models[models.find("pub struct Agent ").unwrap()..];
+    assert!(agent.contains("pub status: AgentsStatus,"));
+    assert!(agent.contains("pub task: TasksStatus,"));
+}
+
+#[test]
+fn test_same_named_namespace_services_are_prefixed() {
+    let source = r#"
+        namespace A.Admin {
+            @route("/a") op x(): void;
+        }
+
+        namespace B.Admin {
+            @route("/b") op y(): void;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Both);
+    let client = read_generated(&temp_dir, "client.rs");
+    let server = read_generated(&temp_dir, "server.rs");
+
+    assert!(client.contains("pub struct AAdminClient<'a>"));
+    assert!(client.contains("pub struct BAdminClient<'a>"));
+    assert!(!client.contains("pub struct AdminClient"));
+    assert!(server.contains("pub trait AAdminHandler"));
+    assert!(server.contains("pub trait BAdminHandler"));
+}
+
+#[test]
+fn test_generate_doc_comments() {
+    let source = r#"
+        /**
+         * A unit of work.
+         *
+         * Tasks are assigned to agents.
+         */
+        model Task {
+            /** Unique identifier */
+            id: string;
+        }
+
+        /** Lifecycle of a task */
+        enum TaskStatus {
+            /** Not started yet */
+            pending,
+            done,
+        }
+
+        interface Tasks {
+            /** Fetch a task by id */
+            @get
+            @route("/tasks/{id}")
+            get(@path id: string): Task;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Both);
+    let models = read_generated(&temp_dir, "models.rs");
+    let enums = read_generated(&temp_dir, "enums.rs");
+    let client = read_generated(&temp_dir, "client.rs");
+    let server = read_generated(&temp_dir, "server.rs");
+
+    assert!(models.contains("/// A unit of work.\n///\n/// Tasks are assigned to agents.\n"));
+    assert!(models.contains("    /// Unique identifier\n"));
+    assert!(enums.contains("/// Lifecycle of a task\n"));
+    assert!(enums.contains("    /// Not started yet\n"));
+    assert!(client.contains("    /// Fetch a task by id\n"));
+    assert!(server.contains("    /// Fetch a task by id\n"));
+}
+
+// ============================================================================
+// Validation Tests
+// ============================================================================
+
+#[test]
+fn test_generate_validate_impls() {
+    let source = r#"
+        model Tag {
+            @minLength(1)
+            @pattern("^[a-z-]+$")
+            name: string;
+        }
+
+        model CreateTaskRequest {
+            @minLength(1)
+            @maxLength(200)
+            title: string;
+            @minValue(1)
+            @maxValue(5)
+            priority?: int32;
+            @maxItems(10)
+            tags: Tag[];
+        }
+
+        model User {
+            id: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @post create(@body body: CreateTaskRequest): void;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Both);
+    let models = read_generated(&temp_dir, "models.rs");
+    let server = read_generated(&temp_dir, "server.rs");
+    let cargo = std::fs::read_to_string(temp_dir.path().join("Cargo.toml")).unwrap();
+
+    assert!(models.contains("pub trait Validate"));
+    assert!(models.contains("impl Validate for User {}"));
+    let request = models
+        .split("impl Validate for CreateTaskRequest {")
+        .nth(1)
+        .unwrap();
+    assert!(request.contains("if value.chars().count() > 200 {"));
+    assert!(request.contains(
+        r#"return Err(ValidationError::new("title", "must be at most 200 characters long"));"#
+    ));
+    assert!(request.contains("if let Some(value) = &self.priority {"));
+    assert!(request.contains("if (*value as f64) < 1.0 {"));
+    assert!(request.contains(r#"item.validate().map_err(|e| e.at(&format!("tags[{}]", i)))?;"#));
+    assert!(models.contains(r#"regex::Regex::new("^[a-z-]+$")"#));
+    assert!(cargo.contains(r#"regex = "1""#));
+    assert!(server.contains("pub struct ValidatedJson<T>(pub T);"));
+}
+
+#[test]
+fn test_generate_without_patterns_has_no_regex_dependency() {
+    let source = r#"
+        model Tag {
+            @maxLength(20)
+            name: string;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Client);
+    let cargo = std::fs::read_to_string(temp_dir.path().join("Cargo.toml")).unwrap();
+
+    assert!(!cargo.contains("regex"));
+}
+
+// ============================================================================
+// Error Response Tests
+// ============================================================================
+
+const ERRORS_SOURCE: &str = r#"
+    model Task {
+        id: string;
+    }
+
+    @error
+    model NotFound {
+        @statusCode _: 404;
+        message: string;
+    }
+
+    @error
+    model ApiError {
+        @statusCode statusCode: int32;
+        code: string;
+        message: string;
+    }
+
+    @route("/tasks")
+    interface Tasks {
+        @get
+        @route("/{id}")
+        get(@path id: string): {
+            @statusCode _: 200;
+            @body body: Task;
+        } | NotFound | ApiError;
+
+        @delete
+        @route("/{id}")
+        delete(@path id: string): {
+            @statusCode _: 204;
+        } | ApiError;
+    }
+"#;
+
+#[test]
+fn test_generate_error_responses() {
+    let (temp_dir, _) = generate_rust(ERRORS_SOURCE, Side::Both);
+    let models = read_generated(&temp_dir, "models.rs");
+    let client = read_generated(&temp_dir, "client.rs");
+    let server = read_generated(&temp_dir, "server.rs");
+
+    // The status is not part of the body
+    assert!(models.contains("    #[serde(skip)]\n    pub status_code: i32,"));
+    assert!(!models.contains("pub _:"));
+
+    let not_found = models
+        .split("impl ErrorResponse for NotFound {")
+        .nth(1)
+        .unwrap();
+    assert!(not_found.contains("if status != 404 {"));
+    let api_error = models
+        .split("impl ErrorResponse for ApiError {")
+        .nth(1)
+        .unwrap();
+    assert!(api_error.contains("u16::try_from(self.status_code).unwrap_or(500)"));
+    assert!(api_error.contains("error.status_code = status as _;"));
+
+    // Several error models get an enum, fixed statuses first
+    assert!(models
+        .contains("pub enum TasksGetError {\n    NotFound(NotFound),\n    ApiError(ApiError),\n}"));
+    assert!(models.contains("impl From<NotFound> for TasksGetError"));
+
+    assert!(client.contains("pub enum ApiError<E = ()> {"));
+    assert!(client.contains("Typed { status: u16, error: E },"));
+    assert!(client.contains(
+        "pub async fn get(&self, id: &str) -> Result<Task, ApiError<crate::models::TasksGetError>>"
+    ));
+    assert!(client.contains(
+        "pub async fn delete(&self, id: &str) -> Result<(), ApiError<crate::models::ApiError>>"
+    ));
+
+    assert!(server.contains("impl axum::response::IntoResponse for crate::models::NotFound {"));
+    assert!(server.contains("impl axum::response::IntoResponse for crate::models::TasksGetError {"));
+    assert!(server.contains(
+        "async fn get(&self, id: String) -> Result<Task, crate::models::TasksGetError>;"
+    ));
+}
+
+#[test]
+fn test_generate_without_error_models_keeps_untyped_errors() {
+    let source = r#"
+        model User { id: string; }
+
+        @route("/users")
+        interface Users {
+            @get list(): User[];
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Both);
+    let client = read_generated(&temp_dir, "client.rs");
+    let server = read_generated(&temp_dir, "server.rs");
+
+    assert!(client.contains("pub async fn list(&self) -> Result<Vec<User>, ApiError> {"));
+    assert!(server.contains("async fn list(&self) -> Result<Vec<User>, ApiError>;"));
+}
+
+#[test]
+fn test_generate_multiple_success_responses() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        model Job {
+            id: string;
+            progress: int32;
+        }
+
+        @error
+        model ApiError {
+            @statusCode statusCode: int32;
+            message: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @post
+            create(@body body: Task): {
+                @statusCode _: 201;
+                @body task: Task;
+            } | {
+                @statusCode _: 202;
+                @body job: Job;
+            } | ApiError;
+
+            @delete
+            @route("/{id}")
+            delete(@path id: string): {
+                @statusCode _: 204;
+            } | {
+                @statusCode _: 202;
+                @body job: Job;
+            } | ApiError;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Both);
+    let models = read_generated(&temp_dir, "models.rs");
+    let client = read_generated(&temp_dir, "client.rs");
+    let server = read_generated(&temp_dir, "server.rs");
+
+    // One variant per status, named after it
+    assert!(models
+        .contains("pub enum TasksCreateResponse {\n    Created(Task),\n    Accepted(Job),\n}"));
+    assert!(
+        models.contains("pub enum TasksDeleteResponse {\n    NoContent,\n    Accepted(Job),\n}")
+    );
+    assert!(models.contains("impl FromResponse for TasksCreateResponse {"));
+    assert!(models.contains("201 => serde_json::from_value(body).map(Self::Created),"));
+    assert!(models.contains("204 => Ok(Self::NoContent),"));
+
+    assert!(client.contains(
+        "pub async fn create(&self, body: &Task) -> Result<TasksCreateResponse, ApiError<crate::models::ApiError>>"
+    ));
+    assert!(client.contains(
+        "self.client.request_responses(Method::POST, &path, &[], &[], Some(body)).await"
+    ));
+    assert!(client.contains("Decode(serde_json::Error),"));
+
+    assert!(server
+        .contains("impl axum::response::IntoResponse for crate::models::TasksCreateResponse {"));
+    assert!(server.contains(
+        "async fn delete(&self, id: String) -> Result<TasksDeleteResponse, crate::models::ApiError>;"
+    ));
+}
+
+#[test]
+fn test_generate_headers() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @post
+            create(@header("X-Request-ID") requestId: string, @body body: Task, @header ifMatch?: string): {
+                @statusCode _: 201;
+                /** Where the task lives */
+                @header location: string;
+                @header("ETag") etag?: string;
+                @body task: Task;
+            };
+
+            @get
+            @route("/{id}")
+            get(@path id: string, @header("X-Trace") trace?: int32): Task;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Both);
+    let models = read_generated(&temp_dir, "models.rs");
+    let client = read_generated(&temp_dir, "client.rs");
+    let server = read_generated(&temp_dir, "server.rs");
+
+    // `@header` params are sent as headers
+    assert!(client.contains(
+        "pub async fn create(&self, request_id: String, body: &Task, if_match: Option<String>) -> Result<WithHeaders<Task, TasksCreateResponseHeaders>, ApiError>"
+    ));
+    assert!(client.contains(r#"headers.push(("X-Request-ID", param_value(&request_id)));"#));
+    assert!(client.contains("if let Some(value) = &if_match {"));
+    assert!(client.contains(r#"headers.push(("if-match", param_value(value)));"#));
+    assert!(client.contains(
+        "self.client.request_with_headers(Method::POST, &path, &[], &headers, Some(body)).await"
+    ));
+    assert!(client
+        .contains("self.client.request(Method::GET, &path, &[], &headers, None::<&()>).await"));
+
+    // Response headers are typed
+    assert!(models.contains("pub struct WithHeaders<T, H> {"));
+    assert!(models.contains(
+        "pub struct TasksCreateResponseHeaders {\n    /// Where the task lives\n    pub location: String,\n    pub etag: Option<String>,\n}"
+    ));
+    assert!(models.contains("impl ResponseHeaders for TasksCreateResponseHeaders {"));
+    assert!(models.contains(r#"headers.push(("ETag", value.to_string()));"#));
+
+    // Servers extract request headers
+    assert!(server.contains("pub struct TasksCreateHeaders {\n    pub request_id: String,\n    pub if_match: Option<String>,\n}"));
+    assert!(server.contains(r#"request_id: required_header(&parts.headers, "X-Request-ID")?,"#));
+    assert!(server.contains(r#"trace: request_header(&parts.headers, "X-Trace")?,"#));
+    assert!(server.contains("impl<T, H> axum::response::IntoResponse for WithHeaders<T, H>"));
+    assert!(server.contains(
+        "async fn get(&self, id: String, trace: Option<i32>) -> Result<Task, ApiError>;"
+    ));
+}
+
+#[test]
+fn test_generate_headers_of_several_responses() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        model Job {
+            id: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @post
+            create(@body body: Task): {
+                @statusCode _: 201;
+                @header location: string;
+                @body task: Task;
+            } | {
+                @statusCode _: 202;
+                @body job: Job;
+            } | {
+                @statusCode _: 204;
+                @header("ETag") etag: string;
+            };
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Both);
+    let models = read_generated(&temp_dir, "models.rs");
+    let server = read_generated(&temp_dir, "server.rs");
+
+    // Each response keeps its own headers
+    assert!(models.contains(
+        "pub enum TasksCreateResponse {\n    Created(WithHeaders<Task, TasksCreateCreatedHeaders>),\n    Accepted(Job),\n    NoContent(WithHeaders<(), TasksCreateNoContentHeaders>),\n}"
+    ));
+    assert!(models.contains("pub struct TasksCreateCreatedHeaders {\n    pub location: String,\n}"));
+    assert!(models.contains("pub struct TasksCreateNoContentHeaders {\n    pub etag: String,\n}"));
+    assert!(models.contains(
+        "headers: TasksCreateCreatedHeaders::from_headers(get).map_err(serde::de::Error::custom)?,"
+    ));
+    assert!(models.contains("202 => serde_json::from_value(body).map(Self::Accepted),"));
+
+    // Servers send them with their response
+    assert!(server.contains("Self::Created(response) => (status, response).into_response(),"));
+    assert!(server.contains("Self::NoContent(response) => (status, response).into_response(),"));
+}
+
+#[test]
+fn test_generate_named_response_with_headers() {
+    let source = r#"
+        model TaskWithEtag {
+            @header etag: string;
+            id: string;
+            title: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @get
+            @route("/{id}")
+            get(@path id: string): TaskWithEtag;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Both);
+    let models = read_generated(&temp_dir, "models.rs");
+    let client = read_generated(&temp_dir, "client.rs");
+    let server = read_generated(&temp_dir, "server.rs");
+
+    assert!(client.contains("Result<WithHeaders<TaskWithEtag, TasksGetResponseHeaders>, ApiError>"));
+    assert!(server.contains("Result<WithHeaders<TaskWithEtag, TasksGetResponseHeaders>, ApiError>"));
+    // The header is not part of the body
+    assert!(models.contains("    #[serde(skip)]\n    pub etag: String,"));
+}
+
+#[test]
+fn test_generate_without_headers_has_no_header_extractors() {
+    let source = r#"
+        model User { id: string; }
+
+        @route("/users")
+        interface Users {
+            @get list(): User[];
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Both);
+    let client = read_generated(&temp_dir, "client.rs");
+    let server = read_generated(&temp_dir, "server.rs");
+
+    assert!(client.contains("self.client.request(Method::GET, &path, &[], &[], None::<&()>).await"));
+    assert!(!server.contains("fn required_header"));
+}
+
 // ============================================================================
 // Enum Generation Tests
 // ============================================================================
@@ -355,6 +1009,53 @@ fn test_generate_client_http_methods() {
     assert!(client.contains("Method::DELETE"));
 }
 
+#[test]
+fn test_generate_client_for_free_operations() {
+    let source = r#"
+        model HealthResponse {
+            status: string;
+        }
+
+        @get
+        @route("/health")
+        op getHealth(): HealthResponse;
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Client);
+    let client = read_generated(&temp_dir, "client.rs");
+
+    assert!(client.contains("pub struct DefaultClient<'a>"));
+    assert!(client.contains("pub async fn get_health(&self) -> Result<HealthResponse, ApiError>"));
+    assert!(client.contains(r#"let path = "/health";"#));
+}
+
+#[test]
+fn test_generate_client_for_templated_operations() {
+    let source = r#"
+        model Project {
+            id: string;
+        }
+
+        @get
+        @route("/{id}")
+        op ReadResource<T>(@path id: string): T;
+
+        @route("/projects")
+        interface ProjectService {
+            get is ReadResource<Project>;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Client);
+    let client = read_generated(&temp_dir, "client.rs");
+
+    assert!(client.contains("pub async fn get(&self, id: &str) -> Result<Project, ApiError>"));
+    assert!(client.contains(r#"let path = format!("/projects/{}""#));
+    // Templates themselves are not generated
+    assert!(!client.contains("DefaultClient"));
+    assert!(!client.contains("read_resource"));
+}
+
 // ============================================================================
 // Server Generation Tests
 // ============================================================================
@@ -380,7 +1081,7 @@ fn test_generate_server_trait() {
 fn test_generate_server_skips_spread_params() {
     let source = r#"
         model PaginationParams {
-            page?: int32;
+            @query page?: int32;
         }
 
         @route("/items")
@@ -398,6 +1099,23 @@ fn test_generate_server_skips_spread_params() {
     assert!(!server.contains(", : "));
 }
 
+#[test]
+fn test_generate_server_trait_for_namespaced_operations() {
+    let source = r#"
+        @route("/admin")
+        namespace Admin {
+            @post
+            op reindex(): void;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Server);
+    let server = read_generated(&temp_dir, "server.rs");
+
+    assert!(server.contains("pub trait AdminHandler"));
+    assert!(server.contains("async fn reindex(&self)"));
+}
+
 // ============================================================================
 // Side Selection Tests
 // ============================================================================
@@ -496,3 +1214,193 @@ fn test_generate_lib_rs() {
     assert!(lib.contains("pub mod client;"));
     assert!(lib.contains("pub mod server;"));
 }
+
+#[test]
+fn test_generate_spread_params() {
+    let source = r#"
+        enum SortOrder {
+            asc,
+            desc,
+        }
+
+        model PaginationParams {
+            @query page?: int32 = 1;
+            @query order?: SortOrder = SortOrder.desc;
+        }
+
+        @route("/items")
+        interface Items {
+            @get
+            list(@query search?: string, ...PaginationParams): string[];
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Both);
+    let client = read_generated(&temp_dir, "client.rs");
+    let server = read_generated(&temp_dir, "server.rs");
+
+    // Spread properties are params of their own, sent in the query
+    assert!(client.contains(
+        "pub async fn list(&self, search: Option<String>, page: Option<i32>, order: Option<SortOrder>)"
+    ));
+    assert!(client.contains(r#"query.push(("order", param_value(value)));"#));
+    assert!(
+        client.contains("self.client.request(Method::GET, &path, &query, &[], None::<&()>).await")
+    );
+
+    // Servers fill in defaults
+    assert!(server.contains(
+        "async fn list(&self, search: Option<String>, page: i32, order: SortOrder) -> Result<Vec<String>, ApiError>;"
+    ));
+    assert!(server.contains(r#"#[serde(default = "ItemsListQuery::default_page")]"#));
+    assert!(
+        server.contains("    fn default_order() -> SortOrder {\n        SortOrder::Desc\n    }")
+    );
+}
+
+#[test]
+fn test_generate_spread_params_of_literal_unions() {
+    let source = r#"
+        model PaginationParams {
+            @query order?: "asc" | "desc" = "desc";
+        }
+
+        @route("/projects")
+        interface ProjectService {
+            @get
+            list(...PaginationParams): string[];
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Both);
+    let models = read_generated(&temp_dir, "models.rs");
+    let client = read_generated(&temp_dir, "client.rs");
+    let server = read_generated(&temp_dir, "server.rs");
+
+    // The inline enum of the model types the params spread from it
+    assert!(models.contains("pub enum PaginationParamsOrder {"));
+    assert!(client.contains(
+        "pub async fn list(&self, order: Option<PaginationParamsOrder>) -> Result<Vec<String>, ApiError>"
+    ));
+    assert!(server.contains("    pub order: PaginationParamsOrder,"));
+    assert!(server.contains(
+        "    fn default_order() -> PaginationParamsOrder {\n        PaginationParamsOrder::Desc\n    }"
+    ));
+    assert!(server.contains(
+        "async fn list(&self, order: PaginationParamsOrder) -> Result<Vec<String>, ApiError>;"
+    ));
+}
+
+#[test]
+fn test_generate_implicit_body() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        model Note {
+            @maxLength(100)
+            text: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @patch
+            @route("/{id}")
+            update(id: string, @minLength(1) title: string, done?: boolean, note?: Note): Task;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Both);
+    let client = read_generated(&temp_dir, "client.rs");
+    let server = read_generated(&temp_dir, "server.rs");
+
+    assert!(client.contains(
+        "pub async fn update(&self, id: &str, title: String, done: Option<bool>, note: Option<Note>) -> Result<Task, ApiError>"
+    ));
+    assert!(client.contains(r#"let path = format!("/tasks/{}", id);"#));
+    assert!(client.contains(r#"payload.insert("title".to_string(), serde_json::json!(title));"#));
+    assert!(client.contains("if let Some(value) = &done {"));
+    assert!(client
+        .contains("self.client.request(Method::PATCH, &path, &[], &[], Some(&payload)).await"));
+
+    assert!(server.contains(
+        "async fn update(&self, id: String, title: String, done: Option<bool>, note: Option<Note>) -> Result<Task, ApiError>;"
+    ));
+
+    // The body is validated like a model before the handler runs
+    assert!(server.contains("impl Validate for TasksUpdateBody {"));
+    assert!(server.contains("let value = &self.title;"));
+    assert!(server.contains("if let Some(value) = &self.note {"));
+    assert!(server.contains(r#"value.validate().map_err(|e| e.at("note"))?;"#));
+    assert!(server.contains("ValidatedJson(payload): ValidatedJson<TasksUpdateBody>,"));
+}
+
+#[test]
+fn test_generate_router() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        @error
+        model NotFound {
+            @statusCode _: 404;
+            message: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @get list(@query page?: int32 = 1, @header("X-Request-ID") requestId?: string): Task[];
+            @get @route("/{id}") get(@path id: string): Task | NotFound;
+            @post create(@body body: Task): { @statusCode _: 201; @body task: Task };
+            @delete @route("/{id}") remove(@path id: string): void;
+            @post @route("/{id}/runs/{runId}") run(@path runId: int32, @path id: string): Task;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Server);
+    let server = read_generated(&temp_dir, "server.rs");
+
+    assert!(server.contains("pub mod tasks {"));
+    assert!(server.contains("pub fn router<H: TasksHandler>(handler: Arc<H>) -> Router {"));
+    assert!(server.contains(r#".route("/tasks", axum::routing::get(list::<H>).post(create::<H>))"#));
+    assert!(server
+        .contains(r#".route("/tasks/:id", axum::routing::get(get::<H>).delete(remove::<H>))"#));
+    assert!(server.contains("Query(query): Query<TasksListQuery>,"));
+    assert!(server.contains("headers: TasksListHeaders,"));
+    assert!(server.contains("handler.list(query.page, headers.request_id).await"));
+    assert!(server.contains("ValidatedJson(body): ValidatedJson<Task>,"));
+    assert!(server.contains("Ok(body) => (StatusCode::CREATED, Json(body)).into_response(),"));
+    assert!(server.contains("Ok(()) => StatusCode::NO_CONTENT.into_response(),"));
+    assert!(server.contains("Err(error) => error.into_response(),"));
+
+    // Path params are extracted in the order of the route
+    assert!(server.contains("Path((id, run_id)): Path<(String, i32)>,"));
+    assert!(server.contains("handler.run(run_id, id).await"));
+}
+
+#[test]
+fn test_generate_router_validates_array_bodies() {
+    let source = r#"
+        model Task {
+            @minLength(1)
+            title: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @post bulk(@body tasks: Task[]): Task[];
+            @put tag(@body tags: string[]): void;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_rust(source, Side::Server);
+    let models = read_generated(&temp_dir, "models.rs");
+    let server = read_generated(&temp_dir, "server.rs");
+
+    assert!(models.contains("impl<T: Validate> Validate for Vec<T> {"));
+    assert!(models.contains(r#"item.validate().map_err(|e| e.at(&format!("[{}]", i)))?;"#));
+    assert!(server.contains("ValidatedJson(tasks): ValidatedJson<Vec<Task>>,"));
+    assert!(server.contains("Json(tags): Json<Vec<String>>,"));
+}
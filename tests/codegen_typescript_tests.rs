@@ -110,6 +110,29 @@ fn test_generate_generic_model() {
     assert!(models.contains("items: T[]"));
 }
 
+#[test]
+fn test_generate_generic_model_usage_stays_generic() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        model PaginatedResponse<T> {
+            items: T[];
+        }
+
+        model TaskList {
+            page: PaginatedResponse<Task>;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_ts(source, Side::Client);
+    let models = read_generated(&temp_dir, "models.ts");
+
+    assert!(models.contains("page: PaginatedResponse<Task>;"));
+    assert!(!models.contains("PaginatedResponseTask"));
+}
+
 #[test]
 fn test_generate_model_with_multiple_type_params() {
     let source = r#"
@@ -197,6 +220,401 @@ fn test_generate_model_preserves_field_names() {
     assert!(models.contains("createdAt: string"));
 }
 
+#[test]
+fn test_generate_model_extends_interface() {
+    let source = r#"
+        model User {
+            id: string;
+            name?: string;
+        }
+
+        model Admin extends User {
+            permissions: string[];
+        }
+    "#;
+
+    let (temp_dir, _) = generate_ts(source, Side::Client);
+    let models = read_generated(&temp_dir, "models.ts");
+
+    assert!(models.contains("export interface Admin extends User {\n  permissions: string[];\n}"));
+}
+
+#[test]
+fn test_generate_doc_comments() {
+    let source = r#"
+        /**
+         * A unit of work.
+         *
+         * Tasks are assigned to agents.
+         */
+        model Task {
+            /** Unique identifier */
+            id: string;
+        }
+
+        /** Lifecycle of a task */
+        enum TaskStatus {
+            /** Not started yet */
+            pending,
+            done,
+        }
+
+        interface Tasks {
+            /** Fetch a task by id */
+            @get
+            @route("/tasks/{id}")
+            get(@path id: string): Task;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_ts(source, Side::Client);
+    let models = read_generated(&temp_dir, "models.ts");
+    let enums = read_generated(&temp_dir, "enums.ts");
+    let client = read_generated(&temp_dir, "client.ts");
+
+    assert!(models.contains("/**\n * A unit of work.\n *\n * Tasks are assigned to agents.\n */\n"));
+    assert!(models.contains("  /** Unique identifier */\n  id: string;"));
+    assert!(enums.contains("/** Lifecycle of a task */\nexport enum TaskStatus"));
+    assert!(enums.contains("  /** Not started yet */\n  Pending"));
+    assert!(client.contains("  /** Fetch a task by id */\n  async get("));
+}
+
+// ============================================================================
+// Validation Tests
+// ============================================================================
+
+#[test]
+fn test_generate_validators() {
+    let source = r#"
+        model Tag {
+            @minLength(1)
+            @pattern("^[a-z-]+$")
+            name: string;
+        }
+
+        model CreateTaskRequest {
+            @minLength(1)
+            @maxLength(200)
+            title: string;
+            @minValue(1)
+            @maxValue(5)
+            priority?: int32;
+            @maxItems(10)
+            tags: Tag[];
+        }
+
+        model User {
+            id: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @post create(@body body: CreateTaskRequest): void;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_ts(source, Side::Both);
+    let models = read_generated(&temp_dir, "models.ts");
+    let server = read_generated(&temp_dir, "server.ts");
+
+    assert!(models.contains("export class ValidationError extends Error {"));
+    assert!(models.contains(
+        "export function validateCreateTaskRequest(value: CreateTaskRequest, path = ''): void {"
+    ));
+    assert!(models.contains("  if ([...value.title].length > 200) {"));
+    assert!(models.contains("  if (value.tags.length > 10) {"));
+    assert!(models.contains(
+        r#"throw new ValidationError(`${path}title`, "must be at most 200 characters long");"#
+    ));
+    assert!(models.contains("  if (value.priority != null && value.priority < 1) {"));
+    assert!(models
+        .contains("value.tags.forEach((item, i) => validateTag(item, `${path}tags[${i}].`));"));
+    assert!(models.contains(r#"if (!new RegExp("^[a-z-]+$").test(value.name)) {"#));
+    assert!(!models.contains("validateUser"));
+    assert!(server.contains("export function parseBody<T>("));
+}
+
+// ============================================================================
+// Error Response Tests
+// ============================================================================
+
+#[test]
+fn test_generate_error_responses() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        @error
+        model NotFound {
+            @statusCode _: 404;
+            message: string;
+        }
+
+        @error
+        model ApiError {
+            @statusCode statusCode: int32;
+            code: string;
+            message: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @get
+            @route("/{id}")
+            get(@path id: string): {
+                @statusCode _: 200;
+                @body body: Task;
+            } | ApiError | NotFound;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_ts(source, Side::Both);
+    let models = read_generated(&temp_dir, "models.ts");
+    let client = read_generated(&temp_dir, "client.ts");
+    let server = read_generated(&temp_dir, "server.ts");
+    let index = read_generated(&temp_dir, "index.ts");
+
+    // The status is not part of the body
+    assert!(models.contains("export interface NotFound {\n  message: string;\n}"));
+    assert!(models.contains("export interface ApiError {\n  statusCode?: number;\n  code: string;"));
+
+    // The `ApiError` model does not clash with the client's error class
+    assert!(client
+        .contains("import type { Task, NotFound, ApiError as ApiErrorModel } from './models';"));
+    assert!(client.contains("export class ApiError<E = unknown> extends Error {"));
+    assert!(index.contains("export { ApiError } from './client';"));
+
+    assert!(client.contains(
+        "function decodeNotFound(status: number, body: any): NotFound | undefined {\n  return status === 404 ? body : undefined;"
+    ));
+    assert!(client.contains(
+        "function decodeApiError(status: number, body: any): ApiErrorModel | undefined {\n  return { ...body, statusCode: status };"
+    ));
+    assert!(client.contains("async get(id: string): Promise<Task> {"));
+    assert!(client.contains("errors: [decodeNotFound, decodeApiError]"));
+
+    assert!(server.contains("export class ErrorResponse<E = unknown> extends Error {"));
+    assert!(server.contains("abstract get(id: string): Promise<Task>;"));
+}
+
+#[test]
+fn test_generate_multiple_success_responses() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        model Job {
+            id: string;
+            progress: int32;
+        }
+
+        @error
+        model ApiError {
+            @statusCode statusCode: int32;
+            message: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @post
+            create(@body body: Task): {
+                @statusCode _: 201;
+                @body task: Task;
+            } | {
+                @statusCode _: 202;
+                @body job: Job;
+            } | ApiError;
+
+            @delete
+            @route("/{id}")
+            delete(@path id: string): {
+                @statusCode _: 204;
+            } | {
+                @statusCode _: 202;
+                @body job: Job;
+            } | ApiError;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_ts(source, Side::Both);
+    let models = read_generated(&temp_dir, "models.ts");
+    let client = read_generated(&temp_dir, "client.ts");
+    let server = read_generated(&temp_dir, "server.ts");
+
+    assert!(models.contains(
+        "export type TasksCreateResponse =\n  | { status: 201; body: Task }\n  | { status: 202; body: Job };"
+    ));
+    assert!(models.contains(
+        "export type TasksDeleteResponse =\n  | { status: 204 }\n  | { status: 202; body: Job };"
+    ));
+
+    assert!(client.contains("async create(body: Task): Promise<TasksCreateResponse> {"));
+    assert!(client.contains(
+        "return this.requestResponse('POST', path, { body: body, errors: [decodeApiError] });"
+    ));
+    assert!(server.contains("abstract delete(id: string): Promise<TasksDeleteResponse>;"));
+}
+
+#[test]
+fn test_generate_headers() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @post
+            create(@header("X-Request-ID") requestId: string, @body body: Task, @header ifMatch?: string): {
+                @statusCode _: 201;
+                /** Where the task lives */
+                @header location: string;
+                @header("ETag") etag?: string;
+                @body task: Task;
+            };
+
+            @get
+            @route("/{id}")
+            get(@path id: string, @header("X-Trace") trace?: int32): Task;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_ts(source, Side::Both);
+    let models = read_generated(&temp_dir, "models.ts");
+    let client = read_generated(&temp_dir, "client.ts");
+    let server = read_generated(&temp_dir, "server.ts");
+
+    assert!(client.contains(
+        "async create(requestId: string, body: Task, ifMatch?: string): Promise<WithHeaders<Task, TasksCreateResponseHeaders>> {"
+    ));
+    assert!(client.contains(
+        "return this.requestWithHeaders('POST', path, readTasksCreateResponseHeaders, { body: body, headers: { 'X-Request-ID': requestId, 'if-match': ifMatch } });"
+    ));
+    assert!(client.contains("return this.request('GET', path, { headers: { 'X-Trace': trace } });"));
+    assert!(client.contains("    etag: headers.get('ETag') ?? undefined,"));
+
+    assert!(models.contains("export interface WithHeaders<T, H> {"));
+    assert!(models.contains("export interface TasksCreateResponseHeaders {\n  /** Where the task lives */\n  location: string;\n  etag?: string;\n}"));
+    assert!(server.contains(
+        "import type { Task, WithHeaders, TasksCreateResponseHeaders } from './models';"
+    ));
+}
+
+#[test]
+fn test_generate_named_response_with_headers() {
+    let source = r#"
+        model TaskWithEtag {
+            @header etag: string;
+            id: string;
+            title: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @get
+            @route("/{id}")
+            get(@path id: string): TaskWithEtag;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_ts(source, Side::Both);
+    let client = read_generated(&temp_dir, "client.ts");
+    let server = read_generated(&temp_dir, "server.ts");
+
+    assert!(client.contains(
+        "async get(id: string): Promise<WithHeaders<TaskWithEtag, TasksGetResponseHeaders>> {"
+    ));
+    assert!(server.contains(
+        "return { status: 200, body: withoutMetadata(result.body, ['etag']), headers: writeTasksGetResponseHeaders(result.headers) };"
+    ));
+}
+
+#[test]
+fn test_generate_headers_of_several_responses() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        model Job {
+            id: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @post
+            create(@body body: Task): {
+                @statusCode _: 201;
+                @header location: string;
+                @body task: Task;
+            } | {
+                @statusCode _: 202;
+                @body job: Job;
+            } | {
+                @statusCode _: 204;
+                @header("ETag") etag: string;
+            };
+        }
+    "#;
+
+    let (temp_dir, _) = generate_ts(source, Side::Both);
+    let models = read_generated(&temp_dir, "models.ts");
+    let client = read_generated(&temp_dir, "client.ts");
+    let server = read_generated(&temp_dir, "server.ts");
+
+    // Each response keeps its own headers
+    assert!(models.contains(
+        "export type TasksCreateResponse =\n  | { status: 201; body: Task; headers: TasksCreateCreatedHeaders }\n  | { status: 202; body: Job }\n  | { status: 204; headers: TasksCreateNoContentHeaders };"
+    ));
+    assert!(models.contains("export interface TasksCreateCreatedHeaders {\n  location: string;\n}"));
+    assert!(!models.contains("export interface WithHeaders"));
+    assert!(client.contains(
+        "return this.requestResponse('POST', path, { body: body, responseHeaders: { 201: readTasksCreateCreatedHeaders, 204: readTasksCreateNoContentHeaders } });"
+    ));
+
+    // Servers send them with their response
+    assert!(server.contains("if (result.status === 201) {"));
+    assert!(server.contains(
+        "const headers = writeTasksCreateNoContentHeaders((result as { headers: TasksCreateNoContentHeaders }).headers);"
+    ));
+    assert!(server.contains("return { status: result.status, headers };"));
+}
+
+#[test]
+fn test_generate_headers_of_every_response() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @post
+            create(@body body: Task): {
+                @statusCode _: 201;
+                @header location: string;
+                @body task: Task;
+            } | {
+                @statusCode _: 202;
+                @header("Retry-After") retryAfter: int32;
+            };
+        }
+    "#;
+
+    let (temp_dir, _) = generate_ts(source, Side::Server);
+    let server = read_generated(&temp_dir, "server.ts");
+
+    // The handler returns on every path
+    let create = &server[server.find("if (result.status === 201) {").unwrap()..];
+    let create = &create[..create.find("      },").unwrap()];
+    assert!(create.contains("if (result.status === 202) {"));
+    assert!(create.ends_with(
+        "        throw new Error(`No response declared for status ${(result as { status: number }).status}`);\n"
+    ));
+}
+
 // ============================================================================
 // Enum Generation Tests
 // ============================================================================
@@ -258,6 +676,26 @@ fn test_generate_client_classes() {
     assert!(client.contains("export class Client"));
 }
 
+#[test]
+fn test_generate_client_for_free_operations() {
+    let source = r#"
+        model HealthResponse {
+            status: string;
+        }
+
+        @get
+        @route("/health")
+        op getHealth(): HealthResponse;
+    "#;
+
+    let (temp_dir, _) = generate_ts(source, Side::Client);
+    let client = read_generated(&temp_dir, "client.ts");
+
+    assert!(client.contains("export class DefaultClient"));
+    assert!(client.contains("async getHealth("));
+    assert!(client.contains("readonly default: DefaultClient;"));
+}
+
 #[test]
 fn test_generate_client_methods() {
     let source = r#"
@@ -356,6 +794,28 @@ fn test_generate_main_client() {
     assert!(client.contains("readonly itemService: ItemServiceClient"));
 }
 
+#[test]
+fn test_generate_clients_for_same_named_namespaces() {
+    let source = r#"
+        namespace A.Admin {
+            @route("/a") op x(): void;
+        }
+
+        namespace B.Admin {
+            @route("/b") op y(): void;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_ts(source, Side::Client);
+    let client = read_generated(&temp_dir, "client.ts");
+
+    assert!(client.contains("export class AAdminClient"));
+    assert!(client.contains("export class BAdminClient"));
+    assert!(client.contains("readonly aAdmin: AAdminClient;"));
+    assert!(client.contains("readonly bAdmin: BAdminClient;"));
+    assert!(!client.contains("readonly admin:"));
+}
+
 // ============================================================================
 // Server Generation Tests
 // ============================================================================
@@ -454,3 +914,132 @@ fn test_bytes_type() {
 
     assert!(models.contains("Uint8Array"));
 }
+
+#[test]
+fn test_generate_spread_params() {
+    let source = r#"
+        enum SortOrder {
+            asc,
+            desc,
+        }
+
+        model PaginationParams {
+            @query page?: int32 = 1;
+            @query order?: SortOrder = SortOrder.desc;
+        }
+
+        @route("/items")
+        interface Items {
+            @get
+            list(@query search?: string, ...PaginationParams): string[];
+        }
+    "#;
+
+    let (temp_dir, _) = generate_ts(source, Side::Both);
+    let client = read_generated(&temp_dir, "client.ts");
+    let server = read_generated(&temp_dir, "server.ts");
+
+    assert!(client.contains(
+        "async list(search?: string, page: number = 1, order: SortOrder = SortOrder.Desc): Promise<string[]>"
+    ));
+    assert!(client.contains("query: { search, page, order }"));
+    assert!(server.contains("search?: string, page?: number, order?: SortOrder"));
+}
+
+#[test]
+fn test_generate_implicit_body() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @patch
+            @route("/{id}")
+            update(id: string, @minLength(1) title: string, done?: boolean): Task;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_ts(source, Side::Client);
+    let client = read_generated(&temp_dir, "client.ts");
+
+    assert!(
+        client.contains("async update(id: string, title: string, done?: boolean): Promise<Task>")
+    );
+    assert!(client.contains("const path = `/tasks/${id}`;"));
+    assert!(client.contains("return this.request('PATCH', path, { body: { title, done } });"));
+}
+
+#[test]
+fn test_generate_server_routes() {
+    let source = r#"
+        model Task {
+            @minLength(1)
+            title: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @get list(@query page?: int32 = 1, @header("X-Request-ID") requestId?: string): Task[];
+            @get @route("/{taskId}") get(@path taskId: string): Task;
+            @post create(@body body: Task): { @statusCode _: 201; @body task: Task };
+            @patch @route("/{taskId}") update(taskId: string, title: string, done?: boolean): Task;
+            @delete @route("/{taskId}") remove(@path taskId: string): void;
+        }
+    "#;
+
+    let (temp_dir, _) = generate_ts(source, Side::Server);
+    let server = read_generated(&temp_dir, "server.ts");
+
+    assert!(server.contains("import { ValidationError, validateTask } from './models';"));
+    assert!(server.contains("export function tasksRoutes(handler: TasksHandler): Route[] {"));
+    assert!(server.contains("export function fetchHandler("));
+    assert!(server.contains("export function expressHandler("));
+    assert!(server.contains("      method: 'GET',\n      path: '/tasks/{taskId}',"));
+
+    // Params are read from the request and converted
+    assert!(server.contains(
+        "const page = ((value) => (value === null ? 1 : toNumber(value, 'page')))(req.query.get('page'));"
+    ));
+    assert!(server.contains("const requestId = req.headers.get('X-Request-ID') ?? undefined;"));
+    assert!(server.contains("const taskId = req.params['taskId'];"));
+    assert!(server.contains(
+        "const body = parseBody<Task>(required(await req.json(), 'body'), validateTask);"
+    ));
+    assert!(server.contains("const data = await objectBody(req);"));
+    assert!(server.contains(
+        "const title = parseBody<string>(required(checkJson(data['title'], 'string', 'title'), 'title'));"
+    ));
+    assert!(server.contains(
+        "const done = checkJson(data['done'], 'boolean', 'done') as boolean | undefined;"
+    ));
+
+    // Responses take the status of the operation
+    assert!(server.contains("return { status: 201, body: await handler.create(body) };"));
+    assert!(server.contains("return { status: 204 };"));
+    assert!(server.contains("return { status: err.status, body: err.body };"));
+}
+
+#[test]
+fn test_generate_server_validates_array_bodies() {
+    let source = r#"
+        model SubTask {
+            @minLength(1)
+            title: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @post bulk(@body items: SubTask[]): SubTask[];
+        }
+    "#;
+
+    let (temp_dir, _) = generate_ts(source, Side::Server);
+    let server = read_generated(&temp_dir, "server.ts");
+
+    assert!(server.contains("import { ValidationError, validateSubTask } from './models';"));
+    assert!(server.contains(
+        "const items = parseBody<SubTask[]>(required(await req.json(), 'items'), (items) => items.forEach((item, i) => validateSubTask(item, `[${i}].`)));"
+    ));
+}
@@ -203,6 +203,30 @@ fn test_compile_interface_client() {
     }
 }
 
+#[test]
+fn test_compile_free_operations() {
+    let source = r#"
+        model HealthResponse {
+            status: string;
+        }
+
+        @get
+        @route("/health")
+        op getHealth(): HealthResponse;
+
+        @route("/admin")
+        namespace Admin {
+            @post
+            @route("/reindex/{id}")
+            op reindex(@path id: string): void;
+        }
+    "#;
+
+    if let Err(e) = generate_and_check_rust(source) {
+        panic!("Failed to compile free operations: {}", e);
+    }
+}
+
 #[test]
 fn test_compile_model_with_all_builtin_types() {
     let source = r#"
@@ -251,6 +275,31 @@ fn test_compile_model_with_spread() {
     }
 }
 
+#[test]
+fn test_compile_model_inheritance() {
+    let source = r#"
+        model User {
+            id: string;
+            name?: string;
+        }
+
+        model Admin extends User {
+            permissions: string[];
+        }
+
+        model Page<T> {
+            items: T[];
+            total: int32;
+        }
+
+        model AdminPage is Page<Admin>;
+    "#;
+
+    if let Err(e) = generate_and_check_rust(source) {
+        panic!("Failed to compile model inheritance: {}", e);
+    }
+}
+
 #[test]
 fn test_compile_model_with_string_union() {
     let source = r#"
@@ -364,6 +413,253 @@ fn test_compile_complex_api() {
     }
 }
 
+#[test]
+fn test_compile_validated_models() {
+    let source = r#"
+        model Tag {
+            @minLength(1)
+            @pattern("^[a-z-]+$")
+            name: string;
+        }
+
+        model CreateTaskRequest {
+            @minLength(1)
+            @maxLength(200)
+            title: string;
+            @maxLength(2000)
+            description?: string;
+            @minValue(1)
+            @maxValue(5)
+            priority?: int32;
+            @maxItems(10)
+            tags: Tag[];
+            parent?: Tag;
+            type: string;
+        }
+
+        model Page<T> {
+            @maxItems(100)
+            items: T[];
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @post create(@body body: CreateTaskRequest): Page<Tag>;
+        }
+    "#;
+
+    if let Err(e) = generate_and_check_rust(source) {
+        panic!("Failed to compile validated models: {}", e);
+    }
+}
+
+#[test]
+fn test_compile_error_responses() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        @error
+        model NotFound {
+            @statusCode _: 404;
+            message: string;
+        }
+
+        @error
+        model ApiError {
+            @statusCode statusCode: int32;
+            code: string;
+            message: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @get
+            @route("/{id}")
+            get(@path id: string): {
+                @statusCode _: 200;
+                @body body: Task;
+            } | NotFound | ApiError;
+
+            @delete
+            @route("/{id}")
+            delete(@path id: string): {
+                @statusCode _: 204;
+            } | ApiError;
+
+            @get list(): Task[];
+        }
+    "#;
+
+    if let Err(e) = generate_and_check_rust(source) {
+        panic!("Failed to compile error responses: {}", e);
+    }
+}
+
+#[test]
+fn test_compile_multiple_success_responses() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        model Job {
+            id: string;
+            progress: int32;
+        }
+
+        @error
+        model ApiError {
+            @statusCode statusCode: int32;
+            message: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @post
+            create(@body body: Task): {
+                @statusCode _: 201;
+                @body task: Task;
+            } | {
+                @statusCode _: 202;
+                @body job: Job;
+            } | ApiError;
+
+            @delete
+            @route("/{id}")
+            delete(@path id: string): {
+                @statusCode _: 204;
+            } | {
+                @statusCode _: 202;
+                @body job: Job;
+            } | ApiError;
+        }
+    "#;
+
+    if let Err(e) = generate_and_check_rust(source) {
+        panic!("Failed to compile multiple success responses: {}", e);
+    }
+}
+
+#[test]
+fn test_compile_headers() {
+    let source = r#"
+        model Task {
+            id: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @post
+            create(@header("X-Request-ID") requestId: string, @body body: Task, @header ifMatch?: string): {
+                @statusCode _: 201;
+                /** Where the task lives */
+                @header location: string;
+                @header("ETag") etag?: string;
+                @body task: Task;
+            };
+
+            @get
+            @route("/{id}")
+            get(@path id: string, @header("X-Trace") trace?: int32): Task;
+        }
+    "#;
+
+    if let Err(e) = generate_and_check_rust(source) {
+        panic!("Failed to compile headers: {}", e);
+    }
+}
+
+#[test]
+fn test_compile_spread_params() {
+    let source = r#"
+        enum SortOrder {
+            asc,
+            desc,
+        }
+
+        model PaginationParams {
+            @query page?: int32 = 1;
+            @query order?: SortOrder = SortOrder.desc;
+            @query since?: utcDateTime;
+            @query direction?: "asc" | "desc" = "desc";
+            @header("X-Tenant") tenant?: string = "default";
+        }
+
+        @route("/items")
+        interface Items {
+            @get
+            list(@query search?: string, ...PaginationParams): string[];
+        }
+    "#;
+
+    if let Err(e) = generate_and_check_rust(source) {
+        panic!("Failed to compile spread params: {}", e);
+    }
+}
+
+#[test]
+fn test_compile_implicit_body() {
+    let source = r#"
+        model Task {
+            @minLength(1)
+            id: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @patch
+            @route("/{id}")
+            update(id: string, @maxLength(100) title: string, task?: Task, tags: string[], @minValue(1) priority?: int32 = 1): Task;
+
+            @post
+            create(title: string): Task;
+        }
+    "#;
+
+    if let Err(e) = generate_and_check_rust(source) {
+        panic!("Failed to compile implicit body: {}", e);
+    }
+}
+
+#[test]
+fn test_compile_router() {
+    let source = r#"
+        model Task {
+            @minLength(1)
+            title: string;
+        }
+
+        @error
+        model NotFound {
+            @statusCode _: 404;
+            message: string;
+        }
+
+        model Job {
+            id: string;
+        }
+
+        @route("/tasks")
+        interface Tasks {
+            @get list(@query page?: int32 = 1, @header("X-Request-ID") requestId?: string): Task[];
+            @get @route("/{id}") get(@path id: string): Task | NotFound;
+            @post create(@body body: Task): { @statusCode _: 201; @header location: string; @body task: Task };
+            @put @route("/{id}") replace(@path id: string, @body body?: Task): { @statusCode _: 200; @body task: Task } | { @statusCode _: 202; @body job: Job };
+            @post @route("/{id}/archive") archive(@path id: string): { @statusCode _: 201; @header location: string; @body task: Task } | { @statusCode _: 202; @body job: Job } | { @statusCode _: 204; @header("ETag") etag: string };
+            @patch @route("/{id}") update(id: string, title: string, done?: boolean = false): Task;
+            @delete @route("/{id}") remove(@path id: string): void;
+            @post @route("/{id}/runs/{runId}") run(@path runId: int32, @path id: string, @body tags: string[]): Task;
+            @post @route("/bulk") bulk(@body tasks: Task[], @query dryRun?: boolean): Task[];
+        }
+    "#;
+
+    if let Err(e) = generate_and_check_rust(source) {
+        panic!("Failed to compile router: {}", e);
+    }
+}
+
 // ============================================================================
 // Edge Case Compilation Tests
 // ============================================================================
@@ -384,6 +680,30 @@ fn test_compile_rust_keyword_fields() {
     }
 }
 
+#[test]
+fn test_compile_rust_keyword_params() {
+    let source = r#"
+        model Item {
+            id: string;
+        }
+
+        @route("/items")
+        interface Items {
+            @get list(@query type?: string, @header ref?: string): Item[];
+            @get @route("/{loop}") get(@path loop: string): Item;
+            @post create(type: string, match?: boolean): Item;
+            @put @route("/{loop}") replace(@path loop: string, @body type: Item): Item;
+        }
+    "#;
+
+    if let Err(e) = generate_and_check_rust(source) {
+        panic!(
+            "Failed to compile operations with Rust keyword params: {}",
+            e
+        );
+    }
+}
+
 #[test]
 fn test_compile_empty_model() {
     let source = r#"
@@ -1,6 +1,7 @@
 //! Comprehensive lexer tests for TypeSpec tokenizer
 
-use typespec_api::lexer::{tokenize, Token};
+use typespec_api::lexer::{tokenize, LexError, LexErrorKind, Token};
+use typespec_api::Span;
 
 // ============================================================================
 // Basic Token Tests
@@ -263,6 +264,21 @@ fn test_tokenize_array_types() {
     assert!(matches!(&tokens[2].0, Token::RBracket));
 }
 
+#[test]
+fn test_tokenize_doc_comments() {
+    let input = "/** Single line */ model\n/**\n * First\n *\n * Second\n */\n/* plain */";
+    let tokens = tokenize(input);
+    let token_types: Vec<_> = tokens.iter().map(|(t, _)| t.clone()).collect();
+
+    assert_eq!(token_types.len(), 3);
+    assert_eq!(token_types[0], Token::DocComment("Single line".to_string()));
+    assert!(matches!(token_types[1], Token::Model));
+    assert_eq!(
+        token_types[2],
+        Token::DocComment("First\n\nSecond".to_string())
+    );
+}
+
 // ============================================================================
 // Edge Cases
 // ============================================================================
@@ -298,3 +314,37 @@ fn test_token_spans() {
     assert_eq!(tokens[1].1.start, 6);
     assert_eq!(tokens[1].1.end, 10);
 }
+
+// ============================================================================
+// Error Tokens
+// ============================================================================
+
+#[test]
+fn test_unknown_character_is_error_token() {
+    let input = "model # User";
+    let tokens = tokenize(input);
+
+    assert!(matches!(&tokens[0].0, Token::Model));
+    assert!(matches!(&tokens[1].0, Token::Error(s) if s == "#"));
+    assert_eq!(tokens[1].1, 6..7);
+    assert!(matches!(&tokens[2].0, Token::Ident(s) if s == "User"));
+}
+
+#[test]
+fn test_unterminated_string_is_error_token() {
+    let input = "@doc(\"oops)\nmodel User {}";
+    let tokens = tokenize(input);
+
+    assert!(matches!(&tokens[2].0, Token::Error(s) if s == "\"oops)"));
+    assert!(matches!(&tokens[3].0, Token::Model));
+}
+
+#[test]
+fn test_lex_error_kind() {
+    let err = LexError::from_token("$", Span::new(3, 4));
+    assert_eq!(err.kind, LexErrorKind::UnexpectedCharacter);
+    assert_eq!(err.to_string(), "unexpected character: $");
+
+    let err = LexError::from_token("\"abc", Span::new(0, 4));
+    assert_eq!(err.kind, LexErrorKind::UnterminatedString);
+}
@@ -1,6 +1,6 @@
 //! Comprehensive parser tests for TypeSpec AST generation
 
-use typespec_api::{ast::*, parse};
+use typespec_api::{ast::*, parse, parse_with_diagnostics};
 
 // ============================================================================
 // Model Parsing Tests
@@ -139,6 +139,25 @@ fn test_parse_model_with_extends() {
     assert!(matches!(&model.extends, Some(TypeRef::Named(n)) if n == "User"));
 }
 
+#[test]
+fn test_parse_model_is() {
+    let source = r#"
+        model TaskPage is PaginatedResponse<Task>;
+
+        model ProjectPage is PaginatedResponse<Project> {
+            archived: int32;
+        }
+    "#;
+
+    let file = parse(source).unwrap();
+    let models: Vec<_> = file.models().collect();
+
+    assert!(matches!(&models[0].is, Some(TypeRef::Generic { .. })));
+    assert!(models[0].properties.is_empty());
+    assert!(models[1].is.is_some());
+    assert_eq!(models[1].properties.len(), 1);
+}
+
 // ============================================================================
 // Enum Parsing Tests
 // ============================================================================
@@ -273,6 +292,22 @@ fn test_parse_interface_with_spread_params() {
     assert!(list_op.params[0].spread);
 }
 
+#[test]
+fn test_parse_param_default() {
+    let source = r#"
+        interface ItemService {
+            @get
+            list(@query page?: int32 = 1, @query order?: string = "desc"): Item[];
+        }
+    "#;
+
+    let file = parse(source).unwrap();
+    let op = &file.interfaces().next().unwrap().operations[0];
+
+    assert_eq!(op.params[0].default, Some(Value::Int(1)));
+    assert_eq!(op.params[1].default, Some(Value::String("desc".into())));
+}
+
 // ============================================================================
 // Type Reference Tests
 // ============================================================================
@@ -435,6 +470,74 @@ fn test_parse_qualified_namespace() {
     assert_eq!(file.namespace, Some("MyCompany.MyApi.V1".to_string()));
 }
 
+#[test]
+fn test_parse_nested_namespace_blocks() {
+    let source = r#"
+        namespace Outer {
+            using TypeSpec.Http;
+
+            @format("uuid")
+            scalar uuid extends string;
+
+            union Id { string, int32 }
+
+            alias Tags = string[];
+
+            model Root {
+                id: uuid;
+            }
+
+            namespace Inner {
+                model Leaf {
+                    id: Id;
+                }
+
+                enum Kind { a, b }
+            }
+        }
+    "#;
+
+    let file = parse(source).unwrap();
+    let Declaration::Namespace(outer) = &file.declarations[0] else {
+        panic!("Expected namespace");
+    };
+    assert_eq!(outer.usings[0].namespace, "TypeSpec.Http");
+    assert_eq!(outer.declarations.len(), 5);
+    assert!(
+        matches!(&outer.declarations[4], Declaration::Namespace(inner) if inner.name == "Inner")
+    );
+
+    // Accessors walk into namespace blocks
+    let models: Vec<_> = file.models().map(|m| m.name.as_str()).collect();
+    assert_eq!(models, vec!["Root", "Leaf"]);
+    assert_eq!(file.enums().count(), 1);
+    assert_eq!(file.scalars().count(), 1);
+    assert_eq!(file.unions().count(), 1);
+    assert_eq!(file.aliases().count(), 1);
+}
+
+#[test]
+fn test_namespace_block_recovers_from_errors() {
+    let source = r#"
+        namespace Api {
+            model Broken {
+                id string;
+            }
+
+            import "nope";
+
+            model Fine {
+                id: string;
+            }
+        }
+    "#;
+
+    let (file, diagnostics) = parse_with_diagnostics(source);
+    assert_eq!(diagnostics.len(), 2);
+    let models: Vec<_> = file.models().map(|m| m.name.as_str()).collect();
+    assert_eq!(models, vec!["Broken", "Fine"]);
+}
+
 // ============================================================================
 // Decorator Arguments Tests
 // ============================================================================
@@ -525,6 +628,312 @@ fn test_parse_unclosed_brace_returns_error() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_parse_error_points_at_offending_token() {
+    let source = "model User {\n    id: string;\n    name string;\n}";
+    let err = parse(source).unwrap_err();
+
+    let span = err.span();
+    assert_eq!(&source[span.start..span.end], "string");
+
+    let rendered = err.render("user.tsp", source);
+    assert!(rendered.contains("user.tsp:3:10"));
+    assert!(rendered.contains("3 |     name string;"));
+    assert!(rendered.contains("  |          ^^^^^^"));
+}
+
+#[test]
+fn test_parse_error_at_end_of_input() {
+    let source = "model User {\n    id: string;\n";
+    let err = parse(source).unwrap_err();
+    assert!(err.render("user.tsp", source).contains("user.tsp:2:16"));
+}
+
+#[test]
+fn test_parse_reports_lex_error_location() {
+    let source = "model User {\n    id: string;\n    $name: string;\n}";
+    let err = parse(source).unwrap_err();
+
+    assert!(matches!(err, typespec_api::parser::ParseError::Lex(_)));
+    let rendered = err.render("user.tsp", source);
+    assert!(rendered.contains("unexpected character: $"));
+    assert!(rendered.contains("user.tsp:3:5"));
+}
+
+#[test]
+fn test_parse_reports_unterminated_string() {
+    let source = "@doc(\"User model)\nmodel User {}";
+    let err = parse(source).unwrap_err();
+    assert!(err.to_string().contains("unterminated string literal"));
+    assert_eq!(err.span().start, 5);
+}
+
+// ============================================================================
+// Operation Declaration Tests
+// ============================================================================
+
+#[test]
+fn test_parse_top_level_op() {
+    let source = r#"
+        model HealthResponse {
+            status: string;
+        }
+
+        @get
+        @route("/health")
+        op getHealth(): HealthResponse;
+    "#;
+
+    let file = parse(source).unwrap();
+    let ops: Vec<_> = file.operations().collect();
+    assert_eq!(ops.len(), 1);
+    assert_eq!(ops[0].name, "getHealth");
+    assert!(ops[0].decorators.iter().any(|d| d.name == "get"));
+    assert!(matches!(&ops[0].return_type, Some(TypeRef::Named(n)) if n == "HealthResponse"));
+    assert!(source[ops[0].span.start..ops[0].span.end].starts_with("op getHealth"));
+}
+
+#[test]
+fn test_parse_namespaced_op() {
+    let source = r#"
+        namespace Admin {
+            @post
+            op reindex(@query force?: boolean): void;
+        }
+    "#;
+
+    let file = parse(source).unwrap();
+    let Declaration::Namespace(ns) = &file.declarations[0] else {
+        panic!("Expected namespace");
+    };
+    let Declaration::Operation(op) = &ns.declarations[0] else {
+        panic!("Expected operation");
+    };
+    assert_eq!(op.name, "reindex");
+    assert!(op.params[0].optional);
+}
+
+#[test]
+fn test_parse_interface_op_keyword() {
+    let source = r#"
+        interface UserService {
+            @get op list(): string[];
+            @delete remove(@path id: string): void;
+        }
+    "#;
+
+    let file = parse(source).unwrap();
+    let iface = file.interfaces().next().unwrap();
+    let names: Vec<_> = iface.operations.iter().map(|o| o.name.as_str()).collect();
+    assert_eq!(names, vec!["list", "remove"]);
+}
+
+#[test]
+fn test_parse_operation_template_and_is() {
+    let source = r#"
+        @get
+        @route("/{id}")
+        op ReadResource<T>(@path id: string): T;
+
+        interface UserService {
+            get is ReadResource<User>;
+        }
+    "#;
+
+    let file = parse(source).unwrap();
+    let template = file.operations().next().unwrap();
+    assert_eq!(template.type_params, vec!["T".to_string()]);
+    assert!(template.is_template());
+
+    let get = &file.interfaces().next().unwrap().operations[0];
+    assert_eq!(get.name, "get");
+    assert!(get.params.is_empty());
+    assert!(matches!(
+        &get.is,
+        Some(TypeRef::Generic { base, args })
+            if matches!(base.as_ref(), TypeRef::Named(n) if n == "ReadResource") && args.len() == 1
+    ));
+}
+
+// ============================================================================
+// Doc Comment Tests
+// ============================================================================
+
+fn doc_of(decorators: &[Decorator]) -> Option<&str> {
+    decorators
+        .iter()
+        .find(|d| d.name == "doc")
+        .and_then(|d| d.get_string_arg(0))
+}
+
+#[test]
+fn test_parse_doc_comments_as_doc_decorators() {
+    let source = r#"
+        /** A task */
+        @doc("Explicit wins")
+        model Task {
+            /** Unique identifier */
+            @key
+            id: string;
+            // not a doc comment
+            name: string;
+        }
+
+        /** Task operations */
+        interface Tasks {
+            /** List tasks */
+            @get list(/** Page size */ @query limit?: int32): Task[];
+        }
+    "#;
+
+    let file = parse(source).unwrap();
+    let model = file.models().next().unwrap();
+    assert_eq!(doc_of(&model.decorators), Some("Explicit wins"));
+    assert_eq!(
+        model.decorators.iter().filter(|d| d.name == "doc").count(),
+        1
+    );
+    assert_eq!(
+        doc_of(&model.properties[0].decorators),
+        Some("Unique identifier")
+    );
+    assert_eq!(doc_of(&model.properties[1].decorators), None);
+
+    let iface = file.interfaces().next().unwrap();
+    assert_eq!(doc_of(&iface.decorators), Some("Task operations"));
+    let op = &iface.operations[0];
+    assert_eq!(doc_of(&op.decorators), Some("List tasks"));
+    assert_eq!(doc_of(&op.params[0].decorators), Some("Page size"));
+}
+
+// ============================================================================
+// Error Recovery Tests
+// ============================================================================
+
+#[test]
+fn test_recovery_reports_every_error() {
+    let source = r#"
+        model User {
+            id string;
+            name: string;
+        }
+
+        enum Status {
+            active,
+            : "x",
+            inactive,
+        }
+
+        interface UserService {
+            get(@path id string): User;
+            list(): User[];
+        }
+
+        model Tag {
+            name: string;
+        }
+    "#;
+
+    let (file, diagnostics) = parse_with_diagnostics(source);
+    assert_eq!(diagnostics.len(), 3);
+    assert!(diagnostics.iter().all(|d| d.is_error()));
+    assert!(diagnostics
+        .windows(2)
+        .all(|w| w[0].span.start < w[1].span.start));
+
+    // Everything around the broken members is still parsed
+    let user = file.models().find(|m| m.name == "User").unwrap();
+    assert_eq!(user.properties.len(), 1);
+    assert_eq!(user.properties[0].name, "name");
+
+    let status = file.enums().next().unwrap();
+    let members: Vec<_> = status.members.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(members, vec!["active", "inactive"]);
+
+    let service = file.interfaces().next().unwrap();
+    assert_eq!(service.operations.len(), 1);
+    assert_eq!(service.operations[0].name, "list");
+
+    assert!(file.models().any(|m| m.name == "Tag"));
+}
+
+#[test]
+fn test_recovery_skips_broken_declaration() {
+    let source = r#"
+        scalar 42;
+        model Good {
+            id: string;
+        }
+    "#;
+
+    let (file, diagnostics) = parse_with_diagnostics(source);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(file.models().count(), 1);
+}
+
+#[test]
+fn test_recovery_includes_lex_errors() {
+    let source = "model A {
+  id: string $;
+  name: string;
+}
+model B { x }";
+    let (file, diagnostics) = parse_with_diagnostics(source);
+
+    assert!(diagnostics[0].message.contains("unexpected character: $"));
+    assert!(diagnostics.len() >= 2);
+    assert_eq!(file.models().count(), 2);
+
+    // `parse` still fails with the earliest error
+    let err = parse(source).unwrap_err();
+    assert_eq!(err.span(), diagnostics[0].span);
+}
+
+// ============================================================================
+// Span Tests
+// ============================================================================
+
+#[test]
+fn test_declaration_spans() {
+    let source = r#"
+        @doc("A user")
+        model User {
+            @key id: string;
+            name?: string;
+        }
+
+        @route("/users")
+        interface UserService {
+            @get get(@path id: string): User;
+        }
+    "#;
+
+    let file = parse(source).unwrap();
+
+    let model = file.models().next().unwrap();
+    assert!(source[model.span.start..model.span.end].starts_with("model User {"));
+    assert!(source[model.span.start..model.span.end].ends_with('}'));
+    assert_eq!(
+        &source[model.decorators[0].span.start..model.decorators[0].span.end],
+        r#"@doc("A user")"#
+    );
+
+    let id = &model.properties[0];
+    assert_eq!(&source[id.span.start..id.span.end], "@key id: string");
+    let name = &model.properties[1];
+    assert_eq!(&source[name.span.start..name.span.end], "name?: string");
+
+    let op = &file.interfaces().next().unwrap().operations[0];
+    assert_eq!(
+        &source[op.span.start..op.span.end],
+        "@get get(@path id: string): User"
+    );
+    assert_eq!(
+        &source[op.params[0].span.start..op.params[0].span.end],
+        "@path id: string"
+    );
+}
+
 // ============================================================================
 // Complex Integration Tests
 // ============================================================================
@@ -0,0 +1,294 @@
+//! Program loader tests
+
+use std::path::Path;
+use tempfile::TempDir;
+use typespec_api::codegen::CodegenError;
+use typespec_api::{
+    parse, Declaration, FileSystem, Generator, InMemory, Language, Overlay, Program, Severity, Side,
+};
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn write_files(files: &[(&str, &str)]) -> TempDir {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    for (name, source) in files {
+        let path = temp_dir.path().join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).expect("Failed to write file");
+    }
+    temp_dir
+}
+
+fn file_name(path: &Path) -> &str {
+    path.file_name().unwrap().to_str().unwrap()
+}
+
+// ============================================================================
+// Import Resolution Tests
+// ============================================================================
+
+#[test]
+fn test_load_follows_relative_imports() {
+    let dir = write_files(&[
+        (
+            "main.tsp",
+            r#"
+            import "@typespec/http";
+            import "./models/task";
+
+            namespace Api;
+
+            model Page { items: Models.Task[]; }
+            "#,
+        ),
+        (
+            "models/task.tsp",
+            r#"
+            import "../common.tsp";
+
+            namespace Api.Models;
+
+            model Task { id: Id; }
+            "#,
+        ),
+        ("common.tsp", "scalar Id extends string;"),
+    ]);
+
+    let program = Program::load(&[dir.path().join("main.tsp")]).unwrap();
+
+    assert!(program.diagnostics().is_empty());
+    let files: Vec<_> = program.files().iter().map(|f| file_name(&f.path)).collect();
+    assert_eq!(files, vec!["main.tsp", "task.tsp", "common.tsp"]);
+
+    let models: Vec<_> = program.file().models().map(|m| m.name.as_str()).collect();
+    assert_eq!(models, vec!["Page", "Task"]);
+    assert_eq!(program.file().scalars().count(), 1);
+}
+
+#[test]
+fn test_load_shared_import_once() {
+    let dir = write_files(&[
+        ("a.tsp", "import \"./common.tsp\";\nmodel A { c: C; }"),
+        ("b.tsp", "import \"./common.tsp\";\nmodel B { c: C; }"),
+        ("common.tsp", "model C {}"),
+    ]);
+
+    let program = Program::load(&[dir.path().join("a.tsp"), dir.path().join("b.tsp")]).unwrap();
+
+    assert!(program.diagnostics().is_empty());
+    assert_eq!(program.files().len(), 3);
+    assert_eq!(program.file().models().count(), 3);
+}
+
+#[test]
+fn test_load_reports_import_cycle() {
+    let dir = write_files(&[
+        ("a.tsp", "import \"./b.tsp\";\nmodel A {}"),
+        ("b.tsp", "import \"./a.tsp\";\nmodel B {}"),
+    ]);
+
+    let program = Program::load(&[dir.path().join("a.tsp")]).unwrap();
+
+    assert!(!program.has_errors());
+    let [warning] = program.diagnostics() else {
+        panic!("Expected one diagnostic");
+    };
+    assert_eq!(warning.severity, Severity::Warning);
+    assert_eq!(warning.message, "import cycle: a.tsp -> b.tsp -> a.tsp");
+    assert_eq!(program.file().models().count(), 2);
+}
+
+#[test]
+fn test_load_reports_missing_import() {
+    let dir = write_files(&[("main.tsp", "import \"./missing.tsp\";\nmodel A {}")]);
+
+    let program = Program::load(&[dir.path().join("main.tsp")]).unwrap();
+
+    assert!(program.has_errors());
+    assert_eq!(
+        program.diagnostics()[0].message,
+        "cannot find import \"./missing.tsp\""
+    );
+}
+
+#[test]
+fn test_load_missing_entry_fails() {
+    let dir = TempDir::new().unwrap();
+    assert!(Program::load(&[dir.path().join("main.tsp")]).is_err());
+}
+
+// ============================================================================
+// Provenance Tests
+// ============================================================================
+
+#[test]
+fn test_declarations_know_their_file() {
+    let dir = write_files(&[
+        ("main.tsp", "import \"./other.tsp\";\nmodel First {}"),
+        ("other.tsp", "\n\nmodel Second {}"),
+    ]);
+
+    let program = Program::load(&[dir.path().join("main.tsp")]).unwrap();
+
+    for model in program.file().models() {
+        let file = program.source_of(model.span).unwrap();
+        let text = &file.source[model.span.start - file.offset..model.span.end - file.offset];
+        assert!(text.contains(&model.name));
+    }
+    let second = program.file().models().nth(1).unwrap();
+    assert_eq!(
+        file_name(&program.source_of(second.span).unwrap().path),
+        "other.tsp"
+    );
+}
+
+#[test]
+fn test_diagnostics_rendered_against_their_file() {
+    let dir = write_files(&[
+        ("main.tsp", "import \"./tasks.tsp\";\nmodel Ok {}"),
+        (
+            "tasks.tsp",
+            "namespace Tasks;\n\nmodel Task {\n  status: TaskStatsu;\n}",
+        ),
+    ]);
+
+    let program = Program::load(&[dir.path().join("main.tsp")]).unwrap();
+
+    assert!(program.has_errors());
+    let rendered = program.render_diagnostics();
+    assert!(rendered.contains("error: unknown type `TaskStatsu`"));
+    assert!(rendered.contains("tasks.tsp:4:3"));
+    assert!(rendered.contains("status: TaskStatsu;"));
+}
+
+#[test]
+fn test_file_namespaces_kept_when_merged() {
+    let dir = write_files(&[
+        (
+            "main.tsp",
+            "import \"./tasks.tsp\";\nimport \"./agents.tsp\";\nnamespace Api;\nmodel Run { a: Tasks.Status; b: Agents.Status; }",
+        ),
+        ("tasks.tsp", "namespace Api.Tasks;\nmodel Status {}"),
+        ("agents.tsp", "namespace Api.Agents;\nmodel Status {}"),
+    ]);
+
+    let program = Program::load(&[dir.path().join("main.tsp")]).unwrap();
+
+    assert!(program.diagnostics().is_empty());
+    let namespaces: Vec<_> = program
+        .file()
+        .declarations
+        .iter()
+        .map(|d| match d {
+            Declaration::Namespace(ns) => ns.name.as_str(),
+            _ => panic!("Expected namespace"),
+        })
+        .collect();
+    assert_eq!(namespaces, vec!["Api", "Api.Tasks", "Api.Agents"]);
+}
+
+#[test]
+fn test_generation_errors_point_into_their_file() {
+    let dir = write_files(&[
+        (
+            "main.tsp",
+            "import \"./tasks.tsp\";\nimport \"./agents.tsp\";",
+        ),
+        ("tasks.tsp", "namespace Tasks;\nmodel Status {}"),
+        (
+            "agents.tsp",
+            "namespace Agents;\nmodel Status {}\nmodel TasksStatus {}",
+        ),
+    ]);
+    let out = TempDir::new().unwrap();
+
+    let program = Program::load(&[dir.path().join("main.tsp")]).unwrap();
+    let result =
+        Generator::from_program(&program, out.path(), "api").generate(Language::Rust, Side::Both);
+
+    let Err(CodegenError::Diagnostics(diagnostics)) = result else {
+        panic!("Expected diagnostics");
+    };
+    assert_eq!(diagnostics.len(), 1);
+    let rendered = program.render(&diagnostics[0]);
+    assert!(rendered.contains(
+        "`Tasks.Status` and `Agents.TasksStatus` would both be generated as `TasksStatus`"
+    ));
+    assert!(rendered.contains("agents.tsp:3:1"));
+}
+
+#[test]
+fn test_free_operations_named_alike_when_parsed_or_loaded() {
+    let source = "namespace Api;\n@route(\"/health\") op health(): void;";
+
+    let file = parse(source).unwrap();
+    let parsed_out = TempDir::new().unwrap();
+    Generator::new(&file, parsed_out.path(), "api")
+        .generate(Language::TypeScript, Side::Client)
+        .unwrap();
+
+    let sources = InMemory::new().with_file("main.tsp", source);
+    let program = Program::load_with(&["main.tsp"], &sources).unwrap();
+    let loaded_out = TempDir::new().unwrap();
+    Generator::from_program(&program, loaded_out.path(), "api")
+        .generate(Language::TypeScript, Side::Client)
+        .unwrap();
+
+    let parsed = std::fs::read_to_string(parsed_out.path().join("client.ts")).unwrap();
+    let loaded = std::fs::read_to_string(loaded_out.path().join("client.ts")).unwrap();
+    assert!(parsed.contains("export class ApiClient"));
+    assert!(parsed.contains("readonly api: ApiClient;"));
+    assert_eq!(parsed, loaded);
+}
+
+// ============================================================================
+// Source Provider Tests
+// ============================================================================
+
+#[test]
+fn test_load_from_memory() {
+    let sources = InMemory::new()
+        .with_file(
+            "api/main.tsp",
+            "import \"../shared/common\";\nmodel Task { id: Id; }",
+        )
+        .with_file("shared/common.tsp", "scalar Id extends string;");
+
+    let program = Program::load_with(&["api/main.tsp"], &sources).unwrap();
+
+    assert!(program.diagnostics().is_empty());
+    let files: Vec<_> = program.files().iter().map(|f| f.path.clone()).collect();
+    assert_eq!(
+        files,
+        vec![Path::new("api/main.tsp"), Path::new("shared/common.tsp")]
+    );
+}
+
+#[test]
+fn test_overlay_shadows_files_on_disk() {
+    let dir = write_files(&[
+        (
+            "main.tsp",
+            "import \"./task.tsp\";\nmodel Page { items: Task[]; }",
+        ),
+        ("task.tsp", "model Task { id: string; }"),
+    ]);
+    let root = dir.path().canonicalize().unwrap();
+
+    // Unsaved edit of task.tsp, and a buffer that was never written to disk
+    let sources = Overlay::new(FileSystem)
+        .with_file(
+            root.join("task.tsp"),
+            "import \"./draft.tsp\";\nmodel Task { id: string; draft: Draft; }",
+        )
+        .with_file(root.join("draft.tsp"), "model Draft {}");
+
+    let program = Program::load_with(&[root.join("main.tsp")], &sources).unwrap();
+
+    assert!(program.diagnostics().is_empty());
+    let task = program.file().models().find(|m| m.name == "Task").unwrap();
+    assert_eq!(task.properties.len(), 2);
+    assert!(program.file().models().any(|m| m.name == "Draft"));
+}